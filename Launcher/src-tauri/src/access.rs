@@ -0,0 +1,456 @@
+use std::{
+    env, fs as stdfs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process,
+};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tokio::fs as tokio_fs;
+
+use crate::{
+    disk,
+    error::{self, LauncherError},
+    installs, load_env, log_line, logs_dir,
+    operation::{self, Operation},
+    silly_dir, state, temp, userdata, vendor_dir, ServerState,
+};
+
+const DEFAULT_VENDOR_DIR: &str = "./vendor/WeylandTavern";
+const RELOCATION_SKIPPED: &[&str] = &["node_modules"];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PathAccess {
+    label: &'static str,
+    path: String,
+    writable: bool,
+    reason: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WriteAccessReport {
+    paths: Vec<PathAccess>,
+    suggested_vendor_dir: Option<String>,
+}
+
+fn vendor_root() -> PathBuf {
+    vendor_dir().unwrap_or_else(|_| {
+        PathBuf::from(env::var("WEYLANDTAVERN_DIR").unwrap_or_else(|_| DEFAULT_VENDOR_DIR.into()))
+    })
+}
+
+fn targets() -> Vec<(&'static str, PathBuf)> {
+    let mut targets = vec![
+        ("Launcher folder", PathBuf::from(".")),
+        ("Launcher logs", logs_dir()),
+        ("Launcher state", state::state_dir()),
+        ("Launcher temp", temp::root()),
+        ("WeylandTavern folder", vendor_root()),
+    ];
+    if let Ok(silly) = silly_dir() {
+        targets.push(("SillyTavern folder", silly.clone()));
+        targets.push(("SillyTavern data", userdata::dir(&silly)));
+        let node_modules = silly.join("node_modules");
+        if node_modules.exists() {
+            targets.push(("node_modules", node_modules));
+        }
+    }
+    targets
+}
+
+fn probe(path: &Path) -> Result<(), String> {
+    let dir = path
+        .ancestors()
+        .find(|candidate| !candidate.as_os_str().is_empty() && candidate.is_dir())
+        .unwrap_or(Path::new("."));
+    let marker = dir.join(format!(".weylandtavern-write-test-{}", process::id()));
+    let result = stdfs::write(&marker, b"ok");
+    let _ = stdfs::remove_file(&marker);
+    result.map_err(|err| match err.kind() {
+        ErrorKind::PermissionDenied => "permission denied".to_string(),
+        ErrorKind::ReadOnlyFilesystem => "the file system is mounted read-only".to_string(),
+        _ => err.to_string(),
+    })
+}
+
+pub(crate) fn check() -> Vec<PathAccess> {
+    targets()
+        .into_iter()
+        .map(|(label, path)| {
+            let result = probe(&path);
+            PathAccess {
+                label,
+                path: path.display().to_string(),
+                writable: result.is_ok(),
+                reason: result.err(),
+            }
+        })
+        .collect()
+}
+
+impl PathAccess {
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub(crate) fn message(&self) -> String {
+        format!(
+            "{} at {} is not writable ({}). Fix its permissions or move WeylandTavern to a folder your user owns.",
+            self.label,
+            self.path,
+            self.reason.as_deref().unwrap_or("unknown error")
+        )
+    }
+}
+
+pub(crate) fn blocked() -> Option<PathAccess> {
+    check().into_iter().find(|entry| !entry.writable)
+}
+
+fn suggested_vendor_dir(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_local_data_dir()
+        .ok()
+        .map(|dir| dir.join("vendor").join("WeylandTavern"))
+}
+
+#[tauri::command]
+pub(crate) async fn check_write_access(app: AppHandle) -> Result<WriteAccessReport, LauncherError> {
+    load_env();
+    Ok(WriteAccessReport {
+        paths: check(),
+        suggested_vendor_dir: suggested_vendor_dir(&app).map(|dir| dir.display().to_string()),
+    })
+}
+
+#[cfg(windows)]
+async fn grant_access(path: &Path) -> Result<std::process::Output, String> {
+    let user = match (env::var("USERDOMAIN"), env::var("USERNAME")) {
+        (Ok(domain), Ok(user)) => format!("{domain}\\{user}"),
+        (_, Ok(user)) => user,
+        _ => return Err("Unable to determine the current user name.".into()),
+    };
+    let icacls_args = format!(
+        "\"{}\" /grant \"{user}:(OI)(CI)M\" /T /C /Q",
+        path.display()
+    );
+    let script = format!(
+        "$p = Start-Process -FilePath icacls -ArgumentList '{}' -Verb RunAs -WindowStyle Hidden -Wait -PassThru; exit $p.ExitCode",
+        icacls_args.replace('\'', "''")
+    );
+    tokio::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to start PowerShell: {e}"))
+}
+
+#[cfg(unix)]
+async fn grant_access(path: &Path) -> Result<std::process::Output, String> {
+    let owner = unsafe { format!("{}:{}", libc::getuid(), libc::getgid()) };
+    let path = path.display().to_string();
+    let mut command = if cfg!(target_os = "macos") {
+        let script = format!(
+            "do shell script \"chown -R {owner} \" & quoted form of \"{}\" with administrator privileges",
+            path.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        let mut command = tokio::process::Command::new("osascript");
+        command.args(["-e", &script]);
+        command
+    } else {
+        let mut command = tokio::process::Command::new("pkexec");
+        command.args(["chown", "-R", &owner, &path]);
+        command
+    };
+    command
+        .output()
+        .await
+        .map_err(|e| format!("Failed to request administrator rights: {e}"))
+}
+
+#[tauri::command]
+pub(crate) async fn fix_path_permissions(
+    app: AppHandle,
+    path: String,
+) -> Result<String, LauncherError> {
+    load_env();
+    let target = PathBuf::from(&path);
+    if !targets().iter().any(|(_, candidate)| *candidate == target) {
+        return Err(format!("{path} is not one of the launcher's folders.").into());
+    }
+    let target = target
+        .ancestors()
+        .find(|candidate| !candidate.as_os_str().is_empty() && candidate.is_dir())
+        .unwrap_or(Path::new("."))
+        .to_path_buf();
+    log_line(
+        &app,
+        &format!(
+            "Requesting administrator rights to give your user write access to {}...",
+            target.display()
+        ),
+    )
+    .await;
+    let output = grant_access(&target).await?;
+    if !output.status.success() || probe(&target).is_err() {
+        let details = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let message = if details.is_empty() {
+            format!(
+                "{} is still not writable. The administrator prompt may have been declined.",
+                target.display()
+            )
+        } else {
+            format!(
+                "Fixing permissions on {} failed: {details}",
+                target.display()
+            )
+        };
+        log_line(&app, &message).await;
+        return Err(message.into());
+    }
+    let message = format!("{} is writable now.", target.display());
+    log_line(&app, &message).await;
+    Ok(message)
+}
+
+pub(crate) fn env_file() -> PathBuf {
+    ["../.env", ".env"]
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.exists())
+        .unwrap_or_else(|| PathBuf::from(".env"))
+}
+
+fn env_line(name: &str, value: &str) -> String {
+    let plain = !value
+        .chars()
+        .any(|c| c.is_whitespace() || matches!(c, '#' | '\'' | '"' | '\\' | '$'));
+    if plain {
+        format!("{name}={value}")
+    } else if !value.contains('\'') {
+        format!("{name}='{value}'")
+    } else {
+        let escaped = value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('$', "\\$");
+        format!("{name}=\"{escaped}\"")
+    }
+}
+
+pub(crate) fn set_env_values(path: &Path, values: &[(&str, String)]) -> Result<(), String> {
+    let existing = stdfs::read_to_string(path).unwrap_or_default();
+    let mut pending: Vec<&(&str, String)> = values.iter().collect();
+    let mut lines: Vec<String> = existing
+        .lines()
+        .map(|line| {
+            let key = line.split('=').next().unwrap_or_default().trim();
+            match pending.iter().position(|(name, _)| *name == key) {
+                Some(index) => {
+                    let (name, value) = pending.remove(index);
+                    env_line(name, value)
+                }
+                None => line.to_string(),
+            }
+        })
+        .collect();
+    lines.extend(pending.iter().map(|(name, value)| env_line(name, value)));
+    stdfs::write(path, lines.join("\n") + "\n")
+        .map_err(|e| format!("Failed to update {}: {e}", path.display()))
+}
+
+pub(crate) async fn copy_tree(source: &Path, target: &Path) -> Result<u64, String> {
+    let mut copied = 0;
+    let mut pending = vec![source.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let destination = target.join(dir.strip_prefix(source).unwrap_or(&dir));
+        tokio_fs::create_dir_all(&destination)
+            .await
+            .map_err(|e| format!("Failed to create {}: {e}", destination.display()))?;
+        let mut entries = tokio_fs::read_dir(&dir)
+            .await
+            .map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let Ok(kind) = entry.file_type().await else {
+                continue;
+            };
+            let skipped = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| RELOCATION_SKIPPED.contains(&name))
+                .unwrap_or(false);
+            if kind.is_dir() && !skipped {
+                pending.push(path);
+            } else if kind.is_file() {
+                tokio_fs::copy(&path, destination.join(entry.file_name()))
+                    .await
+                    .map_err(|e| format!("Failed to copy {}: {e}", path.display()))?;
+                copied += 1;
+            }
+        }
+    }
+    Ok(copied)
+}
+
+async fn relocation_bytes(source: &Path) -> u64 {
+    let mut bytes = 0;
+    let mut pending = vec![source.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(mut entries) = tokio_fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let skipped = entry
+                .file_name()
+                .to_str()
+                .map(|name| RELOCATION_SKIPPED.contains(&name))
+                .unwrap_or(false);
+            match entry.metadata().await {
+                Ok(meta) if meta.is_dir() && !skipped => pending.push(entry.path()),
+                Ok(meta) if meta.is_file() => bytes += meta.len(),
+                _ => {}
+            }
+        }
+    }
+    bytes
+}
+
+#[tauri::command]
+pub(crate) async fn relocate_vendor(
+    app: AppHandle,
+    target: Option<String>,
+) -> Result<String, LauncherError> {
+    let _operation = operation::begin(&app, Operation::VendorRelocate)?;
+    load_env();
+    installs::ensure_stopped(app.state::<ServerState>().inner(), "moving WeylandTavern")?;
+    let silly = silly_dir()?;
+    let source = vendor_root();
+    let target = target
+        .map(PathBuf::from)
+        .or_else(|| suggested_vendor_dir(&app))
+        .ok_or("Unable to determine a folder to move WeylandTavern to.")?;
+    if target.exists()
+        && stdfs::read_dir(&target)
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(false)
+    {
+        return Err(format!("{} already exists and is not empty.", target.display()).into());
+    }
+    if let Err(reason) = probe(&target) {
+        return Err(format!("{} is not writable either ({reason}).", target.display()).into());
+    }
+    let registered = installs::active();
+    let env_path = env_file();
+    let env_writable = if registered.is_some() {
+        Ok(())
+    } else if env_path.exists() {
+        stdfs::OpenOptions::new()
+            .append(true)
+            .open(&env_path)
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    } else {
+        probe(&env_path)
+    };
+    if let Err(reason) = env_writable {
+        return Err(format!(
+            "{} cannot be updated ({reason}), so the new location would not be remembered. Move the whole launcher folder instead.",
+            env_path.display()
+        )
+        .into());
+    }
+
+    let bytes = relocation_bytes(&source).await;
+    if let Ok(available) = disk::available_bytes(&target) {
+        if available < bytes {
+            return Err(LauncherError::new(
+                error::ErrorKind::InsufficientDisk,
+                format!(
+                    "Not enough free space in {} to move {} MB of WeylandTavern.",
+                    target.display(),
+                    bytes.div_ceil(1024 * 1024)
+                ),
+            ));
+        }
+    }
+
+    log_line(
+        &app,
+        &format!(
+            "Copying {} to {} (node_modules will be reinstalled)...",
+            source.display(),
+            target.display()
+        ),
+    )
+    .await;
+    let copied = copy_tree(&source, &target).await?;
+    let new_silly = target.join(
+        silly
+            .strip_prefix(&source)
+            .unwrap_or(Path::new("SillyTavern")),
+    );
+    if registered.is_some() {
+        installs::relocate_active(&new_silly, &target)?;
+    } else {
+        let values = [
+            ("WEYLANDTAVERN_DIR", target.display().to_string()),
+            ("SILLYTAVERN_DIR", new_silly.display().to_string()),
+        ];
+        set_env_values(&env_path, &values)?;
+        for (key, value) in &values {
+            env::set_var(key, value);
+        }
+    }
+    let message = format!(
+        "Moved WeylandTavern to {} ({copied} files). The old copy at {} can be deleted.",
+        target.display(),
+        source.display()
+    );
+    log_line(&app, &message).await;
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_line_quotes_only_when_needed() {
+        assert_eq!(env_line("PORT", "8000"), "PORT=8000");
+        assert_eq!(
+            env_line("ARGS", "--listen --port 8000"),
+            "ARGS='--listen --port 8000'"
+        );
+        assert_eq!(env_line("DIR", "C:\\Games\\ST"), "DIR='C:\\Games\\ST'");
+        assert_eq!(
+            env_line("MOTD", "it's $5 \"now\""),
+            "MOTD=\"it's \\$5 \\\"now\\\"\""
+        );
+    }
+
+    #[test]
+    fn env_line_round_trips_through_dotenvy() {
+        for value in [
+            "plain",
+            "two words",
+            "a#b",
+            "$HOME/x",
+            "C:\\Users\\me",
+            "say \"hi\" it's $5",
+        ] {
+            let line = env_line("KEY", value);
+            let parsed: Vec<(String, String)> = dotenvy::from_read_iter(line.as_bytes())
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(
+                parsed,
+                vec![("KEY".to_string(), value.to_string())],
+                "{line}"
+            );
+        }
+    }
+}
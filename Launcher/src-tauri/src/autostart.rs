@@ -0,0 +1,235 @@
+#[cfg(not(windows))]
+use std::path::Path;
+use std::{env, path::PathBuf};
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::LauncherError;
+use crate::log_line;
+
+pub(crate) const START_MINIMIZED_FLAG: &str = "--start-minimized";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AutostartStatus {
+    enabled: bool,
+    location: Option<String>,
+    command: Option<String>,
+}
+
+struct Target {
+    #[cfg_attr(windows, allow(dead_code))]
+    app_id: String,
+    name: String,
+    exe: PathBuf,
+    working_dir: PathBuf,
+}
+
+impl Target {
+    fn resolve(app: &AppHandle) -> Result<Self, String> {
+        Ok(Self {
+            app_id: app.config().identifier.clone(),
+            name: app
+                .config()
+                .product_name
+                .clone()
+                .unwrap_or_else(|| "WeylandTavern".into()),
+            exe: env::current_exe().map_err(|e| e.to_string())?,
+            working_dir: env::current_dir().map_err(|e| e.to_string())?,
+        })
+    }
+}
+
+#[cfg(windows)]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+#[cfg(windows)]
+fn command_line(target: &Target) -> String {
+    format!(
+        "\"{}\" {START_MINIMIZED_FLAG} --working-dir \"{}\"",
+        target.exe.display(),
+        target.working_dir.display()
+    )
+}
+
+#[cfg(windows)]
+async fn reg(args: &[&str]) -> Result<std::process::Output, String> {
+    tokio::process::Command::new("reg")
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run reg.exe: {e}"))
+}
+
+#[cfg(windows)]
+async fn read_entry(target: &Target) -> Option<(String, String)> {
+    let output = reg(&["query", RUN_KEY, "/v", &target.name]).await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let command = stdout
+        .lines()
+        .find_map(|line| line.split_once("REG_SZ"))
+        .map(|(_, value)| value.trim().to_string())?;
+    Some((format!("{RUN_KEY}\\{}", target.name), command))
+}
+
+#[cfg(windows)]
+async fn write_entry(target: &Target) -> Result<String, String> {
+    let command = command_line(target);
+    let output = reg(&[
+        "add",
+        RUN_KEY,
+        "/v",
+        &target.name,
+        "/t",
+        "REG_SZ",
+        "/d",
+        &command,
+        "/f",
+    ])
+    .await?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to write the Run registry value: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(format!("{RUN_KEY}\\{}", target.name))
+}
+
+#[cfg(windows)]
+async fn remove_entry(target: &Target) -> Result<bool, String> {
+    if read_entry(target).await.is_none() {
+        return Ok(false);
+    }
+    let output = reg(&["delete", RUN_KEY, "/v", &target.name, "/f"]).await?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to remove the Run registry value: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(true)
+}
+
+#[cfg(not(windows))]
+fn entry_path(target: &Target) -> Option<PathBuf> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    if cfg!(target_os = "macos") {
+        home.map(|home| {
+            home.join("Library/LaunchAgents")
+                .join(format!("{}.plist", target.app_id))
+        })
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| home.map(|home| home.join(".config")))
+            .map(|base| {
+                base.join("autostart")
+                    .join(format!("{}.desktop", target.app_id))
+            })
+    }
+}
+
+#[cfg(not(windows))]
+fn xml_escape(value: &Path) -> String {
+    value
+        .display()
+        .to_string()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(not(windows))]
+fn entry_contents(target: &Target) -> String {
+    if cfg!(target_os = "macos") {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n<plist version=\"1.0\">\n<dict>\n  <key>Label</key>\n  <string>{}</string>\n  <key>ProgramArguments</key>\n  <array>\n    <string>{}</string>\n    <string>{START_MINIMIZED_FLAG}</string>\n  </array>\n  <key>WorkingDirectory</key>\n  <string>{}</string>\n  <key>RunAtLoad</key>\n  <true/>\n</dict>\n</plist>\n",
+            target.app_id,
+            xml_escape(&target.exe),
+            xml_escape(&target.working_dir)
+        )
+    } else {
+        format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\" {START_MINIMIZED_FLAG}\nPath={}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+            target.name,
+            target.exe.display(),
+            target.working_dir.display()
+        )
+    }
+}
+
+#[cfg(not(windows))]
+fn command_line(target: &Target) -> String {
+    format!("\"{}\" {START_MINIMIZED_FLAG}", target.exe.display())
+}
+
+#[cfg(not(windows))]
+async fn read_entry(target: &Target) -> Option<(String, String)> {
+    let path = entry_path(target)?;
+    path.exists()
+        .then(|| (path.display().to_string(), command_line(target)))
+}
+
+#[cfg(not(windows))]
+async fn write_entry(target: &Target) -> Result<String, String> {
+    let path =
+        entry_path(target).ok_or("Unable to locate the autostart directory (HOME is not set).")?;
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    tokio::fs::write(&path, entry_contents(target))
+        .await
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    Ok(path.display().to_string())
+}
+
+#[cfg(not(windows))]
+async fn remove_entry(target: &Target) -> Result<bool, String> {
+    let Some(path) = entry_path(target).filter(|path| path.exists()) else {
+        return Ok(false);
+    };
+    tokio::fs::remove_file(&path)
+        .await
+        .map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) async fn autostart_status(app: AppHandle) -> Result<AutostartStatus, LauncherError> {
+    let target = Target::resolve(&app)?;
+    let entry = read_entry(&target).await;
+    Ok(AutostartStatus {
+        enabled: entry.is_some(),
+        location: entry.as_ref().map(|(location, _)| location.clone()),
+        command: entry.map(|(_, command)| command),
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn enable_autostart(app: AppHandle) -> Result<AutostartStatus, LauncherError> {
+    let target = Target::resolve(&app)?;
+    let location = write_entry(&target).await?;
+    log_line(
+        &app,
+        &format!("Registered the launcher to start minimized on login ({location})."),
+    )
+    .await;
+    autostart_status(app).await
+}
+
+#[tauri::command]
+pub(crate) async fn disable_autostart(app: AppHandle) -> Result<AutostartStatus, LauncherError> {
+    let target = Target::resolve(&app)?;
+    if remove_entry(&target).await? {
+        log_line(&app, "Removed the launcher from login autostart.").await;
+    }
+    autostart_status(app).await
+}
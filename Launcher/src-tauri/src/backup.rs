@@ -0,0 +1,174 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use serde::Serialize;
+use tauri::AppHandle;
+use tokio::fs as tokio_fs;
+
+use crate::{
+    disk,
+    error::LauncherError,
+    load_env, log_line,
+    operation::{self, Operation},
+    silly_dir,
+    temp::TempSpace,
+    userdata,
+};
+
+const DEFAULT_BACKUP_DIR: &str = "backups";
+const SKIPPED_DIRS: &[&str] = &["backups", "_cache", "_uploads"];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackupReport {
+    path: String,
+    files: u64,
+    bytes: u64,
+}
+
+pub(crate) fn backup_root() -> PathBuf {
+    env::var("BACKUP_DIR")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_BACKUP_DIR))
+}
+
+pub(crate) async fn snapshots() -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    if let Ok(mut entries) = tokio_fs::read_dir(backup_root()).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if entry.file_type().await.is_ok_and(|kind| kind.is_dir()) {
+                found.push(entry.path());
+            }
+        }
+    }
+    found.sort_by(|a, b| b.cmp(a));
+    found
+}
+
+fn skipped(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| SKIPPED_DIRS.contains(&name))
+        .unwrap_or(false)
+}
+
+async fn collect(root: &Path) -> Result<Vec<(PathBuf, u64)>, String> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut entries = tokio_fs::read_dir(&dir)
+            .await
+            .map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let Ok(kind) = entry.file_type().await else {
+                continue;
+            };
+            if kind.is_dir() {
+                if !skipped(&path) {
+                    pending.push(path);
+                }
+            } else if kind.is_file() {
+                let size = entry.metadata().await.map(|meta| meta.len()).unwrap_or(0);
+                files.push((path, size));
+            }
+        }
+    }
+    Ok(files)
+}
+
+pub(crate) async fn included_files(
+    silly_dir: &Path,
+) -> Result<Vec<(PathBuf, u64, PathBuf)>, String> {
+    let included = [
+        (userdata::dir(silly_dir), "data"),
+        (silly_dir.join("config.yaml"), "config.yaml"),
+    ];
+    let mut files = Vec::new();
+    for (path, name) in &included {
+        if path.is_dir() {
+            for (file, size) in collect(path).await? {
+                let relative = Path::new(name).join(file.strip_prefix(path).unwrap_or(&file));
+                files.push((file, size, relative));
+            }
+        } else if path.is_file() {
+            let size = tokio_fs::metadata(path)
+                .await
+                .map(|meta| meta.len())
+                .unwrap_or(0);
+            files.push((path.clone(), size, PathBuf::from(name)));
+        }
+    }
+    Ok(files)
+}
+
+pub(crate) async fn create(app: &AppHandle, silly_dir: &Path) -> Result<BackupReport, String> {
+    let files = included_files(silly_dir).await?;
+    if files.is_empty() {
+        return Err(format!(
+            "Nothing to back up: {} has no data folder or config.yaml.",
+            silly_dir.display()
+        ));
+    }
+
+    let bytes: u64 = files.iter().map(|(_, size, _)| size).sum();
+    let root = backup_root();
+    tokio_fs::create_dir_all(&root)
+        .await
+        .map_err(|e| format!("Failed to create {}: {e}", root.display()))?;
+    if let Ok(available) = disk::available_bytes(&root) {
+        if available < bytes {
+            return Err(format!(
+                "Not enough free space in {} for a {} MB backup.",
+                root.display(),
+                bytes / (1024 * 1024)
+            ));
+        }
+    }
+
+    let target = root.join(Local::now().format("%Y%m%d-%H%M%S").to_string());
+    let staging = TempSpace::allocate("backup", bytes)?;
+    log_line(
+        app,
+        &format!(
+            "Backing up {} file(s) to {}...",
+            files.len(),
+            target.display()
+        ),
+    )
+    .await;
+    for (path, _, relative) in &files {
+        let destination = staging.path().join(relative);
+        if let Some(parent) = destination.parent() {
+            tokio_fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        tokio_fs::copy(path, &destination)
+            .await
+            .map_err(|e| format!("Failed to copy {}: {e}", path.display()))?;
+    }
+    staging.persist(&target).await?;
+
+    let report = BackupReport {
+        path: target.to_string_lossy().into_owned(),
+        files: files.len() as u64,
+        bytes,
+    };
+    log_line(app, &format!("Backup written to {}.", report.path)).await;
+    Ok(report)
+}
+
+#[tauri::command]
+pub(crate) async fn create_backup(app: AppHandle) -> Result<BackupReport, LauncherError> {
+    let _operation = operation::begin(&app, Operation::Backup)?;
+    load_env();
+    let silly = silly_dir()?;
+    Ok(create(&app, &silly).await?)
+}
@@ -0,0 +1,478 @@
+use std::{
+    env, fs as stdfs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use tokio::fs as tokio_fs;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{
+    access, backup, clone_vendor,
+    error::LauncherError,
+    has_submodules, head_commit, install_packages, installs, load_env, log_line, offline,
+    operation::{self, Operation},
+    run_git, silly_dir,
+    temp::TempSpace,
+    userdata, vendor_dir, ServerState,
+};
+
+const DEFAULT_BUNDLE_DIR: &str = "bundles";
+const BUNDLE_FORMAT: u32 = 1;
+const MANIFEST_FILE: &str = "bundle.json";
+const ENV_FILE: &str = "launcher.env";
+const MACHINE_KEYS: &[&str] = &[
+    "WEYLANDTAVERN_DIR",
+    "SILLYTAVERN_DIR",
+    "DATA_ROOT",
+    "BACKUP_DIR",
+    "BUNDLE_DIR",
+    "CHARACTER_REVIEW_DIR",
+    "TEMP_DIR",
+    "TUNNEL_BIN",
+    "TLS_CERT_PATH",
+    "TLS_KEY_PATH",
+    "VENDOR_MIGRATIONS_DIR",
+    "UPDATE_SCRIPT",
+    "HOOK_PRE_START",
+    "HOOK_POST_START",
+    "HOOK_PRE_STOP",
+    "HOOK_POST_STOP",
+];
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct VendorPin {
+    url: String,
+    branch: Option<String>,
+    commit: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleManifest {
+    format: u32,
+    created: String,
+    launcher_version: String,
+    vendor: Option<VendorPin>,
+    files: u64,
+    bytes: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BundleReport {
+    path: String,
+    files: u64,
+    bytes: u64,
+    commit: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BundleImportReport {
+    cloned: bool,
+    commit: Option<String>,
+    pinned: bool,
+    settings: usize,
+    files: u64,
+    modules_error: Option<String>,
+    message: String,
+}
+
+fn bundle_root() -> PathBuf {
+    env::var("BUNDLE_DIR")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_BUNDLE_DIR))
+}
+
+fn parse_settings(raw: &str) -> Vec<(String, String)> {
+    dotenvy::from_read_iter(raw.as_bytes())
+        .filter_map(Result::ok)
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .filter(|(key, value)| {
+            !key.is_empty()
+                && !value.is_empty()
+                && !value.starts_with('#')
+                && !MACHINE_KEYS.contains(&key.as_str())
+        })
+        .collect()
+}
+
+fn portable_settings() -> Vec<(String, String)> {
+    parse_settings(&stdfs::read_to_string(access::env_file()).unwrap_or_default())
+}
+
+async fn git_text(repo: &Path, args: &[&str]) -> Option<String> {
+    let output = run_git(repo, args).await.ok()?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !text.is_empty()).then_some(text)
+}
+
+async fn vendor_pin() -> Option<VendorPin> {
+    let repo = vendor_dir().ok()?;
+    let commit = head_commit(&repo).await?;
+    let url = git_text(&repo, &["remote", "get-url", "origin"]).await?;
+    let branch = git_text(&repo, &["rev-parse", "--abbrev-ref", "HEAD"])
+        .await
+        .filter(|branch| branch != "HEAD");
+    Some(VendorPin {
+        url,
+        branch,
+        commit,
+    })
+}
+
+fn write_bundle(
+    target: &Path,
+    manifest: &BundleManifest,
+    settings: &[(String, String)],
+    files: &[(PathBuf, PathBuf)],
+) -> Result<(), String> {
+    let partial = target.with_extension("zip.partial");
+    let file = stdfs::File::create(&partial)
+        .map_err(|e| format!("Failed to create {}: {e}", partial.display()))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let failed = |e: &dyn std::fmt::Display| format!("Failed to write {}: {e}", partial.display());
+
+    let manifest = serde_json::to_vec_pretty(manifest).map_err(|e| e.to_string())?;
+    zip.start_file(MANIFEST_FILE, options)
+        .map_err(|e| failed(&e))?;
+    zip.write_all(&manifest).map_err(|e| failed(&e))?;
+    zip.start_file(ENV_FILE, options).map_err(|e| failed(&e))?;
+    for (key, value) in settings {
+        writeln!(zip, "{key}={value}").map_err(|e| failed(&e))?;
+    }
+    for (path, relative) in files {
+        let name = relative
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let mut source = stdfs::File::open(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        zip.start_file(name, options).map_err(|e| failed(&e))?;
+        io::copy(&mut source, &mut zip).map_err(|e| failed(&e))?;
+    }
+    zip.finish().map_err(|e| failed(&e))?;
+    stdfs::rename(&partial, target)
+        .map_err(|e| format!("Failed to finish {}: {e}", target.display()))
+}
+
+fn unpack(bundle: PathBuf) -> Result<TempSpace, String> {
+    let file = stdfs::File::open(&bundle)
+        .map_err(|e| format!("Failed to open {}: {e}", bundle.display()))?;
+    let mut zip = ZipArchive::new(file)
+        .map_err(|e| format!("{} is not a readable bundle: {e}", bundle.display()))?;
+    if zip.by_name(MANIFEST_FILE).is_err() {
+        return Err(format!(
+            "{} is not a WeylandTavern bundle ({MANIFEST_FILE} is missing).",
+            bundle.display()
+        ));
+    }
+    let size = (0..zip.len())
+        .filter_map(|index| zip.by_index(index).ok().map(|entry| entry.size()))
+        .sum();
+    let space = TempSpace::allocate("bundle", size)?;
+    zip.extract(space.path())
+        .map_err(|e| format!("Failed to unpack {}: {e}", bundle.display()))?;
+    Ok(space)
+}
+
+async fn pin_checkout(app: &AppHandle, pin: &VendorPin) -> Result<bool, String> {
+    let repo = vendor_dir()?;
+    let wanted = format!("{}^{{commit}}", pin.commit);
+    let present = run_git(&repo, &["cat-file", "-e", &wanted])
+        .await
+        .is_ok_and(|output| output.status.success());
+    if !present {
+        let output = run_git(&repo, &["fetch", "origin", &pin.commit]).await?;
+        if !output.status.success() {
+            log_line(
+                app,
+                &format!(
+                    "Commit {} is no longer on the remote; keeping the freshly cloned branch.",
+                    pin.commit
+                ),
+            )
+            .await;
+            return Ok(false);
+        }
+    }
+    let output = run_git(&repo, &["reset", "--hard", &pin.commit]).await?;
+    if !output.status.success() {
+        return Err(format!(
+            "git reset to {} failed: {}",
+            pin.commit,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    if has_submodules(&repo) {
+        let output = run_git(&repo, &["submodule", "update", "--init", "--recursive"]).await?;
+        if !output.status.success() {
+            log_line(app, String::from_utf8_lossy(&output.stderr).trim()).await;
+        }
+    }
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) async fn export_bundle(
+    app: AppHandle,
+    path: Option<String>,
+) -> Result<BundleReport, LauncherError> {
+    let _operation = operation::begin(&app, Operation::BundleExport)?;
+    load_env();
+    let silly = silly_dir()?;
+    let files = backup::included_files(&silly).await?;
+    let vendor = vendor_pin().await;
+    if vendor.is_none() {
+        log_line(
+            &app,
+            "The vendor checkout has no origin remote or commit; the bundle will not pin a version.",
+        )
+        .await;
+    }
+    let target = match path.map(|path| path.trim().to_string()) {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => bundle_root().join(format!(
+            "weylandtavern-{}.zip",
+            Local::now().format("%Y%m%d-%H%M%S")
+        )),
+    };
+    if let Some(parent) = target
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        tokio_fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+
+    let bytes = files.iter().map(|(_, size, _)| size).sum();
+    let manifest = BundleManifest {
+        format: BUNDLE_FORMAT,
+        created: Local::now().to_rfc3339(),
+        launcher_version: app.package_info().version.to_string(),
+        vendor: vendor.clone(),
+        files: files.len() as u64,
+        bytes,
+    };
+    log_line(
+        &app,
+        &format!(
+            "Packing {} file(s) into {}...",
+            files.len(),
+            target.display()
+        ),
+    )
+    .await;
+    let settings = portable_settings();
+    let entries: Vec<(PathBuf, PathBuf)> = files
+        .into_iter()
+        .map(|(path, _, relative)| (path, relative))
+        .collect();
+    let report = BundleReport {
+        path: target.display().to_string(),
+        files: manifest.files,
+        bytes,
+        commit: vendor.map(|pin| pin.commit),
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        write_bundle(&target, &manifest, &settings, &entries)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    log_line(&app, &format!("Bundle written to {}.", report.path)).await;
+    Ok(report)
+}
+
+#[tauri::command]
+pub(crate) async fn import_bundle(
+    app: AppHandle,
+    state: State<'_, ServerState>,
+    path: String,
+    replace_data: Option<bool>,
+) -> Result<BundleImportReport, LauncherError> {
+    let _operation = operation::begin(&app, Operation::BundleImport)?;
+    load_env();
+    installs::ensure_stopped(state.inner(), "importing a bundle")?;
+    let bundle = PathBuf::from(path.trim());
+    let unpacked = tauri::async_runtime::spawn_blocking(move || unpack(bundle))
+        .await
+        .map_err(|e| e.to_string())??;
+    let root = unpacked.path().to_path_buf();
+    let manifest: BundleManifest = serde_json::from_slice(
+        &tokio_fs::read(root.join(MANIFEST_FILE))
+            .await
+            .map_err(|e| e.to_string())?,
+    )
+    .map_err(|e| format!("The bundle manifest is unreadable: {e}"))?;
+    if manifest.format > BUNDLE_FORMAT {
+        return Err(format!(
+            "This bundle was made by a newer launcher (format {}). Update the launcher first.",
+            manifest.format
+        )
+        .into());
+    }
+
+    if let Ok(silly) = silly_dir() {
+        let data = userdata::dir(&silly);
+        let (existing, _) = userdata::measure(&data).await;
+        if existing > 0 && !replace_data.unwrap_or(false) {
+            return Err(format!(
+                "{} already holds {existing} file(s) of user data. Import again with replaceData to move it aside and replace it.",
+                data.display()
+            )
+            .into());
+        }
+    }
+
+    let raw = tokio_fs::read_to_string(root.join(ENV_FILE))
+        .await
+        .unwrap_or_default();
+    let parsed = parse_settings(&raw);
+    let settings: Vec<(&str, String)> = parsed
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.clone()))
+        .collect();
+    if !settings.is_empty() {
+        access::set_env_values(&access::env_file(), &settings)?;
+        for (key, value) in &settings {
+            env::set_var(key, value);
+        }
+    }
+
+    let mut cloned = false;
+    let mut pinned = false;
+    if silly_dir().is_err() {
+        if let Some(pin) = &manifest.vendor {
+            env::set_var("VENDOR_REPO_URL", &pin.url);
+            if let Some(branch) = &pin.branch {
+                env::set_var("VENDOR_BRANCH", branch);
+            }
+        }
+        cloned = clone_vendor(app.clone()).await?.cloned;
+        if let Some(pin) = &manifest.vendor {
+            pinned = pin_checkout(&app, pin).await?;
+        }
+    } else if let Some(pin) = &manifest.vendor {
+        let current = vendor_dir().ok();
+        let head = match &current {
+            Some(repo) => head_commit(repo).await,
+            None => None,
+        };
+        pinned = head.as_deref() == Some(pin.commit.as_str());
+        if !pinned {
+            log_line(
+                &app,
+                &format!(
+                    "The existing checkout is at {}, the bundle was made at {}. The checkout was left as it is.",
+                    head.as_deref().unwrap_or("an unknown commit"),
+                    pin.commit
+                ),
+            )
+            .await;
+        }
+    }
+
+    let silly = silly_dir()?;
+    let data = userdata::dir(&silly);
+    let (existing, _) = userdata::measure(&data).await;
+    let mut parked = None;
+    if existing > 0 {
+        let aside = data.with_file_name(format!(
+            "{}.replaced-{}",
+            data.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "data".into()),
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        tokio_fs::rename(&data, &aside)
+            .await
+            .map_err(|e| format!("Failed to move {} aside: {e}", data.display()))?;
+        parked = Some(aside);
+    }
+    let mut files = 0;
+    if root.join("data").is_dir() {
+        files += access::copy_tree(&root.join("data"), &data).await?;
+    }
+    if root.join("config.yaml").is_file() {
+        tokio_fs::copy(root.join("config.yaml"), silly.join("config.yaml"))
+            .await
+            .map_err(|e| format!("Failed to restore config.yaml: {e}"))?;
+        files += 1;
+    }
+
+    let modules_error = install_packages(&app, &silly, offline::detect().await)
+        .await
+        .err()
+        .map(String::from);
+    let mut message = format!(
+        "Bundle from {} imported: {files} file(s) restored, {} setting(s) applied.",
+        manifest.created,
+        settings.len()
+    );
+    if let Some(aside) = &parked {
+        message.push_str(&format!(
+            " The previous user data was moved to {}.",
+            aside.display()
+        ));
+    }
+    if let Some(err) = &modules_error {
+        message.push_str(&format!(" Installing Node modules failed: {err}"));
+    }
+    log_line(&app, &message).await;
+    Ok(BundleImportReport {
+        cloned,
+        commit: manifest.vendor.map(|pin| pin.commit),
+        pinned,
+        settings: settings.len(),
+        files,
+        modules_error,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_settings_keeps_portable_values() {
+        let raw = "PORT=8000\n\
+                   SERVER_ARGS=\"--listen --whitelist\"  # quoted\n\
+                   SILLYTAVERN_DIR=/opt/st\n\
+                   EMPTY=\n\
+                   # just a comment\n\
+                   BROKEN=two words\n\
+                   LOG_LEVEL=info # trailing\n";
+        assert_eq!(
+            parse_settings(raw),
+            vec![
+                ("PORT".to_string(), "8000".to_string()),
+                (
+                    "SERVER_ARGS".to_string(),
+                    "--listen --whitelist".to_string()
+                ),
+                ("LOG_LEVEL".to_string(), "info".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_settings_drops_machine_keys() {
+        let raw = MACHINE_KEYS
+            .iter()
+            .map(|key| format!("{key}=/somewhere\n"))
+            .collect::<String>();
+        assert!(parse_settings(&raw).is_empty());
+    }
+}
@@ -0,0 +1,530 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use chrono::{TimeZone, Utc};
+use instant_acme::{
+    Account, AccountCredentials, AuthorizationStatus, ChallengeType, Identifier, LetsEncrypt,
+    NewAccount, NewOrder, OrderStatus,
+};
+use rcgen::{CertificateParams, DistinguishedName, KeyPair};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+use tokio::{fs as tokio_fs, io::AsyncWriteExt, process::Command as TokioCommand, time::sleep};
+use x509_parser::pem::parse_x509_pem;
+
+use crate::{
+    error::LauncherError, events, load_env, log_line, offline, server_arg, silly_dir, state,
+};
+
+pub(crate) const CHALLENGE_PATH_PREFIX: &str = "/.well-known/acme-challenge/";
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const ACME_ACCOUNT_KEY: &str = "acmeAccount";
+const ACME_ACCOUNT_FILE: &str = "acme-account.json";
+const DEFAULT_WARN_DAYS: i64 = 14;
+const DEFAULT_RENEW_DAYS: i64 = 30;
+const ORDER_POLL_ATTEMPTS: u32 = 10;
+
+static HTTP_CHALLENGES: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CertificateStatus {
+    cert_path: Option<String>,
+    not_after: Option<String>,
+    days_remaining: Option<i64>,
+    expiring_soon: bool,
+    acme_domain: Option<String>,
+    message: String,
+}
+
+struct CertPaths {
+    cert: PathBuf,
+    key: PathBuf,
+}
+
+fn env_days(key: &str, default: i64) -> i64 {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .filter(|days| *days >= 0)
+        .unwrap_or(default)
+}
+
+fn acme_domain() -> Option<String> {
+    env::var("ACME_DOMAIN")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn resolve_path(raw: String) -> PathBuf {
+    let path = PathBuf::from(raw);
+    if path.is_absolute() {
+        return path;
+    }
+    silly_dir().map(|dir| dir.join(&path)).unwrap_or(path)
+}
+
+fn configured_paths() -> Option<CertPaths> {
+    let configured = |env_key: &str, flag: &str| {
+        env::var(env_key)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .or_else(|| server_arg(flag))
+            .map(resolve_path)
+    };
+    Some(CertPaths {
+        cert: configured("SSL_CERT_PATH", "--certPath")?,
+        key: configured("SSL_KEY_PATH", "--keyPath")?,
+    })
+}
+
+async fn read_not_after(path: &Path) -> Result<i64, String> {
+    let data = tokio_fs::read(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let (_, pem) = parse_x509_pem(&data)
+        .map_err(|e| format!("{} is not a PEM certificate: {e}", path.display()))?;
+    let cert = pem
+        .parse_x509()
+        .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+    Ok(cert.validity().not_after.timestamp())
+}
+
+async fn current_status() -> CertificateStatus {
+    let domain = acme_domain();
+    let Some(paths) = configured_paths() else {
+        return CertificateStatus {
+            cert_path: None,
+            not_after: None,
+            days_remaining: None,
+            expiring_soon: false,
+            acme_domain: domain,
+            message: "No SSL certificate is configured.".into(),
+        };
+    };
+    let cert_path = Some(paths.cert.to_string_lossy().into_owned());
+    match read_not_after(&paths.cert).await {
+        Ok(not_after) => {
+            let days = (not_after - Utc::now().timestamp()).div_euclid(86_400);
+            let expiring_soon = days <= env_days("CERT_WARN_DAYS", DEFAULT_WARN_DAYS);
+            let message = if days < 0 {
+                "The SSL certificate has expired.".to_string()
+            } else {
+                format!("The SSL certificate expires in {days} days.")
+            };
+            CertificateStatus {
+                cert_path,
+                not_after: Utc
+                    .timestamp_opt(not_after, 0)
+                    .single()
+                    .map(|time| time.to_rfc3339()),
+                days_remaining: Some(days),
+                expiring_soon,
+                acme_domain: domain,
+                message,
+            }
+        }
+        Err(err) => CertificateStatus {
+            cert_path,
+            not_after: None,
+            days_remaining: None,
+            expiring_soon: true,
+            acme_domain: domain,
+            message: err,
+        },
+    }
+}
+
+pub(crate) fn http_challenge_response(token: &str) -> Option<String> {
+    HTTP_CHALLENGES
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(candidate, _)| candidate == token)
+        .map(|(_, response)| response.clone())
+}
+
+fn set_http_challenge(token: &str, response: &str) {
+    let mut guard = HTTP_CHALLENGES.lock().unwrap();
+    guard.retain(|(candidate, _)| candidate != token);
+    guard.push((token.to_string(), response.to_string()));
+}
+
+fn clear_http_challenge(token: &str) {
+    HTTP_CHALLENGES
+        .lock()
+        .unwrap()
+        .retain(|(candidate, _)| candidate != token);
+}
+
+async fn run_dns_hook(action: &str, record: &str, value: &str) -> Result<(), String> {
+    let hook = env::var("ACME_DNS_HOOK").unwrap_or_default();
+    let mut parts = hook.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or("ACME_CHALLENGE=dns-01 requires ACME_DNS_HOOK to be set.")?;
+    let output = TokioCommand::new(program)
+        .args(parts)
+        .args([action, record, value])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ACME_DNS_HOOK: {e}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "ACME_DNS_HOOK {action} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn acme_directory() -> String {
+    match env::var("ACME_DIRECTORY").unwrap_or_default().trim() {
+        "" | "production" => LetsEncrypt::Production.url().to_string(),
+        "staging" => LetsEncrypt::Staging.url().to_string(),
+        custom => custom.to_string(),
+    }
+}
+
+async fn save_credentials(credentials: &AccountCredentials) -> Result<(), String> {
+    let dir = state::state_dir();
+    tokio_fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    let path = dir.join(ACME_ACCOUNT_FILE);
+    let temp = path.with_extension("json.tmp");
+    let json = serde_json::to_vec_pretty(credentials).map_err(|e| e.to_string())?;
+    let _ = tokio_fs::remove_file(&temp).await;
+    if let Err(err) = write_private(&temp, &json).await {
+        let _ = tokio_fs::remove_file(&temp).await;
+        return Err(format!("Failed to write {}: {err}", temp.display()));
+    }
+    tokio_fs::rename(&temp, &path)
+        .await
+        .map_err(|e| format!("Failed to replace {}: {e}", path.display()))?;
+    state::save(ACME_ACCOUNT_KEY, &Some(path.display().to_string()))
+}
+
+async fn stored_credentials() -> Result<Option<AccountCredentials>, String> {
+    match state::load::<Option<Value>>(ACME_ACCOUNT_KEY) {
+        None => Ok(None),
+        Some(Value::String(path)) => {
+            let raw = tokio_fs::read(&path)
+                .await
+                .map_err(|e| format!("Failed to read the ACME account from {path}: {e}"))?;
+            serde_json::from_slice(&raw)
+                .map(Some)
+                .map_err(|e| format!("The ACME account in {path} is unreadable: {e}"))
+        }
+        Some(legacy) => {
+            let credentials: AccountCredentials = serde_json::from_value(legacy)
+                .map_err(|e| format!("The stored ACME account is unreadable: {e}"))?;
+            save_credentials(&credentials).await?;
+            Ok(Some(credentials))
+        }
+    }
+}
+
+async fn acme_account() -> Result<Account, String> {
+    if let Some(credentials) = stored_credentials().await? {
+        return Account::from_credentials(credentials)
+            .await
+            .map_err(|e| format!("Failed to restore ACME account: {e}"));
+    }
+    let email = env::var("ACME_EMAIL")
+        .ok()
+        .map(|value| format!("mailto:{}", value.trim()))
+        .filter(|value| value.len() > "mailto:".len());
+    let contact: Vec<&str> = email.iter().map(String::as_str).collect();
+    let (account, credentials) = Account::create(
+        &NewAccount {
+            contact: &contact,
+            terms_of_service_agreed: true,
+            only_return_existing: false,
+        },
+        &acme_directory(),
+        None,
+    )
+    .await
+    .map_err(|e| format!("Failed to create ACME account: {e}"))?;
+    save_credentials(&credentials).await?;
+    Ok(account)
+}
+
+enum PendingChallenge {
+    Http { token: String },
+    Dns { record: String, value: String },
+}
+
+async fn cleanup_challenges(pending: &[PendingChallenge]) {
+    for challenge in pending {
+        match challenge {
+            PendingChallenge::Http { token } => clear_http_challenge(token),
+            PendingChallenge::Dns { record, value } => {
+                let _ = run_dns_hook("clear", record, value).await;
+            }
+        }
+    }
+}
+
+async fn renew(app: &AppHandle, domain: &str, paths: &CertPaths) -> Result<(), String> {
+    let use_dns = env::var("ACME_CHALLENGE")
+        .map(|value| value.trim().eq_ignore_ascii_case("dns-01"))
+        .unwrap_or(false);
+    let challenge_type = if use_dns {
+        ChallengeType::Dns01
+    } else {
+        ChallengeType::Http01
+    };
+    log_line(
+        app,
+        &format!(
+            "Requesting a new certificate for {domain} via {}...",
+            if use_dns { "DNS-01" } else { "HTTP-01" }
+        ),
+    )
+    .await;
+
+    let account = acme_account().await?;
+    let mut order = account
+        .new_order(&NewOrder {
+            identifiers: &[Identifier::Dns(domain.to_string())],
+        })
+        .await
+        .map_err(|e| format!("Failed to create ACME order: {e}"))?;
+    let authorizations = order
+        .authorizations()
+        .await
+        .map_err(|e| format!("Failed to fetch ACME authorizations: {e}"))?;
+
+    let mut pending = Vec::new();
+    let mut ready_urls = Vec::new();
+    for authz in &authorizations {
+        match authz.status {
+            AuthorizationStatus::Pending => {}
+            AuthorizationStatus::Valid => continue,
+            other => {
+                cleanup_challenges(&pending).await;
+                return Err(format!("ACME authorization is {other:?}."));
+            }
+        }
+        let Some(challenge) = authz
+            .challenges
+            .iter()
+            .find(|challenge| challenge.r#type == challenge_type)
+        else {
+            cleanup_challenges(&pending).await;
+            return Err("The ACME server did not offer the configured challenge type.".into());
+        };
+        let Identifier::Dns(name) = &authz.identifier;
+        let key_authorization = order.key_authorization(challenge);
+        if use_dns {
+            let record = format!("_acme-challenge.{name}");
+            let value = key_authorization.dns_value();
+            if let Err(err) = run_dns_hook("set", &record, &value).await {
+                cleanup_challenges(&pending).await;
+                return Err(err);
+            }
+            pending.push(PendingChallenge::Dns { record, value });
+        } else {
+            set_http_challenge(&challenge.token, key_authorization.as_str());
+            pending.push(PendingChallenge::Http {
+                token: challenge.token.clone(),
+            });
+        }
+        ready_urls.push(challenge.url.clone());
+    }
+
+    let result = finish_order(&mut order, &ready_urls, domain).await;
+    cleanup_challenges(&pending).await;
+    let (chain, key) = result?;
+
+    for (path, contents, private) in [(&paths.cert, chain, false), (&paths.key, key, true)] {
+        if let Some(parent) = path.parent() {
+            tokio_fs::create_dir_all(parent)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        let temp = path.with_extension("renew.tmp");
+        let _ = tokio_fs::remove_file(&temp).await;
+        let written = if private {
+            write_private(&temp, contents.as_bytes()).await
+        } else {
+            tokio_fs::write(&temp, contents)
+                .await
+                .map_err(|e| e.to_string())
+        };
+        if let Err(err) = written {
+            let _ = tokio_fs::remove_file(&temp).await;
+            return Err(format!("Failed to write {}: {err}", temp.display()));
+        }
+        tokio_fs::rename(&temp, path)
+            .await
+            .map_err(|e| format!("Failed to replace {}: {e}", path.display()))?;
+    }
+
+    log_line(
+        app,
+        "Certificate renewed. Restart WeylandTavern to serve the new certificate.",
+    )
+    .await;
+    events::emit(app, "certificate-renewed", current_status().await);
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn create_private(path: &Path) -> Result<tokio_fs::File, String> {
+    tokio_fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(windows)]
+async fn create_private(path: &Path) -> Result<tokio_fs::File, String> {
+    let user = match (env::var("USERDOMAIN"), env::var("USERNAME")) {
+        (Ok(domain), Ok(user)) => format!("{domain}\\{user}"),
+        (_, Ok(user)) => user,
+        _ => return Err("Unable to determine the current user name.".into()),
+    };
+    let file = tokio_fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .await
+        .map_err(|e| e.to_string())?;
+    let output = TokioCommand::new("icacls")
+        .arg(path)
+        .args(["/inheritance:r", "/grant:r", &format!("{user}:F"), "/Q"])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to start icacls: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to restrict access: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(file)
+}
+
+pub(crate) async fn write_private(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let mut file = create_private(path).await?;
+    file.write_all(contents).await.map_err(|e| e.to_string())?;
+    file.sync_all().await.map_err(|e| e.to_string())
+}
+
+async fn finish_order(
+    order: &mut instant_acme::Order,
+    ready_urls: &[String],
+    domain: &str,
+) -> Result<(String, String), String> {
+    for url in ready_urls {
+        order
+            .set_challenge_ready(url)
+            .await
+            .map_err(|e| format!("Failed to signal ACME challenge readiness: {e}"))?;
+    }
+
+    let mut delay = Duration::from_secs(1);
+    let mut status = order.state().status;
+    for _ in 0..ORDER_POLL_ATTEMPTS {
+        if matches!(status, OrderStatus::Ready | OrderStatus::Invalid) {
+            break;
+        }
+        sleep(delay).await;
+        delay = (delay * 2).min(Duration::from_secs(30));
+        status = order
+            .refresh()
+            .await
+            .map_err(|e| format!("Failed to refresh ACME order: {e}"))?
+            .status;
+    }
+    if status != OrderStatus::Ready {
+        return Err(format!("ACME order did not become ready ({status:?})."));
+    }
+
+    let mut params = CertificateParams::new(vec![domain.to_string()]).map_err(|e| e.to_string())?;
+    params.distinguished_name = DistinguishedName::new();
+    let private_key = KeyPair::generate().map_err(|e| e.to_string())?;
+    let csr = params
+        .serialize_request(&private_key)
+        .map_err(|e| e.to_string())?;
+    order
+        .finalize(csr.der())
+        .await
+        .map_err(|e| format!("Failed to finalize ACME order: {e}"))?;
+
+    for _ in 0..ORDER_POLL_ATTEMPTS {
+        match order
+            .certificate()
+            .await
+            .map_err(|e| format!("Failed to download certificate: {e}"))?
+        {
+            Some(chain) => return Ok((chain, private_key.serialize_pem())),
+            None => sleep(Duration::from_secs(1)).await,
+        }
+    }
+    Err("The ACME server did not issue the certificate in time.".into())
+}
+
+async fn check_once(app: &AppHandle) {
+    load_env();
+    let status = current_status().await;
+    if status.cert_path.is_none() {
+        return;
+    }
+    if status.expiring_soon {
+        log_line(app, &status.message).await;
+        events::emit(app, "certificate-expiry", status.clone());
+    }
+
+    let (Some(domain), Some(paths)) = (acme_domain(), configured_paths()) else {
+        return;
+    };
+    let due = status
+        .days_remaining
+        .map(|days| days <= env_days("CERT_RENEW_DAYS", DEFAULT_RENEW_DAYS))
+        .unwrap_or(true);
+    if due && !offline::detect().await.is_offline() {
+        if let Err(err) = renew(app, &domain, &paths).await {
+            log_line(app, &format!("Certificate renewal failed: {err}")).await;
+        }
+    }
+}
+
+pub(crate) fn spawn_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            check_once(&app).await;
+            sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub(crate) async fn certificate_status() -> Result<CertificateStatus, LauncherError> {
+    load_env();
+    Ok(current_status().await)
+}
+
+#[tauri::command]
+pub(crate) async fn renew_certificate(app: AppHandle) -> Result<CertificateStatus, LauncherError> {
+    load_env();
+    let domain = acme_domain().ok_or("Set ACME_DOMAIN to enable certificate renewal.")?;
+    let paths = configured_paths()
+        .ok_or("Configure SSL_CERT_PATH and SSL_KEY_PATH (or --certPath/--keyPath).")?;
+    renew(&app, &domain, &paths).await?;
+    Ok(current_status().await)
+}
@@ -0,0 +1,545 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Local};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+use tokio::fs as tokio_fs;
+
+use crate::{
+    error::LauncherError, integrity, load_env, log_line, logs_dir, silly_dir, temp::TempSpace,
+    userdata,
+};
+
+const MANIFEST_KEY: &str = "CHARACTER_SYNC_MANIFEST";
+const PUBKEY_KEY: &str = "CHARACTER_SYNC_PUBKEY";
+const SIGNATURE_KEY: &str = "CHARACTER_SYNC_SIGNATURE";
+const QUARANTINE_DIR: &str = "character-quarantine";
+const CARD_EXTENSIONS: &[&str] = &["png", "json"];
+const DEFAULT_REVIEW_DIR: &str = "character-review";
+const PREVIEW_DESCRIPTION_CHARS: usize = 300;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CardManifest {
+    base_url: Option<String>,
+    files: Vec<CardEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CardEntry {
+    name: String,
+    sha256: String,
+    url: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RejectedCard {
+    name: String,
+    reason: String,
+}
+
+pub(crate) struct SyncSummary {
+    downloaded: usize,
+    unchanged: usize,
+    pub(crate) rejected: usize,
+    quarantine: Option<String>,
+    staged: bool,
+}
+
+impl SyncSummary {
+    pub(crate) fn message(&self) -> String {
+        let mut message = if self.staged {
+            format!(
+                "Character update completed: {} waiting for review, {} unchanged",
+                self.downloaded, self.unchanged
+            )
+        } else {
+            format!(
+                "Character update completed: {} downloaded, {} unchanged",
+                self.downloaded, self.unchanged
+            )
+        };
+        match &self.quarantine {
+            Some(dir) => {
+                message.push_str(&format!(", {} rejected (details in {dir}).", self.rejected))
+            }
+            None => message.push('.'),
+        }
+        message
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CardPreview {
+    name: Option<String>,
+    creator: Option<String>,
+    version: Option<String>,
+    spec: Option<String>,
+    tags: Vec<String>,
+    description: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StagedCharacter {
+    file: String,
+    size: u64,
+    staged_at: Option<String>,
+    replaces: bool,
+    preview: Option<CardPreview>,
+    problem: Option<String>,
+}
+
+pub(crate) struct Staging(TempSpace);
+
+impl Staging {
+    pub(crate) fn root(&self) -> &Path {
+        self.0.path()
+    }
+
+    fn cards(&self) -> PathBuf {
+        self.0
+            .path()
+            .join("data")
+            .join("default-user")
+            .join("characters")
+    }
+}
+
+pub(crate) fn manifest_url() -> Option<String> {
+    env::var(MANIFEST_KEY)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| value.starts_with("http://") || value.starts_with("https://"))
+}
+
+pub(crate) fn review_enabled() -> bool {
+    !matches!(
+        env::var("CHARACTER_SYNC_REVIEW")
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase()
+            .as_str(),
+        "false" | "0" | "no" | "off"
+    )
+}
+
+fn review_dir() -> PathBuf {
+    configured("CHARACTER_REVIEW_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_REVIEW_DIR))
+}
+
+fn live_dir(silly: &Path) -> PathBuf {
+    userdata::dir(silly).join("default-user").join("characters")
+}
+
+fn configured(key: &str) -> Option<String> {
+    env::var(key)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn hex_digest(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn valid_name(name: &str) -> bool {
+    let path = Path::new(name);
+    path.file_name().and_then(|file| file.to_str()) == Some(name)
+        && path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| CARD_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+async fn fetch(client: &reqwest::Client, url: &str) -> Result<Vec<u8>, String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to download {url}: {e}"))?;
+    response
+        .bytes()
+        .await
+        .map(|bytes| bytes.to_vec())
+        .map_err(|e| format!("Failed to read {url}: {e}"))
+}
+
+async fn verify_signature(
+    client: &reqwest::Client,
+    manifest_url: &str,
+    raw: &[u8],
+) -> Result<bool, String> {
+    let Some(key) = configured(PUBKEY_KEY) else {
+        return Ok(false);
+    };
+    let key = STANDARD
+        .decode(&key)
+        .map_err(|e| format!("{PUBKEY_KEY} is not valid base64: {e}"))?;
+    let signature_url = configured(SIGNATURE_KEY).unwrap_or_else(|| format!("{manifest_url}.sig"));
+    let signature = fetch(client, &signature_url).await?;
+    let signature = STANDARD
+        .decode(String::from_utf8_lossy(&signature).trim())
+        .map_err(|e| {
+            format!("The manifest signature at {signature_url} is not valid base64: {e}")
+        })?;
+    UnparsedPublicKey::new(&ED25519, &key)
+        .verify(raw, &signature)
+        .map_err(|_| {
+            format!("The character manifest signature does not match {PUBKEY_KEY}; nothing was downloaded.")
+        })?;
+    Ok(true)
+}
+
+fn entry_url(
+    manifest_url: &str,
+    manifest: &CardManifest,
+    entry: &CardEntry,
+) -> Result<String, String> {
+    let base = reqwest::Url::parse(manifest_url).map_err(|e| e.to_string())?;
+    let base = match &manifest.base_url {
+        Some(base_url) => base
+            .join(&format!("{}/", base_url.trim_end_matches('/')))
+            .map_err(|e| e.to_string())?,
+        None => base,
+    };
+    base.join(entry.url.as_deref().unwrap_or(&entry.name))
+        .map(|url| url.to_string())
+        .map_err(|e| format!("Invalid URL for {}: {e}", entry.name))
+}
+
+async fn quarantine(dir: &Path, name: &str, bytes: &[u8]) -> Result<(), String> {
+    tokio_fs::create_dir_all(dir)
+        .await
+        .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    tokio_fs::write(dir.join(name), bytes)
+        .await
+        .map_err(|e| format!("Failed to quarantine {name}: {e}"))
+}
+
+pub(crate) async fn sync(
+    app: &AppHandle,
+    silly: &Path,
+    manifest_url: &str,
+) -> Result<SyncSummary, String> {
+    let client = reqwest::Client::new();
+    log_line(
+        app,
+        &format!("Downloading character manifest from {manifest_url}..."),
+    )
+    .await;
+    let raw = fetch(&client, manifest_url).await?;
+    let signed = verify_signature(&client, manifest_url, &raw).await?;
+    let manifest: CardManifest =
+        serde_json::from_slice(&raw).map_err(|e| format!("Invalid character manifest: {e}"))?;
+    if signed {
+        log_line(app, "Character manifest signature verified.").await;
+    }
+
+    let characters = live_dir(silly);
+    let staged = review_enabled();
+    let destination = if staged {
+        review_dir()
+    } else {
+        characters.clone()
+    };
+    tokio_fs::create_dir_all(&destination)
+        .await
+        .map_err(|e| format!("Failed to create {}: {e}", destination.display()))?;
+    let quarantine_dir = logs_dir()
+        .join(QUARANTINE_DIR)
+        .join(Local::now().format("%Y%m%d-%H%M%S").to_string());
+
+    let mut summary = SyncSummary {
+        downloaded: 0,
+        unchanged: 0,
+        rejected: 0,
+        quarantine: None,
+        staged,
+    };
+    let mut rejected = Vec::new();
+    for entry in &manifest.files {
+        if !valid_name(&entry.name) {
+            rejected.push(RejectedCard {
+                name: entry.name.clone(),
+                reason: "Not a plain .png or .json file name.".into(),
+            });
+            continue;
+        }
+        let mut current = false;
+        for existing in [characters.join(&entry.name), destination.join(&entry.name)] {
+            if let Ok(existing) = tokio_fs::read(&existing).await {
+                current |= hex_digest(&existing).eq_ignore_ascii_case(entry.sha256.trim());
+            }
+        }
+        if current {
+            summary.unchanged += 1;
+            continue;
+        }
+        let bytes = match entry_url(manifest_url, &manifest, entry) {
+            Ok(url) => fetch(&client, &url).await,
+            Err(err) => Err(err),
+        };
+        let bytes = match bytes {
+            Ok(bytes) => bytes,
+            Err(reason) => {
+                rejected.push(RejectedCard {
+                    name: entry.name.clone(),
+                    reason,
+                });
+                continue;
+            }
+        };
+        let digest = hex_digest(&bytes);
+        if !digest.eq_ignore_ascii_case(entry.sha256.trim()) {
+            quarantine(&quarantine_dir, &entry.name, &bytes).await?;
+            rejected.push(RejectedCard {
+                name: entry.name.clone(),
+                reason: format!(
+                    "SHA-256 {digest} does not match the manifest ({}).",
+                    entry.sha256.trim()
+                ),
+            });
+            continue;
+        }
+        let target = destination.join(&entry.name);
+        let partial = destination.join(format!("{}.download", entry.name));
+        tokio_fs::write(&partial, &bytes)
+            .await
+            .map_err(|e| format!("Failed to write {}: {e}", partial.display()))?;
+        tokio_fs::rename(&partial, &target)
+            .await
+            .map_err(|e| format!("Failed to place {}: {e}", target.display()))?;
+        let note = if staged {
+            format!("Staged {} for review (verified).", entry.name)
+        } else {
+            format!("Downloaded {} (verified).", entry.name)
+        };
+        log_line(app, &note).await;
+        summary.downloaded += 1;
+    }
+
+    for card in &rejected {
+        log_line(
+            app,
+            &format!("Rejected character {}: {}", card.name, card.reason),
+        )
+        .await;
+    }
+    summary.rejected = rejected.len();
+    if !rejected.is_empty() {
+        tokio_fs::create_dir_all(&quarantine_dir)
+            .await
+            .map_err(|e| format!("Failed to create {}: {e}", quarantine_dir.display()))?;
+        let report = serde_json::to_string_pretty(&rejected).map_err(|e| e.to_string())?;
+        tokio_fs::write(quarantine_dir.join("rejected.json"), report)
+            .await
+            .map_err(|e| format!("Failed to write the quarantine report: {e}"))?;
+        summary.quarantine = Some(quarantine_dir.display().to_string());
+    }
+    Ok(summary)
+}
+
+async fn move_file(source: &Path, target: &Path) -> Result<(), String> {
+    if tokio_fs::rename(source, target).await.is_ok() {
+        return Ok(());
+    }
+    tokio_fs::copy(source, target)
+        .await
+        .map_err(|e| format!("Failed to copy {}: {e}", source.display()))?;
+    tokio_fs::remove_file(source)
+        .await
+        .map_err(|e| format!("Failed to remove {}: {e}", source.display()))
+}
+
+async fn card_files(dir: &Path) -> Vec<(String, std::fs::Metadata)> {
+    let mut files = Vec::new();
+    let Ok(mut entries) = tokio_fs::read_dir(dir).await else {
+        return files;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !valid_name(&name) {
+            continue;
+        }
+        if let Ok(meta) = entry.metadata().await {
+            if meta.is_file() {
+                files.push((name, meta));
+            }
+        }
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    files
+}
+
+pub(crate) async fn staging() -> Result<Staging, String> {
+    let staging = Staging(TempSpace::allocate("characters", 0)?);
+    let cards = staging.cards();
+    tokio_fs::create_dir_all(&cards)
+        .await
+        .map_err(|e| format!("Failed to create {}: {e}", cards.display()))?;
+    Ok(staging)
+}
+
+pub(crate) async fn stage_downloads(
+    app: &AppHandle,
+    silly: &Path,
+    staging: Staging,
+) -> Result<usize, String> {
+    let live = live_dir(silly);
+    let review = review_dir();
+    tokio_fs::create_dir_all(&review)
+        .await
+        .map_err(|e| format!("Failed to create {}: {e}", review.display()))?;
+    let downloaded = staging.cards();
+    let mut staged = 0;
+    for (name, _) in card_files(&downloaded).await {
+        let path = downloaded.join(&name);
+        let current = tokio_fs::read(live.join(&name)).await.ok();
+        if current.is_some() && current == tokio_fs::read(&path).await.ok() {
+            continue;
+        }
+        move_file(&path, &review.join(&name)).await?;
+        log_line(app, &format!("Staged {name} for review.")).await;
+        staged += 1;
+    }
+    Ok(staged)
+}
+
+fn text(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+fn preview(card: &Value) -> CardPreview {
+    let data = card
+        .get("data")
+        .filter(|data| data.is_object())
+        .unwrap_or(card);
+    CardPreview {
+        name: text(data, "name"),
+        creator: text(data, "creator"),
+        version: text(data, "character_version"),
+        spec: text(card, "spec").map(|spec| match text(card, "spec_version") {
+            Some(version) => format!("{spec} {version}"),
+            None => spec,
+        }),
+        tags: data
+            .get("tags")
+            .and_then(Value::as_array)
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        description: text(data, "description")
+            .map(|text| text.chars().take(PREVIEW_DESCRIPTION_CHARS).collect()),
+    }
+}
+
+fn staged_path(file: &str) -> Result<PathBuf, String> {
+    if !valid_name(file) {
+        return Err(format!("{file} is not a staged character card."));
+    }
+    let path = review_dir().join(file);
+    if !path.is_file() {
+        return Err(format!("{file} is not waiting for review."));
+    }
+    Ok(path)
+}
+
+#[tauri::command]
+pub(crate) async fn list_staged_characters() -> Result<Vec<StagedCharacter>, LauncherError> {
+    load_env();
+    let live = live_dir(&silly_dir()?);
+    let review = review_dir();
+    let mut staged = Vec::new();
+    for (file, meta) in card_files(&review).await {
+        let path = review.join(&file);
+        let raw = tokio_fs::read(&path).await.unwrap_or_default();
+        let card = if file.to_ascii_lowercase().ends_with(".png") {
+            integrity::embedded_card(&raw)
+        } else {
+            serde_json::from_slice(&raw).ok()
+        };
+        staged.push(StagedCharacter {
+            replaces: live.join(&file).is_file(),
+            size: meta.len(),
+            staged_at: meta
+                .modified()
+                .ok()
+                .map(|time| DateTime::<Local>::from(time).to_rfc3339()),
+            preview: card.as_ref().map(preview),
+            problem: integrity::problem(&path, &raw),
+            file,
+        });
+    }
+    Ok(staged)
+}
+
+#[tauri::command]
+pub(crate) async fn approve_staged_character(
+    app: AppHandle,
+    file: String,
+) -> Result<String, LauncherError> {
+    load_env();
+    let source = staged_path(&file)?;
+    let raw = tokio_fs::read(&source)
+        .await
+        .map_err(|e| format!("Failed to read {}: {e}", source.display()))?;
+    if let Some(problem) = integrity::problem(&source, &raw) {
+        return Err(format!("{file} is damaged ({problem}); reject it instead.").into());
+    }
+    let live = live_dir(&silly_dir()?);
+    tokio_fs::create_dir_all(&live)
+        .await
+        .map_err(|e| format!("Failed to create {}: {e}", live.display()))?;
+    let target = live.join(&file);
+    let partial = live.join(format!("{file}.download"));
+    tokio_fs::write(&partial, &raw)
+        .await
+        .map_err(|e| format!("Failed to write {}: {e}", partial.display()))?;
+    tokio_fs::rename(&partial, &target)
+        .await
+        .map_err(|e| format!("Failed to place {}: {e}", target.display()))?;
+    tokio_fs::remove_file(&source)
+        .await
+        .map_err(|e| format!("Failed to remove {}: {e}", source.display()))?;
+    let message = format!("Approved {file}; it is now in {}.", live.display());
+    log_line(&app, &message).await;
+    Ok(message)
+}
+
+#[tauri::command]
+pub(crate) async fn reject_staged_character(
+    app: AppHandle,
+    file: String,
+) -> Result<String, LauncherError> {
+    load_env();
+    let source = staged_path(&file)?;
+    tokio_fs::remove_file(&source)
+        .await
+        .map_err(|e| format!("Failed to remove {}: {e}", source.display()))?;
+    let message = format!("Rejected {file}; it was not added to SillyTavern.");
+    log_line(&app, &message).await;
+    Ok(message)
+}
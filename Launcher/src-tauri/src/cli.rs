@@ -0,0 +1,331 @@
+use std::{
+    env,
+    io::Write,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    backup, certs, finalize_stash, launch, load_env,
+    operation::{self, Operation},
+    prompt::{self, PromptAction},
+    repo, run_character_sync, shutdown, silly_dir, update_vendor, ServerState, UpdateStatus,
+};
+
+const EXIT_OK: i32 = 0;
+const EXIT_FAILED: i32 = 1;
+const EXIT_NEEDS_ATTENTION: i32 = 3;
+
+static HEADLESS: AtomicBool = AtomicBool::new(false);
+static START_MINIMIZED: AtomicBool = AtomicBool::new(false);
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+#[derive(Parser)]
+#[command(
+    name = "weylandtavern-launcher",
+    version,
+    about = "WeylandTavern launcher"
+)]
+struct Cli {
+    #[arg(long, help = "Run a subcommand without opening a window")]
+    headless: bool,
+    #[arg(
+        long,
+        conflicts_with = "headless",
+        help = "Start WeylandTavern in the tray without showing the window"
+    )]
+    start_minimized: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "Only report what updates, stash handling, restores, and node_modules repairs would do"
+    )]
+    dry_run: bool,
+    #[arg(long, hide = true)]
+    working_dir: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Option<HeadlessCommand>,
+}
+
+#[derive(Subcommand, Clone)]
+pub(crate) enum HeadlessCommand {
+    #[command(about = "Start WeylandTavern and keep it running until interrupted")]
+    Start {
+        #[arg(long, help = "Skip npm install even if it would normally run")]
+        force: bool,
+    },
+    #[command(about = "Pull the latest WeylandTavern version")]
+    Update {
+        #[arg(long, help = "Stash local changes before pulling")]
+        overwrite: bool,
+        #[arg(long, help = "Retry with a stash when the plain pull fails")]
+        retry_with_stash: bool,
+        #[arg(long, value_enum, help = "What to do with stashed changes afterwards")]
+        stash: Option<StashChoice>,
+    },
+    #[command(about = "Run the character updater")]
+    Sync,
+    #[command(about = "Copy SillyTavern's data folder and config.yaml into BACKUP_DIR")]
+    Backup,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub(crate) enum StashChoice {
+    Restore,
+    Discard,
+    Keep,
+}
+
+pub(crate) fn parse() -> Option<HeadlessCommand> {
+    let cli = Cli::parse();
+    if let Some(dir) = &cli.working_dir {
+        if let Err(err) = env::set_current_dir(dir) {
+            eprintln!("Failed to switch to {}: {err}", dir.display());
+        }
+    }
+    START_MINIMIZED.store(cli.start_minimized, Ordering::Relaxed);
+    DRY_RUN.store(cli.dry_run, Ordering::Relaxed);
+    match (cli.headless, cli.command) {
+        (true, Some(command)) => {
+            HEADLESS.store(true, Ordering::Relaxed);
+            Some(command)
+        }
+        (true, None) => Cli::command()
+            .error(
+                ErrorKind::MissingSubcommand,
+                "--headless needs one of: start, update, sync, backup",
+            )
+            .exit(),
+        (false, Some(_)) => Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "subcommands only run together with --headless",
+            )
+            .exit(),
+        (false, None) => None,
+    }
+}
+
+pub(crate) fn is_headless() -> bool {
+    HEADLESS.load(Ordering::Relaxed)
+}
+
+pub(crate) fn start_minimized() -> bool {
+    START_MINIMIZED.load(Ordering::Relaxed)
+}
+
+pub(crate) fn dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LaunchOptions {
+    start_minimized: bool,
+    dry_run: bool,
+}
+
+#[tauri::command]
+pub(crate) fn launch_options() -> LaunchOptions {
+    LaunchOptions {
+        start_minimized: start_minimized(),
+        dry_run: dry_run(),
+    }
+}
+
+pub(crate) fn print_event<T: Serialize>(event: &str, data: T) {
+    let line = json!({ "event": event, "data": data });
+    let mut stdout = std::io::stdout().lock();
+    let _ = writeln!(stdout, "{line}");
+    let _ = stdout.flush();
+}
+
+fn print_result<T: Serialize>(command: &str, ok: bool, data: T) {
+    print_event(
+        "result",
+        json!({ "command": command, "ok": ok, "result": data }),
+    );
+}
+
+fn print_error(command: &str, err: &str) -> i32 {
+    print_result(command, false, json!({ "error": err }));
+    EXIT_FAILED
+}
+
+async fn wait_for_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut term) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = term.recv() => {}
+            }
+            return;
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+async fn start(app: &AppHandle, force: bool) -> i32 {
+    certs::spawn_monitor(app.clone());
+    let operation = match operation::begin(app, Operation::ServerStart) {
+        Ok(guard) => guard,
+        Err(err) => return print_error("start", &err.to_string()),
+    };
+    if let Err(err) = launch(app, app.state::<ServerState>(), force).await {
+        let retry_force = match prompt::for_start_failure(&err).answer() {
+            Some(PromptAction::ForceStart) => true,
+            Some(PromptAction::RetryInstall | PromptAction::RetryStart) => force,
+            _ => return print_error("start", &err.to_string()),
+        };
+        if let Err(err) = launch(app, app.state::<ServerState>(), retry_force).await {
+            return print_error("start", &err.to_string());
+        }
+    }
+    drop(operation);
+    let url = app
+        .state::<ServerState>()
+        .inner()
+        .hooks
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|ctx| ctx.url.clone());
+    print_event("ready", json!({ "url": url }));
+
+    let crashed = async {
+        loop {
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            if app
+                .state::<ServerState>()
+                .inner()
+                .child
+                .lock()
+                .unwrap()
+                .is_none()
+            {
+                break;
+            }
+        }
+    };
+    tokio::select! {
+        _ = wait_for_signal() => {
+            shutdown(app, false).await;
+            print_result("start", true, json!({ "stopped": "signal" }));
+            EXIT_OK
+        }
+        _ = crashed => {
+            print_error("start", "WeylandTavern exited unexpectedly.")
+        }
+    }
+}
+
+async fn update(
+    app: &AppHandle,
+    overwrite: bool,
+    retry_with_stash: bool,
+    stash: Option<StashChoice>,
+) -> i32 {
+    let mut response = match update_vendor(app.clone(), overwrite, None).await {
+        Ok(response) => response,
+        Err(err) => return print_error("update", &err.to_string()),
+    };
+    let answer = |response: &crate::UpdateResponse| {
+        response.prompt.as_ref().and_then(|prompt| prompt.answer())
+    };
+    if response.repo_state.is_some() && answer(&response) == Some(PromptAction::RepairRepo) {
+        let repaired = match operation::begin(app, Operation::RepoRepair) {
+            Ok(_operation) => repo::run_repair(app).await.map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+        if let Err(err) = repaired {
+            return print_error("update", &err);
+        }
+        response = match update_vendor(app.clone(), overwrite, None).await {
+            Ok(response) => response,
+            Err(err) => return print_error("update", &err.to_string()),
+        };
+    }
+    if matches!(response.status, UpdateStatus::NeedRetry)
+        && (retry_with_stash || answer(&response) == Some(PromptAction::RetryWithStash))
+    {
+        response = match update_vendor(app.clone(), true, None).await {
+            Ok(response) => response,
+            Err(err) => return print_error("update", &err.to_string()),
+        };
+    }
+
+    if response.stash_used {
+        let choice = stash.or(match answer(&response) {
+            Some(PromptAction::RestoreStash) => Some(StashChoice::Restore),
+            Some(PromptAction::DiscardStash) => Some(StashChoice::Discard),
+            _ => None,
+        });
+        let revert = match choice {
+            Some(StashChoice::Restore) => Some(true),
+            Some(StashChoice::Discard) => Some(false),
+            Some(StashChoice::Keep) | None => None,
+        };
+        if let Some(revert) = revert {
+            if let Err(err) = finalize_stash(app.clone(), revert, None).await {
+                return print_error("update", &err.to_string());
+            }
+        }
+    }
+
+    let code = match response.status {
+        UpdateStatus::Success
+        | UpdateStatus::UpToDate
+        | UpdateStatus::Offline
+        | UpdateStatus::DryRun => EXIT_OK,
+        UpdateStatus::NeedRetry => EXIT_NEEDS_ATTENTION,
+        UpdateStatus::Failed => EXIT_FAILED,
+    };
+    print_result("update", code == EXIT_OK, &response);
+    code
+}
+
+pub(crate) async fn run(app: &AppHandle, command: HeadlessCommand) -> i32 {
+    load_env();
+    match command {
+        HeadlessCommand::Start { force } => start(app, force).await,
+        HeadlessCommand::Update {
+            overwrite,
+            retry_with_stash,
+            stash,
+        } => update(app, overwrite, retry_with_stash, stash).await,
+        HeadlessCommand::Sync => match run_character_sync(app.clone()).await {
+            Ok(response) => {
+                let ok = response.success;
+                print_result("sync", ok, &response);
+                if ok {
+                    EXIT_OK
+                } else {
+                    EXIT_FAILED
+                }
+            }
+            Err(err) => print_error("sync", &err.to_string()),
+        },
+        HeadlessCommand::Backup => {
+            let result = match (operation::begin(app, Operation::Backup), silly_dir()) {
+                (Err(err), _) => Err(err.to_string()),
+                (Ok(_operation), Ok(silly)) => backup::create(app, &silly).await,
+                (Ok(_), Err(err)) => Err(err.to_string()),
+            };
+            match result {
+                Ok(report) => {
+                    print_result("backup", true, &report);
+                    EXIT_OK
+                }
+                Err(err) => print_error("backup", &err),
+            }
+        }
+    }
+}
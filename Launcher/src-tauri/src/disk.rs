@@ -0,0 +1,118 @@
+use std::{env, path::Path};
+
+use crate::error::{ErrorKind, LauncherError};
+
+const MIB: u64 = 1024 * 1024;
+
+#[derive(Clone, Copy)]
+pub(crate) enum DiskOperation {
+    VendorClone,
+    GitPull,
+    NpmInstall,
+    CharacterSync,
+}
+
+impl DiskOperation {
+    fn env_key(self) -> &'static str {
+        match self {
+            Self::VendorClone => "DISK_REQUIRED_CLONE_MB",
+            Self::GitPull => "DISK_REQUIRED_GIT_MB",
+            Self::NpmInstall => "DISK_REQUIRED_NPM_MB",
+            Self::CharacterSync => "DISK_REQUIRED_CHARACTER_SYNC_MB",
+        }
+    }
+
+    fn default_mb(self) -> u64 {
+        match self {
+            Self::VendorClone => 1024,
+            Self::GitPull => 256,
+            Self::NpmInstall => 1024,
+            Self::CharacterSync => 256,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::VendorClone => "downloading WeylandTavern",
+            Self::GitPull => "the vendor update",
+            Self::NpmInstall => "npm install",
+            Self::CharacterSync => "the character sync",
+        }
+    }
+
+    fn required_bytes(self) -> u64 {
+        env::var(self.env_key())
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .unwrap_or(self.default_mb())
+            .saturating_mul(MIB)
+    }
+}
+
+fn existing_ancestor(path: &Path) -> &Path {
+    path.ancestors()
+        .find(|candidate| !candidate.as_os_str().is_empty() && candidate.exists())
+        .unwrap_or(Path::new("."))
+}
+
+#[cfg(unix)]
+pub(crate) fn available_bytes(path: &Path) -> Result<u64, String> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let target = existing_ancestor(path);
+    let c_path = CString::new(target.as_os_str().as_bytes()).map_err(|e| e.to_string())?;
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+    #[allow(clippy::unnecessary_cast)]
+    Ok(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+#[cfg(windows)]
+pub(crate) fn available_bytes(path: &Path) -> Result<u64, String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::{core::PCWSTR, Win32::Storage::FileSystem::GetDiskFreeSpaceExW};
+
+    let target = existing_ancestor(path);
+    let wide: Vec<u16> = target
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut available = 0u64;
+    unsafe { GetDiskFreeSpaceExW(PCWSTR(wide.as_ptr()), Some(&mut available), None, None) }
+        .map_err(|e| format!("GetDiskFreeSpaceExW failed: {e}"))?;
+    Ok(available)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    if bytes >= 1024 * MIB {
+        format!("{:.1} GB", bytes as f64 / (1024 * MIB) as f64)
+    } else {
+        format!("{} MB", bytes / MIB)
+    }
+}
+
+pub(crate) fn ensure_space(path: &Path, operation: DiskOperation) -> Result<(), LauncherError> {
+    let required = operation.required_bytes();
+    if required == 0 {
+        return Ok(());
+    }
+    let Ok(available) = available_bytes(path) else {
+        return Ok(());
+    };
+    if available >= required {
+        return Ok(());
+    }
+    Err(LauncherError::new(
+        ErrorKind::InsufficientDisk,
+        format!(
+            "Not enough disk space for {}: {} free on the volume holding {}, about {} needed.",
+            operation.describe(),
+            format_bytes(available),
+            path.display(),
+            format_bytes(required)
+        ),
+    ))
+}
@@ -0,0 +1,236 @@
+use std::env;
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::LauncherError;
+#[cfg(target_os = "linux")]
+use crate::log_line;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum DisplayServer {
+    #[cfg(target_os = "linux")]
+    Wayland,
+    #[cfg(target_os = "linux")]
+    X11,
+    #[cfg(not(target_os = "linux"))]
+    Native,
+    #[cfg(target_os = "linux")]
+    Unknown,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DisplayCapabilities {
+    display_server: DisplayServer,
+    desktop: Option<String>,
+    client_side_decorations: bool,
+    window_positioning: bool,
+    app_id: String,
+    desktop_entry: Option<String>,
+}
+
+#[cfg(target_os = "linux")]
+fn read_setting(key: &str) -> Option<String> {
+    env::var(key)
+        .ok()
+        .map(|value| value.trim().to_ascii_lowercase())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(target_os = "linux")]
+fn detect_display_server() -> DisplayServer {
+    match env::var("GDK_BACKEND").ok().as_deref().map(str::trim) {
+        Some("x11") => return DisplayServer::X11,
+        Some("wayland") => return DisplayServer::Wayland,
+        _ => {}
+    }
+    let session = read_setting("XDG_SESSION_TYPE");
+    if session.as_deref() == Some("wayland") || env::var_os("WAYLAND_DISPLAY").is_some() {
+        DisplayServer::Wayland
+    } else if session.as_deref() == Some("x11") || env::var_os("DISPLAY").is_some() {
+        DisplayServer::X11
+    } else {
+        DisplayServer::Unknown
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn detect_display_server() -> DisplayServer {
+    DisplayServer::Native
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn supports_positioning() -> bool {
+    detect_display_server() != DisplayServer::Wayland
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn supports_positioning() -> bool {
+    true
+}
+
+#[cfg(target_os = "linux")]
+fn set_default(key: &str, value: &str) {
+    if env::var_os(key).is_none() {
+        env::set_var(key, value);
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn prepare_environment() {
+    match read_setting("LINUX_DISPLAY_BACKEND").as_deref() {
+        Some("x11") => set_default("GDK_BACKEND", "x11"),
+        Some("wayland") => set_default("GDK_BACKEND", "wayland"),
+        _ => {}
+    }
+    match read_setting("LINUX_CLIENT_DECORATIONS").as_deref() {
+        Some("true") => set_default("GTK_CSD", "1"),
+        Some("false") => set_default("GTK_CSD", "0"),
+        _ => {}
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn prepare_environment() {}
+
+#[cfg(target_os = "linux")]
+fn client_side_decorations(server: DisplayServer) -> bool {
+    server == DisplayServer::Wayland || env::var("GTK_CSD").ok().as_deref() == Some("1")
+}
+
+#[cfg(not(target_os = "linux"))]
+fn client_side_decorations(_server: DisplayServer) -> bool {
+    false
+}
+
+#[cfg(target_os = "linux")]
+fn applications_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|base| base.join("applications"))
+}
+
+#[cfg(target_os = "linux")]
+fn desktop_entry_path(app_id: &str) -> Option<PathBuf> {
+    applications_dir().map(|dir| dir.join(format!("{app_id}.desktop")))
+}
+
+#[cfg(target_os = "linux")]
+fn installed_desktop_entry(app_id: &str) -> Option<String> {
+    desktop_entry_path(app_id)
+        .filter(|path| path.exists())
+        .map(|path| path.display().to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn escape_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+        .replace('\r', "\\r")
+}
+
+#[cfg(target_os = "linux")]
+fn exec_arg(arg: &str) -> String {
+    let mut quoted = String::from('"');
+    for ch in arg.chars() {
+        match ch {
+            '"' | '`' | '$' | '\\' => {
+                quoted.push('\\');
+                quoted.push(ch);
+            }
+            '%' => quoted.push_str("%%"),
+            _ => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    escape_value(&quoted)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn installed_desktop_entry(_app_id: &str) -> Option<String> {
+    None
+}
+
+#[tauri::command]
+pub(crate) async fn display_capabilities(
+    app: AppHandle,
+) -> Result<DisplayCapabilities, LauncherError> {
+    let display_server = detect_display_server();
+    let app_id = app.config().identifier.clone();
+    Ok(DisplayCapabilities {
+        display_server,
+        desktop: env::var("XDG_CURRENT_DESKTOP")
+            .ok()
+            .filter(|value| !value.trim().is_empty()),
+        client_side_decorations: client_side_decorations(display_server),
+        window_positioning: supports_positioning(),
+        desktop_entry: installed_desktop_entry(&app_id),
+        app_id,
+    })
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub(crate) async fn install_desktop_entry(app: AppHandle) -> Result<String, LauncherError> {
+    let app_id = app.config().identifier.clone();
+    let name = app
+        .config()
+        .product_name
+        .clone()
+        .unwrap_or_else(|| "WeylandTavern".into());
+    let exe = env::current_exe().map_err(|e| e.to_string())?;
+    let working_dir = env::current_dir().map_err(|e| e.to_string())?;
+    let wm_class = exe
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| app_id.clone());
+    let icon = ["icons/icon.png", "src-tauri/icons/icon.png"]
+        .iter()
+        .map(|candidate| working_dir.join(candidate))
+        .find(|candidate| candidate.exists());
+
+    let mut entry = format!(
+        "[Desktop Entry]\nType=Application\nName={}\nExec={}\nPath={}\nStartupWMClass={}\nTerminal=false\nCategories=Game;Utility;\n",
+        escape_value(&name),
+        exec_arg(&exe.to_string_lossy()),
+        escape_value(&working_dir.to_string_lossy()),
+        escape_value(&wm_class)
+    );
+    if let Some(icon) = icon {
+        entry.push_str(&format!("Icon={}\n", escape_value(&icon.to_string_lossy())));
+    }
+
+    let path = desktop_entry_path(&app_id)
+        .ok_or("Unable to locate the applications directory (HOME is not set).")?;
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tokio::fs::write(&path, entry)
+        .await
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    log_line(
+        &app,
+        &format!(
+            "Installed desktop entry {} for taskbar grouping.",
+            path.display()
+        ),
+    )
+    .await;
+    Ok(path.display().to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+#[tauri::command]
+pub(crate) async fn install_desktop_entry() -> Result<String, LauncherError> {
+    Err("Desktop entries are only used on Linux.".into())
+}
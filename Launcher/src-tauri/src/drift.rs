@@ -0,0 +1,236 @@
+use std::{
+    env, fs as stdfs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+use crate::{events, health, instance, log_line, parse_port, silly_env_port, ServerState};
+
+const DEFAULT_INTERVAL_SECONDS: u64 = 5;
+const WATCHED_FILES: &[&str] = &[".env", "config.yaml"];
+
+#[derive(Clone, PartialEq, Eq)]
+struct ConfigSnapshot {
+    hashes: Vec<Option<String>>,
+    env_port: Option<u16>,
+    yaml_port: Option<u16>,
+    yaml_listen: Option<bool>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DriftChange {
+    key: &'static str,
+    previous: Option<String>,
+    current: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ConfigDrift {
+    files: Vec<String>,
+    changes: Vec<DriftChange>,
+    running_port: u16,
+    expected_port: u16,
+    reconciled: bool,
+    restart_required: bool,
+    message: String,
+}
+
+fn watch_interval() -> Option<Duration> {
+    let seconds = env::var("CONFIG_WATCH_SECONDS")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL_SECONDS);
+    (seconds > 0).then(|| Duration::from_secs(seconds))
+}
+
+fn file_hash(path: &Path) -> Option<String> {
+    let bytes = stdfs::read(path).ok()?;
+    Some(format!("{:x}", Sha256::digest(bytes)))
+}
+
+fn read_yaml(silly_dir: &Path) -> (Option<u16>, Option<bool>) {
+    let Some(yaml) = stdfs::read_to_string(silly_dir.join("config.yaml"))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str::<serde_yaml::Value>(&raw).ok())
+    else {
+        return (None, None);
+    };
+    let port = yaml
+        .get("port")
+        .and_then(|value| value.as_u64())
+        .and_then(|port| u16::try_from(port).ok())
+        .filter(|port| *port != 0);
+    let listen = yaml.get("listen").and_then(|value| value.as_bool());
+    (port, listen)
+}
+
+fn snapshot(silly_dir: &Path) -> ConfigSnapshot {
+    let (yaml_port, yaml_listen) = read_yaml(silly_dir);
+    ConfigSnapshot {
+        hashes: WATCHED_FILES
+            .iter()
+            .map(|name| file_hash(&silly_dir.join(name)))
+            .collect(),
+        env_port: silly_env_port(silly_dir).ok().flatten(),
+        yaml_port,
+        yaml_listen,
+    }
+}
+
+fn changes(previous: &ConfigSnapshot, current: &ConfigSnapshot) -> Vec<DriftChange> {
+    let mut changes = Vec::new();
+    let mut compare = |key, before: Option<String>, after: Option<String>| {
+        if before != after {
+            changes.push(DriftChange {
+                key,
+                previous: before,
+                current: after,
+            });
+        }
+    };
+    compare(
+        ".env PORT",
+        previous.env_port.map(|port| port.to_string()),
+        current.env_port.map(|port| port.to_string()),
+    );
+    compare(
+        "config.yaml port",
+        previous.yaml_port.map(|port| port.to_string()),
+        current.yaml_port.map(|port| port.to_string()),
+    );
+    compare(
+        "config.yaml listen",
+        previous.yaml_listen.map(|listen| listen.to_string()),
+        current.yaml_listen.map(|listen| listen.to_string()),
+    );
+    changes
+}
+
+fn expected_port(snapshot: &ConfigSnapshot, running: u16) -> u16 {
+    snapshot
+        .env_port
+        .or_else(|| {
+            env::var("SERVER_PORT")
+                .ok()
+                .and_then(|value| parse_port(&value))
+        })
+        .unwrap_or(running)
+}
+
+async fn responds(url: &str) -> bool {
+    match health::HealthProbe::from_env(url) {
+        Ok(probe) => probe.check().await.is_ok(),
+        Err(_) => false,
+    }
+}
+
+async fn reconcile(app: &AppHandle, host: &str, port: u16) -> Option<String> {
+    let url = format!("http://{host}:{port}/");
+    if !responds(&url).await {
+        return None;
+    }
+    let state = app.state::<ServerState>();
+    if let Some(ctx) = state.inner().hooks.lock().unwrap().as_mut() {
+        ctx.port = port;
+        ctx.url = url.clone();
+    }
+    if let Some(liveness) = state.inner().liveness.lock().unwrap().take() {
+        let _ = liveness.send(());
+    }
+    if let Ok(probe) = health::HealthProbe::from_env(&url) {
+        if let Some(handle) = health::spawn_liveness(app, probe) {
+            state.inner().liveness.lock().unwrap().replace(handle);
+        }
+    }
+    instance::publish_url(Some(&url));
+    events::emit(app, "server-ready", &url);
+    Some(url)
+}
+
+async fn check(app: &AppHandle, silly_dir: &Path, previous: &ConfigSnapshot) -> ConfigSnapshot {
+    let current = snapshot(silly_dir);
+    if current == *previous {
+        return current;
+    }
+    let files: Vec<String> = WATCHED_FILES
+        .iter()
+        .zip(previous.hashes.iter().zip(&current.hashes))
+        .filter(|(_, (before, after))| before != after)
+        .map(|(name, _)| silly_dir.join(name).display().to_string())
+        .collect();
+    let changes = changes(previous, &current);
+    if changes.is_empty() {
+        return current;
+    }
+
+    let endpoint = app
+        .state::<ServerState>()
+        .inner()
+        .hooks
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|ctx| (ctx.host.clone(), ctx.port, ctx.url.clone()));
+    let Some((host, running_port, running_url)) = endpoint else {
+        return current;
+    };
+    let expected = expected_port(&current, running_port);
+    let mut reconciled = false;
+    let yaml_port_changed = changes
+        .iter()
+        .any(|change| change.key == "config.yaml port");
+    let message = if expected == running_port && yaml_port_changed {
+        format!(
+            "SillyTavern's config.yaml port changed, but the launcher starts it with --port {running_port}. Set PORT in SillyTavern's .env to move it."
+        )
+    } else if expected == running_port {
+        "SillyTavern changed its configuration; the launcher's endpoint is unaffected.".to_string()
+    } else if responds(&running_url).await {
+        format!(
+            "SillyTavern now expects port {expected} but is still serving on {running_port}. The launcher will switch on the next restart and redirect the old port."
+        )
+    } else if let Some(url) = reconcile(app, &host, expected).await {
+        reconciled = true;
+        format!("SillyTavern moved to {url}; the launcher now follows the new address.")
+    } else {
+        format!(
+            "SillyTavern now expects port {expected}, but nothing answers there yet. Restart WeylandTavern to apply it."
+        )
+    };
+    log_line(app, &format!("Configuration drift: {message}")).await;
+    let drift = ConfigDrift {
+        files,
+        changes,
+        running_port,
+        expected_port: expected,
+        reconciled,
+        restart_required: expected != running_port && !reconciled,
+        message,
+    };
+    events::emit(app, "config-drift", &drift);
+    current
+}
+
+pub(crate) fn spawn_watcher(app: &AppHandle, silly_dir: PathBuf) -> Option<oneshot::Sender<()>> {
+    let interval = watch_interval()?;
+    let (stop_tx, mut stop_rx) = oneshot::channel::<()>();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut last = snapshot(&silly_dir);
+        loop {
+            tokio::select! {
+                _ = &mut stop_rx => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+            last = check(&app, &silly_dir, &last).await;
+        }
+    });
+    Some(stop_tx)
+}
@@ -0,0 +1,115 @@
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::{cli, events, log_line};
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Change {
+    Create,
+    Replace,
+    Remove,
+}
+
+impl Change {
+    fn verb(self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Replace => "replace",
+            Self::Remove => "remove",
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PlannedCommand {
+    command: String,
+    dir: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PlannedFile {
+    change: Change,
+    path: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DryRun {
+    action: &'static str,
+    summary: String,
+    commands: Vec<PlannedCommand>,
+    files: Vec<PlannedFile>,
+    notes: Vec<String>,
+}
+
+impl DryRun {
+    pub(crate) fn new(action: &'static str) -> Self {
+        Self {
+            action,
+            summary: String::new(),
+            commands: Vec::new(),
+            files: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    pub(crate) fn command(&mut self, program: &str, args: &[&str], dir: &Path) {
+        let mut command = program.to_string();
+        for arg in args {
+            command.push(' ');
+            command.push_str(arg);
+        }
+        self.commands.push(PlannedCommand {
+            command,
+            dir: dir.display().to_string(),
+        });
+    }
+
+    pub(crate) fn file(&mut self, change: Change, path: &Path) {
+        self.files.push(PlannedFile {
+            change,
+            path: path.display().to_string(),
+        });
+    }
+
+    pub(crate) fn note(&mut self, note: impl Into<String>) {
+        self.notes.push(note.into());
+    }
+
+    pub(crate) fn summary(&self) -> &str {
+        &self.summary
+    }
+
+    pub(crate) async fn finish(mut self, app: &AppHandle, summary: impl Into<String>) -> Self {
+        self.summary = format!("Dry run: {} Nothing was changed.", summary.into());
+        log_line(app, &self.summary).await;
+        for planned in &self.commands {
+            log_line(
+                app,
+                &format!("  would run: {} (in {})", planned.command, planned.dir),
+            )
+            .await;
+        }
+        for planned in &self.files {
+            log_line(
+                app,
+                &format!("  would {}: {}", planned.change.verb(), planned.path),
+            )
+            .await;
+        }
+        for note in &self.notes {
+            log_line(app, &format!("  note: {note}")).await;
+        }
+        events::emit(app, "dry-run", &self);
+        self
+    }
+}
+
+pub(crate) fn requested(flag: Option<bool>) -> bool {
+    flag.unwrap_or_else(cli::dry_run)
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::operation::Operation;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ErrorKind {
+    Busy,
+    ServerRunning,
+    ReadOnlyPath,
+    NpmInstallFailed,
+    InsufficientDisk,
+    Offline,
+    Git,
+    RepoState,
+    HealthCheck,
+    NotConfigured,
+    Unprotected,
+    HookFailed,
+    SpawnFailed,
+    Failed,
+}
+
+impl ErrorKind {
+    fn remediation(self) -> Option<&'static str> {
+        match self {
+            Self::Busy => Some("Wait for the current operation to finish, then try again."),
+            Self::ServerRunning => Some("Stop WeylandTavern first."),
+            Self::ReadOnlyPath => Some(
+                "Fix the folder permissions or move WeylandTavern to a folder you can write to.",
+            ),
+            Self::NpmInstallFailed => {
+                Some("Retry the install, or repair node_modules if it keeps failing.")
+            }
+            Self::InsufficientDisk => Some("Free up space on that drive, then try again."),
+            Self::Offline => {
+                Some("Reconnect to the internet, or set OFFLINE_MODE=false if the check is wrong.")
+            }
+            Self::Git => Some("Check the log for git's output and that git works in a terminal."),
+            Self::RepoState => {
+                Some("Run the repository repair, or fix the vendor checkout by hand.")
+            }
+            Self::HealthCheck => {
+                Some("Check the server log for startup errors, then start the server again.")
+            }
+            Self::NotConfigured => Some("Check the paths and settings in .env."),
+            Self::Unprotected => Some(
+                "Set SHARE_PORT to put the token-protected guest proxy in front of it, or set TUNNEL_ALLOW_UNPROTECTED=true.",
+            ),
+            Self::HookFailed => {
+                Some("Check the hook command in .env and its output in the log.")
+            }
+            Self::SpawnFailed => Some(
+                "Check that Node.js works and that SILLYTAVERN_DIR and the logs folder are writable.",
+            ),
+            Self::Failed => None,
+        }
+    }
+
+    fn retryable(self) -> bool {
+        matches!(
+            self,
+            Self::Busy | Self::NpmInstallFailed | Self::Offline | Self::HealthCheck
+        )
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LauncherError {
+    pub(crate) kind: ErrorKind,
+    pub(crate) message: String,
+    pub(crate) details: Option<String>,
+    pub(crate) remediation: Option<String>,
+    pub(crate) retryable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) current_operation: Option<Operation>,
+}
+
+impl LauncherError {
+    pub(crate) fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            details: None,
+            remediation: kind.remediation().map(str::to_string),
+            retryable: kind.retryable(),
+            current_operation: None,
+        }
+    }
+
+    pub(crate) fn busy(operation: Operation) -> Self {
+        let mut err = Self::new(
+            ErrorKind::Busy,
+            format!("The launcher is busy {}.", operation.label()),
+        );
+        err.current_operation = Some(operation);
+        err
+    }
+
+    pub(crate) fn with_details(mut self, details: impl Into<String>) -> Self {
+        let details = details.into();
+        self.details = (!details.trim().is_empty()).then(|| details.trim().to_string());
+        self
+    }
+}
+
+impl fmt::Display for LauncherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.details {
+            Some(details) => write!(f, "{} Details: {details}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl From<String> for LauncherError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorKind::Failed, message)
+    }
+}
+
+impl From<&str> for LauncherError {
+    fn from(message: &str) -> Self {
+        Self::new(ErrorKind::Failed, message)
+    }
+}
+
+impl From<LauncherError> for String {
+    fn from(err: LauncherError) -> Self {
+        err.to_string()
+    }
+}
@@ -0,0 +1,91 @@
+use std::{collections::BTreeMap, sync::Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, EventTarget, Manager, Window};
+
+use crate::{error::LauncherError, repro, switcher};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum EventClass {
+    Lifecycle,
+    Logs,
+    Status,
+    Notices,
+    Settings,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Verbosity {
+    Off,
+    Basic,
+    Full,
+}
+
+type Filters = BTreeMap<EventClass, Verbosity>;
+
+static SUBSCRIPTIONS: Mutex<BTreeMap<String, Filters>> = Mutex::new(BTreeMap::new());
+
+fn classify(event: &str) -> (EventClass, Verbosity) {
+    match event {
+        "log" => (EventClass::Logs, Verbosity::Basic),
+        "log-styled" => (EventClass::Logs, Verbosity::Full),
+        "server-health" => (EventClass::Status, Verbosity::Full),
+        "tunnel-url"
+        | "config-drift"
+        | "certificate-expiry"
+        | "certificate-renewed"
+        | "operation-changed"
+        | "dry-run" => (EventClass::Status, Verbosity::Basic),
+        "update-available" | "chat-recovery" => (EventClass::Notices, Verbosity::Basic),
+        "settings-changed" => (EventClass::Settings, Verbosity::Basic),
+        _ => (EventClass::Lifecycle, Verbosity::Basic),
+    }
+}
+
+// Windows that never called subscribe get everything; lifecycle events
+// (server-ready, server-crashed, prompt, ...) cannot be filtered out.
+fn wants(label: &str, event: &str) -> bool {
+    let (class, needed) = classify(event);
+    if class == EventClass::Lifecycle {
+        return true;
+    }
+    SUBSCRIPTIONS
+        .lock()
+        .unwrap()
+        .get(label)
+        .is_none_or(|filters| filters.get(&class).copied().unwrap_or(Verbosity::Off) >= needed)
+}
+
+pub(crate) fn emit<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    let listening: Vec<String> = app
+        .webview_windows()
+        .into_keys()
+        .filter(|label| !switcher::is_instance_window(label) && wants(label, event))
+        .collect();
+    if listening.is_empty() && !repro::records(event) {
+        return;
+    }
+    let _ = app.emit_filter(event, payload, |target| match target {
+        EventTarget::Window { label }
+        | EventTarget::Webview { label }
+        | EventTarget::WebviewWindow { label }
+        | EventTarget::AnyLabel { label } => listening.contains(label),
+        _ => true,
+    });
+}
+
+pub(crate) fn forget(label: &str) {
+    SUBSCRIPTIONS.lock().unwrap().remove(label);
+}
+
+#[tauri::command]
+pub(crate) async fn subscribe(window: Window, filters: Filters) -> Result<(), LauncherError> {
+    tracing::debug!(window = window.label(), "event subscription updated");
+    SUBSCRIPTIONS
+        .lock()
+        .unwrap()
+        .insert(window.label().to_string(), filters);
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use std::{env, time::Duration};
+
+use tauri::AppHandle;
+use tokio::sync::oneshot;
+
+use crate::{
+    events, log_line,
+    retry::{Attempted, OperationClass, RetryPolicy},
+};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 5;
+
+#[derive(Clone)]
+pub(crate) struct HealthProbe {
+    url: String,
+    statuses: Vec<(u16, u16)>,
+    status_label: String,
+    body: Option<String>,
+    client: reqwest::Client,
+}
+
+fn read_setting(key: &str) -> Option<String> {
+    env::var(key)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_statuses(value: &str) -> Result<Vec<(u16, u16)>, String> {
+    let invalid = || format!("Invalid HEALTH_EXPECTED_STATUS value '{value}'.");
+    let mut ranges = Vec::new();
+    for part in value
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+    {
+        let lower = part.to_ascii_lowercase();
+        let range = if let Some(class) = lower.strip_suffix("xx") {
+            let class = class.parse::<u16>().map_err(|_| invalid())?;
+            (class * 100, class * 100 + 99)
+        } else if let Some((start, end)) = part.split_once('-') {
+            (
+                start.trim().parse::<u16>().map_err(|_| invalid())?,
+                end.trim().parse::<u16>().map_err(|_| invalid())?,
+            )
+        } else {
+            let code = part.parse::<u16>().map_err(|_| invalid())?;
+            (code, code)
+        };
+        if range.0 > range.1 || !(100..=599).contains(&range.0) || range.1 > 599 {
+            return Err(invalid());
+        }
+        ranges.push(range);
+    }
+    if ranges.is_empty() {
+        return Err(invalid());
+    }
+    Ok(ranges)
+}
+
+impl HealthProbe {
+    pub(crate) fn from_env(base_url: &str) -> Result<Self, String> {
+        let path = read_setting("HEALTH_PATH").unwrap_or_else(|| "/".into());
+        let url = format!(
+            "{}/{}",
+            base_url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        );
+        let status_label = read_setting("HEALTH_EXPECTED_STATUS").unwrap_or_else(|| "2xx".into());
+        let timeout = read_setting("HEALTH_TIMEOUT_SECONDS")
+            .and_then(|value| value.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_TIMEOUT_SECS);
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(timeout))
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            url,
+            statuses: parse_statuses(&status_label)?,
+            status_label,
+            body: read_setting("HEALTH_EXPECT_BODY"),
+            client,
+        })
+    }
+
+    pub(crate) fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub(crate) async fn check(&self) -> Result<(), String> {
+        let response = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .map_err(|e| format!("request failed: {e}"))?;
+        let status = response.status().as_u16();
+        if !self
+            .statuses
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&status))
+        {
+            return Err(format!(
+                "expected status {}, got {status}",
+                self.status_label
+            ));
+        }
+        if let Some(expected) = &self.body {
+            let body = response
+                .text()
+                .await
+                .map_err(|e| format!("failed to read response body: {e}"))?;
+            if !body.contains(expected.as_str()) {
+                return Err(format!("response body does not contain '{expected}'"));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn wait_until_healthy(&self) -> Attempted<Result<(), String>> {
+        RetryPolicy::for_class(OperationClass::Health)
+            .run(|_| self.check(), |result| result.is_err())
+            .await
+    }
+}
+
+fn liveness_interval() -> Option<Duration> {
+    read_setting("HEALTH_INTERVAL_SECONDS")
+        .and_then(|value| value.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+}
+
+pub(crate) fn spawn_liveness(app: &AppHandle, probe: HealthProbe) -> Option<oneshot::Sender<()>> {
+    let interval = liveness_interval()?;
+    let (stop_tx, mut stop_rx) = oneshot::channel::<()>();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut healthy = true;
+        loop {
+            tokio::select! {
+                _ = &mut stop_rx => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+            let result = probe.check().await;
+            if result.is_ok() == healthy {
+                continue;
+            }
+            healthy = result.is_ok();
+            match result {
+                Ok(()) => log_line(&app, "Liveness check: server is responding again.").await,
+                Err(err) => {
+                    log_line(
+                        &app,
+                        &format!("Liveness check failed for {}: {err}", probe.url()),
+                    )
+                    .await
+                }
+            }
+            events::emit(&app, "server-health", healthy);
+        }
+    });
+    Some(stop_tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_statuses_accepts_classes_ranges_and_codes() {
+        assert_eq!(parse_statuses("2xx"), Ok(vec![(200, 299)]));
+        assert_eq!(
+            parse_statuses("200, 301-302,4XX"),
+            Ok(vec![(200, 200), (301, 302), (400, 499)])
+        );
+    }
+
+    #[test]
+    fn parse_statuses_rejects_bad_values() {
+        for value in ["", " , ", "abc", "600", "302-301", "99", "2xx-3xx"] {
+            assert!(parse_statuses(value).is_err(), "{value} should be rejected");
+        }
+    }
+}
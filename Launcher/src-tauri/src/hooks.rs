@@ -0,0 +1,149 @@
+use std::{env, path::PathBuf, process::Stdio, time::Duration};
+
+use tauri::AppHandle;
+use tokio::{io::AsyncRead, process::Command as TokioCommand, time::timeout};
+
+use crate::{apply_node_env, log_line, sanitize::LossyLines, trace};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+#[derive(Clone, Copy)]
+pub(crate) enum Hook {
+    PreStart,
+    PostStart,
+    PreStop,
+    PostStop,
+}
+
+impl Hook {
+    fn name(self) -> &'static str {
+        match self {
+            Self::PreStart => "pre_start",
+            Self::PostStart => "post_start",
+            Self::PreStop => "pre_stop",
+            Self::PostStop => "post_stop",
+        }
+    }
+
+    fn env_key(self) -> &'static str {
+        match self {
+            Self::PreStart => "HOOK_PRE_START",
+            Self::PostStart => "HOOK_POST_START",
+            Self::PreStop => "HOOK_PRE_STOP",
+            Self::PostStop => "HOOK_POST_STOP",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct HookContext {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) url: String,
+    pub(crate) silly_dir: PathBuf,
+}
+
+fn hook_timeout() -> Duration {
+    let secs = env::var("HOOK_TIMEOUT_SECONDS")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
+#[cfg(windows)]
+fn shell_command(line: &str) -> TokioCommand {
+    let mut cmd = TokioCommand::new("cmd");
+    cmd.arg("/C").raw_arg(line);
+    cmd
+}
+
+#[cfg(not(windows))]
+fn shell_command(line: &str) -> TokioCommand {
+    let mut cmd = TokioCommand::new("sh");
+    cmd.args(["-c", line]);
+    cmd
+}
+
+async fn stream_lines(app: AppHandle, name: &'static str, reader: impl AsyncRead + Unpin) {
+    let mut lines = LossyLines::new(reader);
+    while let Some(line) = lines.next_line().await {
+        log_line(&app, &format!("[{name}] {line}")).await;
+    }
+}
+
+pub(crate) async fn run(app: &AppHandle, hook: Hook, ctx: &HookContext) -> Result<(), String> {
+    let Some(line) = env::var(hook.env_key())
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    else {
+        return Ok(());
+    };
+    let name = hook.name();
+    log_line(app, &format!("Running {name} hook: {line}")).await;
+
+    let port = ctx.port.to_string();
+    let mut cmd = shell_command(&line);
+    apply_node_env(&mut cmd);
+    cmd.current_dir(&ctx.silly_dir)
+        .env("PORT", &port)
+        .env("ST_PORT", &port)
+        .env("SERVER_HOST", &ctx.host)
+        .env("SERVER_PORT", &port)
+        .env("SERVER_URL", &ctx.url)
+        .env("SILLYTAVERN_DIR", &ctx.silly_dir)
+        .env("LAUNCHER_HOOK", name)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    trace::spawning(&cmd);
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start {name} hook: {e}"))?;
+
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(tauri::async_runtime::spawn(stream_lines(
+            app.clone(),
+            name,
+            stdout,
+        )));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(tauri::async_runtime::spawn(stream_lines(
+            app.clone(),
+            name,
+            stderr,
+        )));
+    }
+
+    let limit = hook_timeout();
+    let status = match timeout(limit, child.wait()).await {
+        Ok(status) => status.map_err(|e| format!("{name} hook failed: {e}"))?,
+        Err(_) => {
+            let _ = child.kill().await;
+            return Err(format!(
+                "{name} hook timed out after {} seconds.",
+                limit.as_secs()
+            ));
+        }
+    };
+    for reader in readers {
+        let _ = reader.await;
+    }
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{name} hook exited with {status}."))
+    }
+}
+
+pub(crate) async fn run_logged(app: &AppHandle, hook: Hook, ctx: &HookContext) {
+    if let Err(err) = run(app, hook, ctx).await {
+        log_line(app, &err).await;
+    }
+}
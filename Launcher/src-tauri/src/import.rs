@@ -0,0 +1,525 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs as stdfs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, State};
+use tokio::fs as tokio_fs;
+
+use crate::{
+    error::LauncherError,
+    installs, load_env, log_line,
+    operation::{self, Operation},
+    silly_dir,
+    temp::TempSpace,
+    userdata, ServerState,
+};
+
+const DEFAULT_USER: &str = "default-user";
+const CHARACTERS_DIR: &str = "characters";
+const CHATS_DIR: &str = "chats";
+const PERSONAS_DIR: &str = "User Avatars";
+const WORLDS_DIR: &str = "worlds";
+const SETTINGS_FILE: &str = "settings.json";
+const CARD_EXTENSIONS: &[&str] = &["png", "json"];
+const AVATAR_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "gif"];
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+enum ImportKind {
+    Character,
+    Chat,
+    Persona,
+    WorldInfo,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+enum SourceLayout {
+    UserData,
+    CardFolder,
+}
+
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Collision {
+    #[default]
+    Skip,
+    Rename,
+    Overwrite,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+enum ImportAction {
+    Imported,
+    Renamed,
+    Overwritten,
+    Skipped,
+    Failed,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ImportItem {
+    id: String,
+    kind: ImportKind,
+    name: String,
+    target: String,
+    exists: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ImportPreview {
+    source: String,
+    layout: SourceLayout,
+    archive: bool,
+    items: Vec<ImportItem>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportOutcome {
+    id: String,
+    kind: ImportKind,
+    target: String,
+    action: ImportAction,
+    detail: Option<String>,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ImportReport {
+    source: String,
+    imported: usize,
+    renamed: usize,
+    overwritten: usize,
+    skipped: usize,
+    failed: usize,
+    items: Vec<ImportOutcome>,
+}
+
+struct Source {
+    display: String,
+    root: PathBuf,
+    layout: SourceLayout,
+    archive: bool,
+    _extracted: Option<TempSpace>,
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+}
+
+fn files_in(dir: &Path, extensions: &[&str]) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = stdfs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| extension(path).is_some_and(|ext| extensions.contains(&ext.as_str())))
+        .collect();
+    files.sort();
+    files
+}
+
+fn user_root(dir: &Path) -> Option<PathBuf> {
+    let mut base = dir.to_path_buf();
+    let children: Vec<PathBuf> = stdfs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    if let [only] = children.as_slice() {
+        if only.is_dir() {
+            base = only.clone();
+        }
+    }
+    [
+        base.clone(),
+        base.join("data"),
+        base.join("SillyTavern").join("data"),
+    ]
+    .into_iter()
+    .flat_map(|candidate| [candidate.join(DEFAULT_USER), candidate])
+    .find(|candidate| candidate.join(CHARACTERS_DIR).is_dir())
+}
+
+fn extract(archive: PathBuf) -> Result<TempSpace, String> {
+    let file = stdfs::File::open(&archive)
+        .map_err(|e| format!("Failed to open {}: {e}", archive.display()))?;
+    let mut zip = zip::ZipArchive::new(file)
+        .map_err(|e| format!("{} is not a readable zip archive: {e}", archive.display()))?;
+    let size = (0..zip.len())
+        .filter_map(|index| zip.by_index(index).ok().map(|entry| entry.size()))
+        .sum();
+    let space = TempSpace::allocate("import", size)?;
+    zip.extract(space.path())
+        .map_err(|e| format!("Failed to unpack {}: {e}", archive.display()))?;
+    Ok(space)
+}
+
+async fn open(path: &str) -> Result<Source, String> {
+    let given = PathBuf::from(path.trim());
+    let display = given.display().to_string();
+    let archive = given.is_file() && extension(&given).as_deref() == Some("zip");
+    let extracted = if archive {
+        let archive = given.clone();
+        Some(
+            tauri::async_runtime::spawn_blocking(move || extract(archive))
+                .await
+                .map_err(|e| e.to_string())??,
+        )
+    } else if given.is_dir() {
+        None
+    } else {
+        return Err(format!("{display} is neither a folder nor a zip archive."));
+    };
+    let dir = extracted
+        .as_ref()
+        .map(|space| space.path().to_path_buf())
+        .unwrap_or(given);
+    let (root, layout) = match user_root(&dir) {
+        Some(root) => (root, SourceLayout::UserData),
+        None => (dir, SourceLayout::CardFolder),
+    };
+    Ok(Source {
+        display,
+        root,
+        layout,
+        archive,
+        _extracted: extracted,
+    })
+}
+
+fn relative_id(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_slice(&stdfs::read(path).ok()?).ok()
+}
+
+fn persona_names(root: &Path) -> BTreeMap<String, String> {
+    read_json(&root.join(SETTINGS_FILE))
+        .and_then(|settings| settings.pointer("/power_user/personas").cloned())
+        .and_then(|personas| serde_json::from_value(personas).ok())
+        .unwrap_or_default()
+}
+
+fn discover(source: &Source, dest: &Path) -> Vec<ImportItem> {
+    let mut found: Vec<(ImportKind, PathBuf, String, PathBuf)> = Vec::new();
+    match source.layout {
+        SourceLayout::UserData => {
+            let root = &source.root;
+            for card in files_in(&root.join(CHARACTERS_DIR), CARD_EXTENSIONS) {
+                let target = Path::new(CHARACTERS_DIR).join(file_name(&card));
+                found.push((ImportKind::Character, card.clone(), stem(&card), target));
+            }
+            let mut folders: Vec<PathBuf> = stdfs::read_dir(root.join(CHATS_DIR))
+                .into_iter()
+                .flatten()
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect();
+            folders.sort();
+            for folder in folders {
+                let character = file_name(&folder);
+                for chat in files_in(&folder, &["jsonl"]) {
+                    let target = Path::new(CHATS_DIR).join(&character).join(file_name(&chat));
+                    let name = format!("{character} – {}", stem(&chat));
+                    found.push((ImportKind::Chat, chat, name, target));
+                }
+            }
+            let names = persona_names(root);
+            for avatar in files_in(&root.join(PERSONAS_DIR), AVATAR_EXTENSIONS) {
+                let file = file_name(&avatar);
+                let name = names.get(&file).cloned().unwrap_or_else(|| stem(&avatar));
+                let target = Path::new(PERSONAS_DIR).join(&file);
+                found.push((ImportKind::Persona, avatar, name, target));
+            }
+            for world in files_in(&root.join(WORLDS_DIR), &["json"]) {
+                let target = Path::new(WORLDS_DIR).join(file_name(&world));
+                found.push((ImportKind::WorldInfo, world.clone(), stem(&world), target));
+            }
+        }
+        SourceLayout::CardFolder => {
+            for file in files_in(&source.root, CARD_EXTENSIONS) {
+                let is_world = extension(&file).as_deref() == Some("json")
+                    && read_json(&file).is_some_and(|value| value.get("entries").is_some());
+                let (kind, dir) = if is_world {
+                    (ImportKind::WorldInfo, WORLDS_DIR)
+                } else {
+                    (ImportKind::Character, CHARACTERS_DIR)
+                };
+                let target = Path::new(dir).join(file_name(&file));
+                found.push((kind, file.clone(), stem(&file), target));
+            }
+        }
+    }
+    found
+        .into_iter()
+        .map(|(kind, path, name, target)| ImportItem {
+            id: relative_id(&source.root, &path),
+            kind,
+            name,
+            exists: dest.join(&target).exists(),
+            target: relative_id(Path::new(""), &target),
+        })
+        .collect()
+}
+
+fn destination() -> Result<PathBuf, String> {
+    let silly = silly_dir()?;
+    Ok(userdata::dir(&silly).join(DEFAULT_USER))
+}
+
+fn same_folder(source: &Source, dest: &Path) -> bool {
+    match (source.root.canonicalize(), dest.canonicalize()) {
+        (Ok(source), Ok(dest)) => source == dest,
+        _ => false,
+    }
+}
+
+fn free_name(dir: &Path, name: &str) -> String {
+    let path = Path::new(name);
+    let stem = stem(path);
+    let suffix = extension(path)
+        .map(|ext| format!(".{ext}"))
+        .unwrap_or_default();
+    (2..)
+        .map(|index| format!("{stem} ({index}){suffix}"))
+        .find(|candidate| !dir.join(candidate).exists())
+        .unwrap_or_else(|| name.to_string())
+}
+
+async fn merge_personas(
+    source: &Path,
+    dest: &Path,
+    copied: &[(String, String)],
+    overwrite: bool,
+) -> Result<(), String> {
+    let Some(settings) = read_json(&source.join(SETTINGS_FILE)) else {
+        return Ok(());
+    };
+    let path = dest.join(SETTINGS_FILE);
+    let Some(mut target) = read_json(&path) else {
+        return Ok(());
+    };
+    let mut changed = false;
+    for section in ["personas", "persona_descriptions"] {
+        let Some(from) = settings
+            .pointer(&format!("/power_user/{section}"))
+            .and_then(Value::as_object)
+        else {
+            continue;
+        };
+        let Some(power_user) = target.get_mut("power_user").and_then(Value::as_object_mut) else {
+            continue;
+        };
+        let into = power_user
+            .entry(section)
+            .or_insert_with(|| Value::Object(Default::default()));
+        let Some(into) = into.as_object_mut() else {
+            continue;
+        };
+        for (original, imported) in copied {
+            if let Some(value) = from.get(original) {
+                if overwrite || !into.contains_key(imported) {
+                    into.insert(imported.clone(), value.clone());
+                    changed = true;
+                }
+            }
+        }
+    }
+    if !changed {
+        return Ok(());
+    }
+    let text = serde_json::to_string_pretty(&target).map_err(|e| e.to_string())?;
+    tokio_fs::write(&path, text)
+        .await
+        .map_err(|e| format!("Failed to update {}: {e}", path.display()))
+}
+
+#[tauri::command]
+pub(crate) async fn scan_import_source(path: String) -> Result<ImportPreview, LauncherError> {
+    load_env();
+    let dest = destination()?;
+    let source = open(&path).await?;
+    if same_folder(&source, &dest) {
+        return Err("That is the install the launcher already manages.".into());
+    }
+    let items = discover(&source, &dest);
+    Ok(ImportPreview {
+        source: source.display.clone(),
+        layout: source.layout,
+        archive: source.archive,
+        items,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn import_from_source(
+    app: AppHandle,
+    state: State<'_, ServerState>,
+    path: String,
+    items: Option<Vec<String>>,
+    collision: Option<Collision>,
+) -> Result<ImportReport, LauncherError> {
+    let _operation = operation::begin(&app, Operation::DataImport)?;
+    load_env();
+    installs::ensure_stopped(state.inner(), "importing into it")?;
+    let collision = collision.unwrap_or_default();
+    let dest = destination()?;
+    let source = open(&path).await?;
+    if same_folder(&source, &dest) {
+        return Err("That is the install the launcher already manages.".into());
+    }
+    let wanted: Option<BTreeSet<String>> = items.map(|ids| ids.into_iter().collect());
+    let mut selected: Vec<ImportItem> = discover(&source, &dest)
+        .into_iter()
+        .filter(|item| wanted.as_ref().is_none_or(|ids| ids.contains(&item.id)))
+        .collect();
+    selected.sort_by_key(|item| item.kind);
+
+    let mut report = ImportReport {
+        source: source.display.clone(),
+        ..ImportReport::default()
+    };
+    let mut renamed_characters: BTreeMap<String, String> = BTreeMap::new();
+    let mut personas: Vec<(String, String)> = Vec::new();
+    for item in selected {
+        let mut target = PathBuf::from(&item.target);
+        if item.kind == ImportKind::Chat {
+            let mut parts = item.target.splitn(3, '/');
+            if let (Some(_), Some(character), Some(file)) =
+                (parts.next(), parts.next(), parts.next())
+            {
+                if let Some(renamed) = renamed_characters.get(character) {
+                    target = Path::new(CHATS_DIR).join(renamed).join(file);
+                }
+            }
+        }
+        let mut action = ImportAction::Imported;
+        if dest.join(&target).exists() {
+            match collision {
+                Collision::Skip => action = ImportAction::Skipped,
+                Collision::Overwrite => action = ImportAction::Overwritten,
+                Collision::Rename => {
+                    let dir = target.parent().map(Path::to_path_buf).unwrap_or_default();
+                    target = dir.join(free_name(&dest.join(&dir), &file_name(&target)));
+                    action = ImportAction::Renamed;
+                }
+            }
+        }
+        let mut detail = None;
+        if !matches!(action, ImportAction::Skipped) {
+            let from = source.root.join(&item.id);
+            let to = dest.join(&target);
+            let copied = match to.parent() {
+                Some(dir) => tokio_fs::create_dir_all(dir)
+                    .await
+                    .map_err(|e| e.to_string()),
+                None => Ok(()),
+            };
+            let copied = match copied {
+                Ok(()) => tokio_fs::copy(&from, &to)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| e.to_string()),
+                Err(err) => Err(err),
+            };
+            match copied {
+                Ok(()) => match item.kind {
+                    ImportKind::Character if matches!(action, ImportAction::Renamed) => {
+                        renamed_characters.insert(item.name.clone(), stem(&target));
+                    }
+                    ImportKind::Persona => {
+                        personas.push((file_name(Path::new(&item.id)), file_name(&target)));
+                    }
+                    _ => {}
+                },
+                Err(err) => {
+                    action = ImportAction::Failed;
+                    detail = Some(err);
+                }
+            }
+        }
+        match action {
+            ImportAction::Imported => report.imported += 1,
+            ImportAction::Renamed => report.renamed += 1,
+            ImportAction::Overwritten => report.overwritten += 1,
+            ImportAction::Skipped => report.skipped += 1,
+            ImportAction::Failed => report.failed += 1,
+        }
+        report.items.push(ImportOutcome {
+            id: item.id,
+            kind: item.kind,
+            target: relative_id(Path::new(""), &target),
+            action,
+            detail,
+        });
+    }
+
+    if matches!(source.layout, SourceLayout::UserData) && !personas.is_empty() {
+        let overwrite = matches!(collision, Collision::Overwrite);
+        if let Err(err) = merge_personas(&source.root, &dest, &personas, overwrite).await {
+            log_line(&app, &format!("Persona names were not merged: {err}")).await;
+        }
+    }
+    for outcome in report
+        .items
+        .iter()
+        .filter(|outcome| matches!(outcome.action, ImportAction::Failed))
+    {
+        log_line(
+            &app,
+            &format!(
+                "Import of {} failed: {}",
+                outcome.id,
+                outcome.detail.as_deref().unwrap_or("unknown error")
+            ),
+        )
+        .await;
+    }
+    log_line(
+        &app,
+        &format!(
+            "Imported from {}: {} new, {} renamed, {} overwritten, {} skipped, {} failed.",
+            report.source,
+            report.imported,
+            report.renamed,
+            report.overwritten,
+            report.skipped,
+            report.failed
+        ),
+    )
+    .await;
+    Ok(report)
+}
@@ -0,0 +1,242 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::{
+    error::{ErrorKind, LauncherError},
+    log_line, run_git,
+    state::{self, ChangeOrigin},
+    ServerState,
+};
+
+const INSTALLS_KEY: &str = "installs";
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Install {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) path: String,
+    pub(crate) repo: String,
+    pub(crate) remote: Option<String>,
+    pub(crate) branch: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct InstallRegistry {
+    active: Option<String>,
+    installs: Vec<Install>,
+}
+
+pub(crate) fn active() -> Option<Install> {
+    let registry: InstallRegistry = state::load(INSTALLS_KEY);
+    let id = registry.active?;
+    registry
+        .installs
+        .into_iter()
+        .find(|install| install.id == id)
+}
+
+pub(crate) fn pull_source() -> Option<(String, String)> {
+    let install = active()?;
+    let remote = install.remote?;
+    Some((remote, install.branch.unwrap_or_else(|| "HEAD".into())))
+}
+
+pub(crate) fn relocate_active(path: &Path, repo: &Path) -> Result<(), String> {
+    state::update(
+        INSTALLS_KEY,
+        ChangeOrigin::Launcher,
+        |registry: &mut InstallRegistry| {
+            let active = registry.active.clone();
+            if let Some(install) = registry
+                .installs
+                .iter_mut()
+                .find(|install| Some(&install.id) == active.as_ref())
+            {
+                install.path = path.display().to_string();
+                install.repo = repo.display().to_string();
+            }
+        },
+    )
+    .map(|_| ())
+}
+
+fn slug(name: &str) -> String {
+    let slug: String = name
+        .trim()
+        .to_ascii_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    slug.split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn locate(path: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(path.trim());
+    [path.clone(), path.join("SillyTavern")]
+        .into_iter()
+        .find(|candidate| candidate.join("server.js").is_file())
+        .map(|found| found.canonicalize().unwrap_or(found))
+        .ok_or_else(|| {
+            format!(
+                "{} does not contain SillyTavern (no server.js there or in a SillyTavern subfolder).",
+                path.display()
+            )
+        })
+}
+
+async fn repo_root(silly: &Path) -> Result<PathBuf, String> {
+    let output = run_git(silly, &["rev-parse", "--show-toplevel"]).await?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} is not inside a git checkout, so it cannot be updated.",
+            silly.display()
+        ));
+    }
+    let root = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    Ok(root.canonicalize().unwrap_or(root))
+}
+
+pub(crate) fn ensure_stopped(state: &ServerState, action: &str) -> Result<(), LauncherError> {
+    if state.child.lock().unwrap().is_some() {
+        return Err(LauncherError::new(
+            ErrorKind::ServerRunning,
+            format!("Stop WeylandTavern before {action}."),
+        ));
+    }
+    Ok(())
+}
+
+fn optional(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[tauri::command]
+pub(crate) async fn list_installs() -> Result<InstallRegistry, LauncherError> {
+    Ok(state::load(INSTALLS_KEY))
+}
+
+#[tauri::command]
+pub(crate) async fn add_install(
+    app: AppHandle,
+    name: String,
+    path: String,
+    remote: Option<String>,
+    branch: Option<String>,
+) -> Result<InstallRegistry, LauncherError> {
+    let id = slug(&name);
+    if id.is_empty() {
+        return Err("Give the install a name with at least one letter or digit.".into());
+    }
+    let silly = locate(&path)?;
+    let repo = repo_root(&silly).await?;
+    let install = Install {
+        id: id.clone(),
+        name: name.trim().to_string(),
+        path: silly.display().to_string(),
+        repo: repo.display().to_string(),
+        remote: optional(remote),
+        branch: optional(branch),
+    };
+    let current: InstallRegistry = state::load(INSTALLS_KEY);
+    if let Some(existing) = current
+        .installs
+        .iter()
+        .find(|existing| existing.id == id || existing.path == install.path)
+    {
+        return Err(format!(
+            "\"{}\" is already registered for {}.",
+            existing.name, existing.path
+        )
+        .into());
+    }
+    let (registry, _) = state::update(
+        INSTALLS_KEY,
+        ChangeOrigin::Ui,
+        |registry: &mut InstallRegistry| {
+            registry.installs.push(install.clone());
+            registry.clone()
+        },
+    )?;
+    log_line(
+        &app,
+        &format!(
+            "Registered install \"{}\" at {}.",
+            install.name, install.path
+        ),
+    )
+    .await;
+    Ok(registry)
+}
+
+#[tauri::command]
+pub(crate) async fn remove_install(
+    app: AppHandle,
+    state: State<'_, ServerState>,
+    id: String,
+) -> Result<InstallRegistry, LauncherError> {
+    let current: InstallRegistry = state::load(INSTALLS_KEY);
+    if !current.installs.iter().any(|install| install.id == id) {
+        return Err(format!("No install is registered as \"{id}\".").into());
+    }
+    if current.active.as_deref() == Some(id.as_str()) {
+        ensure_stopped(state.inner(), "removing the active install")?;
+    }
+    let (registry, _) = state::update(
+        INSTALLS_KEY,
+        ChangeOrigin::Ui,
+        |registry: &mut InstallRegistry| {
+            registry.installs.retain(|install| install.id != id);
+            if registry.active.as_deref() == Some(id.as_str()) {
+                registry.active = None;
+            }
+            registry.clone()
+        },
+    )?;
+    log_line(
+        &app,
+        &format!("Removed install \"{id}\" from the list; its files were left in place."),
+    )
+    .await;
+    Ok(registry)
+}
+
+#[tauri::command]
+pub(crate) async fn set_active_install(
+    app: AppHandle,
+    state: State<'_, ServerState>,
+    id: Option<String>,
+) -> Result<InstallRegistry, LauncherError> {
+    ensure_stopped(state.inner(), "switching installs")?;
+    let current: InstallRegistry = state::load(INSTALLS_KEY);
+    if let Some(id) = &id {
+        if !current.installs.iter().any(|install| &install.id == id) {
+            return Err(format!("No install is registered as \"{id}\".").into());
+        }
+    }
+    let (registry, _) = state::update(
+        INSTALLS_KEY,
+        ChangeOrigin::Ui,
+        |registry: &mut InstallRegistry| {
+            registry.active = id.clone();
+            registry.clone()
+        },
+    )?;
+    let message = match active() {
+        Some(install) => format!(
+            "Switched to install \"{}\" ({}).",
+            install.name, install.path
+        ),
+        None => "Switched back to SILLYTAVERN_DIR from .env.".to_string(),
+    };
+    log_line(&app, &message).await;
+    Ok(registry)
+}
@@ -0,0 +1,339 @@
+use std::{
+    env, fs as stdfs,
+    io::{ErrorKind, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener as StdTcpListener},
+    path::{Path, PathBuf},
+    process,
+    sync::OnceLock,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::{autostart::START_MINIMIZED_FLAG, events, installs, log_line, tray};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(800);
+const LOCK_PREFIX: &str = "weylandtavern-";
+const LOCK_SUFFIX: &str = ".lock";
+const MAX_MESSAGE_BYTES: u64 = 64 * 1024;
+
+static LOCK_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LockRecord {
+    pid: u32,
+    port: u16,
+    silly_dir: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RunningInstance {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) silly_dir: String,
+    pub(crate) pid: u32,
+    pub(crate) url: Option<String>,
+    pub(crate) current: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct Forwarded {
+    args: Vec<String>,
+    cwd: Option<String>,
+}
+
+pub(crate) struct Instance {
+    listener: StdTcpListener,
+}
+
+fn silly_dir_key() -> String {
+    let configured = installs::active()
+        .map(|install| install.path)
+        .or_else(|| env::var("SILLYTAVERN_DIR").ok())
+        .unwrap_or_else(|| "./vendor/WeylandTavern/SillyTavern".into());
+    let path = PathBuf::from(&configured);
+    path.canonicalize()
+        .or_else(|_| env::current_dir().map(|cwd| cwd.join(&path)))
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+fn profile_name(silly_dir: &str) -> String {
+    if let Some(name) = env::var("PROFILE_NAME")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .or_else(|| installs::active().map(|install| install.name))
+    {
+        return name;
+    }
+    let path = Path::new(silly_dir);
+    let folder = |path: &Path| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+    };
+    match folder(path) {
+        Some(name) if name.eq_ignore_ascii_case("SillyTavern") => {
+            path.parent().and_then(folder).unwrap_or(name)
+        }
+        Some(name) => name,
+        None => silly_dir.to_string(),
+    }
+}
+
+fn lock_path(silly_dir: &str) -> PathBuf {
+    let digest = format!("{:x}", Sha256::digest(silly_dir.as_bytes()));
+    env::temp_dir().join(format!("{LOCK_PREFIX}{}{LOCK_SUFFIX}", &digest[..16]))
+}
+
+fn read_record(path: &Path) -> Option<LockRecord> {
+    let raw = stdfs::read_to_string(path).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn write_record(path: &Path, record: &LockRecord) -> std::io::Result<()> {
+    let mut file = stdfs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?;
+    file.write_all(serde_json::to_string(record)?.as_bytes())
+}
+
+async fn connect(port: u16) -> Option<TcpStream> {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(addr))
+        .await
+        .ok()?
+        .ok()
+}
+
+async fn forward(port: u16) -> Result<(), String> {
+    let mut stream = connect(port)
+        .await
+        .ok_or("the running launcher did not answer")?;
+    let message = Forwarded {
+        args: env::args().skip(1).collect(),
+        cwd: env::current_dir().ok().map(|cwd| cwd.display().to_string()),
+    };
+    let mut line = serde_json::to_string(&message).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stream
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut reply = String::new();
+    tokio::time::timeout(
+        CONNECT_TIMEOUT,
+        BufReader::new(stream).read_line(&mut reply),
+    )
+    .await
+    .map_err(|_| "the running launcher did not confirm".to_string())?
+    .map_err(|e| e.to_string())?;
+    if reply.trim() == "ok" {
+        Ok(())
+    } else {
+        Err("the running launcher rejected the request".into())
+    }
+}
+
+pub(crate) async fn claim(headless: bool) -> Instance {
+    let silly_dir = silly_dir_key();
+    let path = lock_path(&silly_dir);
+    let listener = match StdTcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("Failed to open the single-instance socket: {err}");
+            process::exit(1);
+        }
+    };
+    let port = listener.local_addr().map(|addr| addr.port()).unwrap_or(0);
+    let record = LockRecord {
+        pid: process::id(),
+        port,
+        name: profile_name(&silly_dir),
+        silly_dir: silly_dir.clone(),
+        url: None,
+    };
+
+    for _ in 0..3 {
+        match write_record(&path, &record) {
+            Ok(()) => {
+                let _ = LOCK_PATH.set(path);
+                return Instance { listener };
+            }
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => {}
+            Err(err) => {
+                eprintln!(
+                    "Failed to create the instance lock {}: {err}. Continuing without it.",
+                    path.display()
+                );
+                return Instance { listener };
+            }
+        }
+
+        let existing = match read_record(&path) {
+            Some(existing) => existing,
+            None => {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                match read_record(&path) {
+                    Some(existing) => existing,
+                    None => {
+                        let _ = stdfs::remove_file(&path);
+                        continue;
+                    }
+                }
+            }
+        };
+        if connect(existing.port).await.is_none() {
+            let _ = stdfs::remove_file(&path);
+            continue;
+        }
+        if headless {
+            eprintln!(
+                "WeylandTavern is already running for {} (launcher PID {}). Stop it before running a headless command.",
+                existing.silly_dir, existing.pid
+            );
+            process::exit(1);
+        }
+        match forward(existing.port).await {
+            Ok(()) => {
+                eprintln!(
+                    "WeylandTavern is already running for {} (launcher PID {}); switched to its window.",
+                    existing.silly_dir, existing.pid
+                );
+                process::exit(0);
+            }
+            Err(err) => {
+                eprintln!(
+                    "WeylandTavern is already running for {} (launcher PID {}), but {err}.",
+                    existing.silly_dir, existing.pid
+                );
+                process::exit(1);
+            }
+        }
+    }
+
+    eprintln!(
+        "Could not take the instance lock {}. Continuing without it.",
+        path.display()
+    );
+    Instance { listener }
+}
+
+async fn handle(app: &AppHandle, stream: TcpStream) {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    if tokio::io::AsyncReadExt::take(BufReader::new(reader), MAX_MESSAGE_BYTES)
+        .read_line(&mut line)
+        .await
+        .is_err()
+    {
+        return;
+    }
+    let Ok(message) = serde_json::from_str::<Forwarded>(&line) else {
+        let _ = writer.write_all(b"invalid\n").await;
+        return;
+    };
+    let _ = writer.write_all(b"ok\n").await;
+
+    let minimized = message.args.iter().any(|arg| arg == START_MINIMIZED_FLAG);
+    if !minimized {
+        tray::show_windows(app);
+    }
+    log_line(
+        app,
+        "Another launch of WeylandTavern was redirected to this window.",
+    )
+    .await;
+    events::emit(app, "instance-forwarded", &message);
+}
+
+impl Instance {
+    pub(crate) fn serve(self, app: &AppHandle) {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if self.listener.set_nonblocking(true).is_err() {
+                return;
+            }
+            let Ok(listener) = TcpListener::from_std(self.listener) else {
+                return;
+            };
+            while let Ok((stream, _)) = listener.accept().await {
+                handle(&app, stream).await;
+            }
+        });
+    }
+}
+
+pub(crate) fn publish_url(url: Option<&str>) {
+    let Some(path) = LOCK_PATH.get() else {
+        return;
+    };
+    let Some(mut record) = read_record(path).filter(|record| record.pid == process::id()) else {
+        return;
+    };
+    record.url = url.map(str::to_string);
+    if let Ok(raw) = serde_json::to_string(&record) {
+        let _ = stdfs::write(path, raw);
+    }
+}
+
+pub(crate) async fn running() -> Vec<RunningInstance> {
+    let Ok(entries) = stdfs::read_dir(env::temp_dir()) else {
+        return Vec::new();
+    };
+    let mut instances = Vec::new();
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let Some(id) = file_name
+            .strip_prefix(LOCK_PREFIX)
+            .and_then(|rest| rest.strip_suffix(LOCK_SUFFIX))
+        else {
+            continue;
+        };
+        let Some(record) = read_record(&entry.path()) else {
+            continue;
+        };
+        let current = record.pid == process::id();
+        if !current && connect(record.port).await.is_none() {
+            continue;
+        }
+        instances.push(RunningInstance {
+            id: id.to_string(),
+            name: if record.name.is_empty() {
+                profile_name(&record.silly_dir)
+            } else {
+                record.name
+            },
+            silly_dir: record.silly_dir,
+            pid: record.pid,
+            url: record.url,
+            current,
+        });
+    }
+    instances.sort_by(|a, b| b.current.cmp(&a.current).then(a.name.cmp(&b.name)));
+    instances
+}
+
+pub(crate) fn release() {
+    let Some(path) = LOCK_PATH.get() else {
+        return;
+    };
+    if read_record(path).is_some_and(|record| record.pid == process::id()) {
+        let _ = stdfs::remove_file(path);
+    }
+}
@@ -0,0 +1,413 @@
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Local;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+use tokio::fs as tokio_fs;
+
+use crate::{
+    backup,
+    dryrun::{self, Change, DryRun},
+    error::LauncherError,
+    load_env, log_line, logs_dir, recovery, silly_dir, userdata,
+};
+
+const REPAIR_DIR: &str = "data-repair";
+const SKIPPED_DIRS: &[&str] = &["backups", "_cache", "_uploads", "thumbnails"];
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const CARD_KEYWORDS: &[&[u8]] = &[b"chara", b"ccv3"];
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum DataIssueKind {
+    Empty,
+    Truncated,
+    InvalidJson,
+    InvalidPng,
+    MissingCard,
+    InvalidCard,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DataIssue {
+    path: String,
+    kind: DataIssueKind,
+    detail: String,
+    backup: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataScanReport {
+    root: String,
+    scanned: usize,
+    issues: Vec<DataIssue>,
+}
+
+type Problem = (DataIssueKind, String);
+
+fn json_problem(err: serde_json::Error, what: &str) -> Problem {
+    if err.is_eof() {
+        (
+            DataIssueKind::Truncated,
+            format!("{what} ends early ({err})."),
+        )
+    } else {
+        (
+            DataIssueKind::InvalidJson,
+            format!("{what} is not valid JSON ({err})."),
+        )
+    }
+}
+
+fn check_jsonl(raw: &[u8]) -> Option<Problem> {
+    let lines: Vec<&[u8]> = raw.split(|byte| *byte == b'\n').collect();
+    let last = lines
+        .iter()
+        .rposition(|line| !line.iter().all(u8::is_ascii_whitespace))?;
+    let invalid: Vec<(usize, serde_json::Error)> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.iter().all(u8::is_ascii_whitespace))
+        .filter_map(|(index, line)| {
+            serde_json::from_slice::<Value>(line)
+                .err()
+                .map(|err| (index, err))
+        })
+        .collect();
+    let (first, _) = invalid.first()?;
+    if invalid.len() == 1 && *first == last {
+        return Some((
+            DataIssueKind::Truncated,
+            format!("The last line ({}) is cut off.", last + 1),
+        ));
+    }
+    Some((
+        DataIssueKind::InvalidJson,
+        format!(
+            "{} line(s) are not valid JSON (first at line {}).",
+            invalid.len(),
+            first + 1
+        ),
+    ))
+}
+
+fn check_card(text: &[u8]) -> Option<Problem> {
+    let decoded = match STANDARD.decode(text.trim_ascii()) {
+        Ok(decoded) => decoded,
+        Err(err) => {
+            return Some((
+                DataIssueKind::InvalidCard,
+                format!("The embedded card is not valid base64 ({err})."),
+            ))
+        }
+    };
+    serde_json::from_slice::<Value>(&decoded).err().map(|err| {
+        let (_, detail) = json_problem(err, "The embedded card");
+        (DataIssueKind::InvalidCard, detail)
+    })
+}
+
+fn png_cards(raw: &[u8]) -> Result<Vec<&[u8]>, Problem> {
+    if !raw.starts_with(PNG_SIGNATURE) {
+        return Err((
+            DataIssueKind::InvalidPng,
+            "The file is not a PNG image.".into(),
+        ));
+    }
+    let mut offset = PNG_SIGNATURE.len();
+    let mut cards = Vec::new();
+    loop {
+        let Some(header) = raw.get(offset..offset + 8) else {
+            return Err((
+                DataIssueKind::Truncated,
+                format!(
+                    "The image stops after {} bytes, before its end marker.",
+                    raw.len()
+                ),
+            ));
+        };
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let kind = &header[4..8];
+        let Some(data) = raw.get(offset + 8..offset + 8 + length) else {
+            return Err((
+                DataIssueKind::Truncated,
+                format!("The image stops inside a chunk after {} bytes.", raw.len()),
+            ));
+        };
+        if kind == b"tEXt" {
+            if let Some(split) = data.iter().position(|byte| *byte == 0) {
+                if CARD_KEYWORDS.contains(&&data[..split]) {
+                    cards.push(&data[split + 1..]);
+                }
+            }
+        }
+        if kind == b"IEND" {
+            break;
+        }
+        offset += 12 + length;
+    }
+    Ok(cards)
+}
+
+fn check_png(raw: &[u8]) -> Option<Problem> {
+    let cards = match png_cards(raw) {
+        Ok(cards) => cards,
+        Err(problem) => return Some(problem),
+    };
+    if cards.is_empty() {
+        return Some((
+            DataIssueKind::MissingCard,
+            "The image has no embedded character card (chara chunk).".into(),
+        ));
+    }
+    cards.into_iter().find_map(check_card)
+}
+
+pub(crate) fn embedded_card(raw: &[u8]) -> Option<Value> {
+    png_cards(raw).ok()?.into_iter().rev().find_map(|text| {
+        let decoded = STANDARD.decode(text.trim_ascii()).ok()?;
+        serde_json::from_slice(&decoded).ok()
+    })
+}
+
+fn in_characters(path: &Path) -> bool {
+    path.parent()
+        .and_then(|dir| dir.file_name())
+        .is_some_and(|name| name == "characters")
+}
+
+fn scanned(path: &Path) -> bool {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json" | "jsonl") => true,
+        Some("png") => in_characters(path),
+        _ => false,
+    }
+}
+
+fn check(path: &Path, raw: &[u8]) -> Option<Problem> {
+    if raw.is_empty() {
+        return Some((DataIssueKind::Empty, "The file is empty.".into()));
+    }
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("jsonl") => check_jsonl(raw),
+        Some("png") => check_png(raw),
+        _ => serde_json::from_slice::<Value>(raw)
+            .err()
+            .map(|err| json_problem(err, "The file")),
+    }
+}
+
+pub(crate) fn problem(path: &Path, raw: &[u8]) -> Option<String> {
+    check(path, raw).map(|(_, detail)| detail)
+}
+
+async fn candidates(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(mut entries) = tokio_fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let Ok(kind) = entry.file_type().await else {
+                continue;
+            };
+            let skipped = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| SKIPPED_DIRS.contains(&name));
+            if kind.is_dir() && !skipped {
+                pending.push(path);
+            } else if kind.is_file() && scanned(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+async fn latest_backup(relative: &Path, file: &Path) -> Option<PathBuf> {
+    for snapshot in backup::snapshots().await {
+        let candidate = snapshot.join(relative);
+        if let Ok(raw) = tokio_fs::read(&candidate).await {
+            if check(&candidate, &raw).is_none() {
+                return Some(candidate);
+            }
+        }
+    }
+    if file.extension().and_then(|ext| ext.to_str()) == Some("jsonl") {
+        return recovery::latest_backup(file).await;
+    }
+    None
+}
+
+#[tauri::command]
+pub(crate) async fn scan_user_data(app: AppHandle) -> Result<DataScanReport, LauncherError> {
+    load_env();
+    let silly = silly_dir()?;
+    let root = userdata::dir(&silly);
+    if !root.is_dir() {
+        return Err(format!("No user data folder at {}.", root.display()).into());
+    }
+    let files = candidates(&root).await;
+    let mut issues = Vec::new();
+    for file in &files {
+        let Ok(raw) = tokio_fs::read(file).await else {
+            continue;
+        };
+        let Some((kind, detail)) = check(file, &raw) else {
+            continue;
+        };
+        let relative = userdata::relative(&silly, file);
+        let backup = latest_backup(&relative, file)
+            .await
+            .map(|path| path.display().to_string());
+        issues.push(DataIssue {
+            path: relative.display().to_string(),
+            kind,
+            detail,
+            backup,
+        });
+    }
+    let message = if issues.is_empty() {
+        format!(
+            "Scanned {} user data file(s); no damage found.",
+            files.len()
+        )
+    } else {
+        format!(
+            "Scanned {} user data file(s); {} need attention.",
+            files.len(),
+            issues.len()
+        )
+    };
+    log_line(&app, &message).await;
+    for issue in &issues {
+        log_line(
+            &app,
+            &format!("Data check: {} – {}", issue.path, issue.detail),
+        )
+        .await;
+    }
+    Ok(DataScanReport {
+        root: root.display().to_string(),
+        scanned: files.len(),
+        issues,
+    })
+}
+
+fn preserved_path(silly: &Path, target: &Path, stamp: &str) -> Result<PathBuf, String> {
+    let relative = userdata::relative(silly, target);
+    if relative.has_root() || relative.is_absolute() {
+        return Err(format!(
+            "{} is outside the user data folder.",
+            target.display()
+        ));
+    }
+    Ok(logs_dir().join(REPAIR_DIR).join(stamp).join(relative))
+}
+
+#[tauri::command]
+pub(crate) async fn restore_user_file(
+    app: AppHandle,
+    path: String,
+    backup: String,
+    dry_run: Option<bool>,
+) -> Result<String, LauncherError> {
+    load_env();
+    let silly = silly_dir()?;
+    if Path::new(&path).has_root() || Path::new(&path).is_absolute() {
+        return Err(format!("{path} must be relative to the SillyTavern folder.").into());
+    }
+    let data = tokio_fs::canonicalize(userdata::dir(&silly))
+        .await
+        .map_err(|e| e.to_string())?;
+    let target = userdata::resolve(&silly, &path);
+    let parent = target
+        .parent()
+        .ok_or_else(|| format!("{path} is not a user data file."))?;
+    let parent = tokio_fs::canonicalize(parent)
+        .await
+        .map_err(|e| format!("{path} not found: {e}"))?;
+    if !parent.starts_with(&data) {
+        return Err(format!("{path} is outside the user data folder.").into());
+    }
+    let source = tokio_fs::canonicalize(&backup)
+        .await
+        .map_err(|e| format!("Backup {backup} not found: {e}"))?;
+    let backups = tokio_fs::canonicalize(backup::backup_root()).await.ok();
+    let allowed = backups.is_some_and(|root| source.starts_with(root))
+        || (source.starts_with(&data)
+            && source
+                .ancestors()
+                .any(|dir| dir.file_name().is_some_and(|name| name == "backups")));
+    if !allowed {
+        return Err("Files can only be restored from launcher or SillyTavern backups.".into());
+    }
+    let contents = tokio_fs::read(&source)
+        .await
+        .map_err(|e| format!("Failed to read {}: {e}", source.display()))?;
+    if let Some((_, detail)) = check(&target, &contents) {
+        return Err(format!("The backup is damaged too: {detail}").into());
+    }
+
+    let stamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let copy = preserved_path(&silly, &target, &stamp)?;
+    if dryrun::requested(dry_run) {
+        let mut plan = DryRun::new("restore_user_file");
+        if target.exists() {
+            plan.file(Change::Create, &copy);
+            plan.file(Change::Replace, &target);
+        } else {
+            plan.file(Change::Create, &target);
+        }
+        let plan = plan
+            .finish(
+                &app,
+                format!("restoring {path} would copy it from {}.", source.display()),
+            )
+            .await;
+        return Ok(plan.summary().to_string());
+    }
+    let mut preserved = None;
+    if target.exists() {
+        if let Some(dir) = copy.parent() {
+            tokio_fs::create_dir_all(dir)
+                .await
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        tokio_fs::copy(&target, &copy)
+            .await
+            .map_err(|e| format!("Failed to preserve {}: {e}", target.display()))?;
+        preserved = Some(copy);
+    }
+    let staging = target.with_file_name(format!(
+        "{}.restoring",
+        target
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    ));
+    tokio_fs::write(&staging, &contents)
+        .await
+        .map_err(|e| format!("Failed to write {}: {e}", staging.display()))?;
+    tokio_fs::rename(&staging, &target)
+        .await
+        .map_err(|e| format!("Failed to replace {}: {e}", target.display()))?;
+
+    let mut message = format!("Restored {path} from {}.", source.display());
+    if let Some(copy) = preserved {
+        message.push_str(&format!(
+            " The damaged copy was saved to {}.",
+            copy.display()
+        ));
+    }
+    log_line(&app, &message).await;
+    Ok(message)
+}
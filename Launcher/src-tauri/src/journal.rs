@@ -0,0 +1,136 @@
+use std::path::PathBuf;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    fs::{self as tokio_fs, OpenOptions},
+    io::AsyncWriteExt,
+};
+
+use crate::error::LauncherError;
+use crate::logs_dir;
+
+const JOURNAL_FILE: &str = "update-journal.jsonl";
+const DETAILS_DIR: &str = "updates";
+const DEFAULT_LIST_LIMIT: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum JournalAction {
+    Pull,
+    StashAndPull,
+    StashPop,
+    StashClear,
+    VendorScript,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JournalEntry {
+    pub(crate) id: String,
+    pub(crate) timestamp: String,
+    pub(crate) action: JournalAction,
+    pub(crate) from_commit: Option<String>,
+    pub(crate) to_commit: Option<String>,
+    pub(crate) result: String,
+    pub(crate) stash_used: bool,
+    pub(crate) message: String,
+    pub(crate) detail_log: Option<String>,
+}
+
+impl JournalEntry {
+    pub(crate) fn new(action: JournalAction) -> Self {
+        let now = Local::now();
+        let suffix: String = (0..2)
+            .map(|_| format!("{:02x}", rand::random::<u8>()))
+            .collect();
+        Self {
+            id: format!("{}-{suffix}", now.format("%Y%m%d-%H%M%S")),
+            timestamp: now.to_rfc3339(),
+            action,
+            from_commit: None,
+            to_commit: None,
+            result: String::new(),
+            stash_used: false,
+            message: String::new(),
+            detail_log: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JournalDetail {
+    entry: JournalEntry,
+    details: Option<String>,
+}
+
+fn journal_path() -> PathBuf {
+    logs_dir().join(JOURNAL_FILE)
+}
+
+pub(crate) async fn detail_path(id: &str) -> Result<PathBuf, String> {
+    let dir = logs_dir().join(DETAILS_DIR);
+    tokio_fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(dir.join(format!("{id}.log")))
+}
+
+pub(crate) async fn append(entry: &JournalEntry) -> Result<(), String> {
+    tokio_fs::create_dir_all(logs_dir())
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    line.push('\n');
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(journal_path())
+        .await
+        .map_err(|e| e.to_string())?;
+    file.write_all(line.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    file.flush().await.map_err(|e| e.to_string())
+}
+
+async fn read_entries() -> Result<Vec<JournalEntry>, String> {
+    let path = journal_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = tokio_fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    Ok(raw
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[tauri::command]
+pub(crate) async fn list_update_journal(
+    limit: Option<usize>,
+) -> Result<Vec<JournalEntry>, LauncherError> {
+    let limit = limit.unwrap_or(DEFAULT_LIST_LIMIT);
+    let mut entries = read_entries().await?;
+    entries.reverse();
+    entries.truncate(limit);
+    Ok(entries)
+}
+
+#[tauri::command]
+pub(crate) async fn read_update_entry(id: String) -> Result<JournalDetail, LauncherError> {
+    let entry = read_entries()
+        .await?
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| format!("No update journal entry with id {id}."))?;
+    let details = match entry.detail_log.as_deref() {
+        Some(path) => tokio_fs::read_to_string(path).await.ok(),
+        None => None,
+    };
+    Ok(JournalDetail { entry, details })
+}
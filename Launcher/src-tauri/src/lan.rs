@@ -0,0 +1,189 @@
+use std::{
+    env,
+    net::{IpAddr, SocketAddr},
+};
+
+use serde::Serialize;
+use tauri::AppHandle;
+#[cfg(windows)]
+use tokio::process::Command as TokioCommand;
+
+#[cfg(windows)]
+use crate::log_line;
+use crate::{error::LauncherError, load_env, parse_port, qr_svg, ServerState};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LanAddress {
+    interface: String,
+    address: String,
+    url: String,
+    qr_svg: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LanAccessInfo {
+    host: String,
+    port: u16,
+    listening_on_lan: bool,
+    addresses: Vec<LanAddress>,
+    firewall_rule: Option<bool>,
+    hint: Option<String>,
+}
+
+#[cfg(windows)]
+fn firewall_rule_name(port: u16) -> String {
+    format!("WeylandTavern (TCP {port})")
+}
+
+fn server_endpoint(state: &ServerState) -> (String, u16) {
+    if let Some(ctx) = state.hooks.lock().unwrap().as_ref() {
+        return (ctx.host.clone(), ctx.port);
+    }
+    (
+        env::var("SERVER_HOST").unwrap_or_else(|_| "127.0.0.1".into()),
+        env::var("SERVER_PORT")
+            .ok()
+            .and_then(|value| parse_port(&value))
+            .unwrap_or(8000),
+    )
+}
+
+fn url_for(ip: IpAddr, port: u16) -> String {
+    format!("http://{}/", SocketAddr::new(ip, port))
+}
+
+fn usable(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => !v4.is_loopback() && !v4.is_link_local(),
+        IpAddr::V6(v6) => !v6.is_loopback() && (v6.segments()[0] & 0xffc0) != 0xfe80,
+    }
+}
+
+#[cfg(windows)]
+async fn firewall_rule_exists(port: u16) -> bool {
+    TokioCommand::new("netsh")
+        .args(["advfirewall", "firewall", "show", "rule"])
+        .arg(format!("name={}", firewall_rule_name(port)))
+        .output()
+        .await
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(not(windows))]
+async fn firewall_rule_exists(_port: u16) -> bool {
+    false
+}
+
+#[tauri::command]
+pub(crate) async fn lan_access_info(
+    state: tauri::State<'_, ServerState>,
+) -> Result<LanAccessInfo, LauncherError> {
+    load_env();
+    let (host, port) = server_endpoint(state.inner());
+    let bind = host.parse::<IpAddr>().ok();
+    let listening_on_lan = bind
+        .map(|ip| ip.is_unspecified() || usable(&ip))
+        .unwrap_or(false);
+
+    let interfaces = if_addrs::get_if_addrs().map_err(|e| e.to_string())?;
+    let mut addresses: Vec<LanAddress> = interfaces
+        .into_iter()
+        .filter(|iface| usable(&iface.ip()))
+        .filter(|iface| match bind {
+            Some(bind) if !bind.is_unspecified() => bind == iface.ip(),
+            Some(IpAddr::V4(_)) => iface.ip().is_ipv4(),
+            _ => true,
+        })
+        .map(|iface| {
+            let ip = iface.ip();
+            let url = url_for(ip, port);
+            LanAddress {
+                interface: iface.name,
+                address: ip.to_string(),
+                qr_svg: qr_svg(&url),
+                url,
+            }
+        })
+        .collect();
+    addresses.sort_by_key(|address| address.address.contains(':'));
+
+    let hint = if !listening_on_lan {
+        Some(format!(
+            "The server listens on {host}, which other devices cannot reach. Set SERVER_HOST=0.0.0.0, allow your LAN in SillyTavern's whitelist, then restart."
+        ))
+    } else if addresses.is_empty() {
+        Some("No LAN interface with a usable address was found.".into())
+    } else {
+        None
+    };
+
+    Ok(LanAccessInfo {
+        host,
+        port,
+        listening_on_lan,
+        addresses,
+        firewall_rule: if cfg!(windows) {
+            Some(firewall_rule_exists(port).await)
+        } else {
+            None
+        },
+        hint,
+    })
+}
+
+#[cfg(windows)]
+#[tauri::command]
+pub(crate) async fn allow_lan_firewall(
+    app: AppHandle,
+    state: tauri::State<'_, ServerState>,
+    port: Option<u16>,
+) -> Result<String, LauncherError> {
+    let port = port.unwrap_or_else(|| server_endpoint(state.inner()).1);
+    let name = firewall_rule_name(port);
+    if firewall_rule_exists(port).await {
+        return Ok(format!("Firewall rule \"{name}\" already exists."));
+    }
+
+    let netsh_args = format!(
+        "advfirewall firewall add rule name=\"{name}\" dir=in action=allow protocol=TCP localport={port} profile=private"
+    );
+    let script = format!(
+        "$p = Start-Process -FilePath netsh -ArgumentList '{}' -Verb RunAs -WindowStyle Hidden -Wait -PassThru; exit $p.ExitCode",
+        netsh_args.replace('\'', "''")
+    );
+    log_line(
+        &app,
+        &format!("Requesting administrator rights to allow inbound TCP {port}..."),
+    )
+    .await;
+    let output = TokioCommand::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to start PowerShell: {e}"))?;
+    if !output.status.success() || !firewall_rule_exists(port).await {
+        let details = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let message = if details.contains("canceled by the user") {
+            "The administrator prompt was declined; the firewall rule was not created.".to_string()
+        } else {
+            format!("Creating the firewall rule failed. {details}")
+        };
+        log_line(&app, &message).await;
+        return Err(message.into());
+    }
+    let message = format!("Created firewall rule \"{name}\" for private networks.");
+    log_line(&app, &message).await;
+    Ok(message)
+}
+
+#[cfg(not(windows))]
+#[tauri::command]
+pub(crate) async fn allow_lan_firewall(
+    _app: AppHandle,
+    _port: Option<u16>,
+) -> Result<String, LauncherError> {
+    Err("Automatic firewall rules are only available on Windows. Open the port with your firewall tool (for example `ufw allow <port>/tcp`).".into())
+}
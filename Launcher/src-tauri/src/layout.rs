@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{Monitor, PhysicalPosition, PhysicalSize, WebviewWindow, Window};
+
+use crate::{
+    display,
+    error::LauncherError,
+    state::{self, ChangeOrigin},
+};
+
+const UI_STATE_KEY: &str = "uiState";
+const MIN_VISIBLE_PX: i32 = 64;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct WindowGeometry {
+    width: u32,
+    height: u32,
+    x: i32,
+    y: i32,
+    monitor: Option<String>,
+    maximized: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct UiState {
+    window: Option<WindowGeometry>,
+    show_logs: bool,
+    preferences: Map<String, Value>,
+}
+
+// The title bar has to land on the monitor the window was closed on,
+// otherwise an unplugged screen would leave the window unreachable.
+fn fits(monitor: &Monitor, geometry: &WindowGeometry) -> bool {
+    if geometry.monitor.is_some() && monitor.name() != geometry.monitor.as_ref() {
+        return false;
+    }
+    let origin = monitor.position();
+    let size = monitor.size();
+    let right = origin.x + size.width as i32;
+    let bottom = origin.y + size.height as i32;
+    geometry.x + geometry.width as i32 - MIN_VISIBLE_PX > origin.x
+        && geometry.x + MIN_VISIBLE_PX < right
+        && geometry.y >= origin.y
+        && geometry.y + MIN_VISIBLE_PX < bottom
+}
+
+pub(crate) fn capture(window: &Window) {
+    if window.is_minimized().unwrap_or(false) || !window.is_visible().unwrap_or(false) {
+        return;
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| monitor.name().cloned());
+    let bounds = window.inner_size().ok().zip(window.outer_position().ok());
+    let _ = state::update(UI_STATE_KEY, ChangeOrigin::Launcher, |ui: &mut UiState| {
+        let mut geometry = ui.window.take().unwrap_or_default();
+        geometry.maximized = maximized;
+        if let Some((size, position)) = bounds.filter(|_| !maximized) {
+            geometry.width = size.width;
+            geometry.height = size.height;
+            geometry.x = position.x;
+            geometry.y = position.y;
+            geometry.monitor = monitor;
+        }
+        ui.window = Some(geometry);
+    });
+}
+
+pub(crate) fn restore(window: &WebviewWindow) -> bool {
+    let Some(geometry) = state::load::<UiState>(UI_STATE_KEY).window else {
+        return false;
+    };
+    if geometry.width > 0 && geometry.height > 0 {
+        let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+        let on_screen = window
+            .available_monitors()
+            .is_ok_and(|monitors| monitors.iter().any(|monitor| fits(monitor, &geometry)));
+        if on_screen && display::supports_positioning() {
+            let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+        }
+    }
+    geometry.maximized
+}
+
+#[tauri::command]
+pub(crate) async fn load_ui_state() -> Result<UiState, LauncherError> {
+    Ok(state::load(UI_STATE_KEY))
+}
+
+#[tauri::command]
+pub(crate) async fn save_ui_state(
+    show_logs: Option<bool>,
+    preferences: Option<Map<String, Value>>,
+) -> Result<UiState, LauncherError> {
+    let (ui, _) = state::update(UI_STATE_KEY, ChangeOrigin::Ui, |ui: &mut UiState| {
+        if let Some(show_logs) = show_logs {
+            ui.show_logs = show_logs;
+        }
+        for (key, value) in preferences.unwrap_or_default() {
+            if value.is_null() {
+                ui.preferences.remove(&key);
+            } else {
+                ui.preferences.insert(key, value);
+            }
+        }
+        ui.clone()
+    })?;
+    Ok(ui)
+}
@@ -1,26 +1,90 @@
+mod access;
+mod autostart;
+mod backup;
+mod bundle;
+mod certs;
+mod characters;
+mod cli;
+mod disk;
+mod display;
+mod drift;
+mod dryrun;
+mod error;
+mod events;
+mod health;
+mod hooks;
+mod import;
+mod installs;
+mod instance;
+mod integrity;
+mod journal;
+mod lan;
+mod layout;
+mod manifest;
+mod migrations;
+mod notify;
+mod observer;
+mod offline;
+mod onboarding;
+mod operation;
+mod packages;
+mod playtime;
+mod prompt;
+mod recovery;
+mod redirect;
+mod repo;
+mod repro;
+mod retry;
+mod sanitize;
+mod scripts;
+mod settings;
+mod share;
+mod state;
+mod switcher;
+mod temp;
+mod tls;
+mod trace;
+mod tray;
+mod tuning;
+mod tunnel;
+mod updates;
+mod userdata;
+
 use std::{
-    env,
-    ffi::{OsStr, OsString},
-    fs as stdfs,
+    env, fs as stdfs,
     net::TcpListener,
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
-    time::Duration,
 };
 
 use chrono::Local;
+use disk::DiskOperation;
 use dotenvy::{from_filename, from_path_iter};
-use serde::Serialize;
+use dryrun::{Change, DryRun};
+use error::{ErrorKind, LauncherError};
+use hooks::{Hook, HookContext};
+use journal::{JournalAction, JournalEntry};
+use notify::NotifyEvent;
+use onboarding::OnboardingStep;
+use operation::Operation;
+use packages::{CacheAction, PackageManager, PackageManagerKind};
+use prompt::{Prompt, PromptAction, PromptCode};
+use repo::RepoState;
+use retry::{OperationClass, RetryPolicy};
+use sanitize::{LogLine, LossyLines};
+use serde::{Deserialize, Serialize};
 use std::process::Stdio;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Manager};
 use tokio::{
     fs::{self as tokio_fs, OpenOptions},
-    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    io::AsyncWriteExt,
     process::{Child as TokioChild, Command as TokioCommand},
-    sync::Mutex as AsyncMutex,
-    time::sleep,
+    sync::{oneshot, Mutex as AsyncMutex},
 };
+use updates::UpdatePolicyMode;
 
+#[cfg(not(windows))]
+use std::time::Duration;
 #[cfg(not(windows))]
 use tokio::time::timeout;
 
@@ -69,15 +133,27 @@ unsafe impl Sync for JobHandle {}
 
 struct ServerState {
     child: Mutex<Option<TokioChild>>,
+    share_proxy: Mutex<Option<oneshot::Sender<()>>>,
+    tls_proxy: Mutex<Option<oneshot::Sender<()>>>,
+    port_redirect: Mutex<Option<oneshot::Sender<()>>>,
+    liveness: Mutex<Option<oneshot::Sender<()>>>,
+    config_watch: Mutex<Option<oneshot::Sender<()>>>,
+    playtime: Mutex<Option<oneshot::Sender<()>>>,
+    hooks: Mutex<Option<HookContext>>,
+    tunnel: Mutex<Option<tunnel::Tunnel>>,
+    tuning: Mutex<Option<tuning::AppliedTuning>>,
+    operation: Mutex<Option<operation::CurrentOperation>>,
     #[cfg(windows)]
     job: Mutex<Option<JobHandle>>,
 }
 
-#[cfg(windows)]
-const NPM_CANDIDATES: &[&str] = &["npm.cmd", "npm"];
-
-#[cfg(not(windows))]
-const NPM_CANDIDATES: &[&str] = &["npm"];
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerStatus {
+    running: bool,
+    pid: Option<u32>,
+    tuning: Option<tuning::AppliedTuning>,
+}
 
 const FALLBACK_PORTS: &[u16] = &[8000, 8080, 3000, 5173];
 
@@ -88,6 +164,21 @@ enum UpdateStatus {
     UpToDate,
     NeedRetry,
     Failed,
+    Offline,
+    DryRun,
+}
+
+impl UpdateStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::UpToDate => "upToDate",
+            Self::NeedRetry => "needRetry",
+            Self::Failed => "failed",
+            Self::Offline => "offline",
+            Self::DryRun => "dryRun",
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -99,39 +190,91 @@ struct UpdateResponse {
     diff: Option<String>,
     stash_used: bool,
     log_contents: Option<String>,
+    attempts: u32,
+    prompt: Option<Prompt>,
+    repo_state: Option<RepoState>,
+    plan: Option<DryRun>,
+}
+
+fn update_failure_actions(stash_used: bool) -> Vec<PromptAction> {
+    let mut actions = Vec::new();
+    if stash_used {
+        actions.push(PromptAction::ManageStash);
+    }
+    actions.extend([PromptAction::SkipUpdate, PromptAction::Exit]);
+    actions
 }
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
-struct CharacterResponse {
-    success: bool,
+struct BootstrapResponse {
+    cloned: bool,
+    shallow: bool,
     message: String,
 }
 
-enum NpmTool {
-    Binary(OsString),
-    Script(PathBuf),
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+enum NpmCacheAction {
+    #[default]
+    None,
+    Verify,
+    Clean,
 }
 
-impl NpmTool {
-    fn into_command(self) -> TokioCommand {
+impl NpmCacheAction {
+    fn action(self) -> Option<CacheAction> {
         match self {
-            Self::Binary(bin) => TokioCommand::new(bin),
-            Self::Script(path) => {
-                let mut cmd = TokioCommand::new("node");
-                cmd.arg(path.as_os_str());
-                cmd
-            }
+            Self::None => None,
+            Self::Verify => Some(CacheAction::Verify),
+            Self::Clean => Some(CacheAction::Clean),
         }
     }
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CharacterResponse {
+    success: bool,
+    message: String,
+    offline: bool,
+    attempts: u32,
+}
+
 fn apply_node_env(cmd: &mut TokioCommand) {
     cmd.env("NODE_ENV", "production");
     cmd.env("NO_BROWSER", "1");
     cmd.env("BROWSER", "none");
 }
 
+fn qr_svg(text: &str) -> String {
+    qrcode::QrCode::new(text.as_bytes())
+        .map(|code| {
+            code.render::<qrcode::render::svg::Color>()
+                .min_dimensions(200, 200)
+                .quiet_zone(true)
+                .build()
+        })
+        .unwrap_or_default()
+}
+
+fn server_arg(flag: &str) -> Option<String> {
+    let args = env::var("SERVER_ARGS").unwrap_or_default();
+    let mut iter = args.split_whitespace();
+    while let Some(arg) = iter.next() {
+        if arg == flag {
+            return iter.next().map(str::to_string);
+        }
+        if let Some(value) = arg
+            .strip_prefix(flag)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
 fn args_contains_flag(args: &[String], flag: &str) -> bool {
     args.iter().any(|arg| {
         if arg == flag {
@@ -146,81 +289,339 @@ fn args_contains_flag(args: &[String], flag: &str) -> bool {
 
 #[tokio::main]
 async fn main() {
+    load_env();
+    let headless = cli::parse();
+    trace::init();
+    let instance = instance::claim(headless.is_some()).await;
+    display::prepare_environment();
     tauri::Builder::default()
         .manage(ServerState {
             child: Mutex::new(None),
+            share_proxy: Mutex::new(None),
+            tls_proxy: Mutex::new(None),
+            port_redirect: Mutex::new(None),
+            liveness: Mutex::new(None),
+            config_watch: Mutex::new(None),
+            playtime: Mutex::new(None),
+            hooks: Mutex::new(None),
+            tunnel: Mutex::new(None),
+            tuning: Mutex::new(None),
+            operation: Mutex::new(None),
             #[cfg(windows)]
             job: Mutex::new(None),
         })
+        .plugin(tauri_plugin_notification::init())
+        .manage(share::GuestTokens::load())
+        .manage(observer::ObserverState::default())
         .invoke_handler(tauri::generate_handler![
             update_vendor,
             finalize_stash,
+            bootstrap_vendor,
+            unshallow_vendor,
+            check_dependencies,
             run_character_sync,
-            start_server
+            start_server,
+            server_status,
+            repair_node_modules,
+            manifest::verify_installation,
+            manifest::repair_installation,
+            manifest::generate_release_manifest,
+            share::create_guest_token,
+            share::list_guest_tokens,
+            share::revoke_guest_token,
+            onboarding::get_onboarding_state,
+            onboarding::reset_onboarding,
+            settings::list_settings_snapshots,
+            settings::diff_settings,
+            journal::list_update_journal,
+            journal::read_update_entry,
+            backup::create_backup,
+            scripts::list_vendor_scripts,
+            scripts::run_vendor_script,
+            certs::certificate_status,
+            certs::renew_certificate,
+            tls::tls_proxy_status,
+            tls::enable_tls_proxy,
+            tls::disable_tls_proxy,
+            tls::regenerate_tls_certificate,
+            display::display_capabilities,
+            display::install_desktop_entry,
+            observer::start_observer_session,
+            observer::stop_observer_session,
+            recovery::verify_chats,
+            recovery::restore_chat_backup,
+            tunnel::tunnel_status,
+            lan::lan_access_info,
+            lan::allow_lan_firewall,
+            repro::capture_repro_state,
+            playtime::playtime_stats,
+            notify::notification_settings,
+            notify::set_notification_enabled,
+            state::read_setting,
+            state::write_setting,
+            updates::check_for_updates,
+            updates::last_update_check,
+            trace::get_log_level,
+            trace::set_log_level,
+            events::subscribe,
+            userdata::data_root_status,
+            integrity::scan_user_data,
+            integrity::restore_user_file,
+            import::scan_import_source,
+            import::import_from_source,
+            bundle::export_bundle,
+            bundle::import_bundle,
+            updates::get_update_policy,
+            updates::set_update_policy,
+            repo::repo_state,
+            repo::repair_repo,
+            operation::get_current_operation,
+            characters::list_staged_characters,
+            characters::approve_staged_character,
+            characters::reject_staged_character,
+            userdata::migrate_data_root,
+            installs::list_installs,
+            installs::add_install,
+            installs::remove_install,
+            installs::set_active_install,
+            layout::load_ui_state,
+            layout::save_ui_state,
+            cli::launch_options,
+            switcher::list_instances,
+            switcher::open_instance_window,
+            autostart::autostart_status,
+            autostart::enable_autostart,
+            autostart::disable_autostart,
+            access::check_write_access,
+            access::fix_path_permissions,
+            access::relocate_vendor
         ])
-        .setup(|_| {
+        .setup(move |app| {
             load_env();
+            state::attach(app.handle());
+            repro::listen(app.handle());
+            instance.serve(app.handle());
+            let sweep_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move { temp::sweep(&sweep_handle).await });
+            if let Some(command) = headless.clone() {
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    let code = cli::run(&handle, command).await;
+                    instance::release();
+                    std::process::exit(code);
+                });
+                return Ok(());
+            }
+            certs::spawn_monitor(app.handle().clone());
+            updates::spawn_scheduler(app.handle());
+            let minimized = cli::start_minimized();
+            if minimized {
+                tray::install(app.handle())?;
+            }
+            for config in &app.config().app.windows {
+                let window = tauri::WebviewWindowBuilder::from_config(app.handle(), config)?
+                    .visible(false)
+                    .build()?;
+                let maximized = layout::restore(&window);
+                if config.visible && !minimized {
+                    window.show()?;
+                    if maximized {
+                        window.maximize()?;
+                    }
+                }
+            }
             Ok(())
         })
         .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                events::forget(window.label());
+            }
+            if let tauri::WindowEvent::Focused(focused) = event {
+                playtime::set_focused(*focused);
+            }
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                if switcher::is_instance_window(window.label()) {
+                    return;
+                }
                 api.prevent_close();
+                layout::capture(window);
                 let app = window.app_handle().clone();
                 tauri::async_runtime::spawn(async move {
-                    let state = app.state::<ServerState>();
-                    shutdown(state).await;
+                    shutdown(&app, false).await;
                     app.exit(0);
                 });
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error running tauri app");
+        .build(tauri::generate_context!())
+        .expect("error building tauri app")
+        .run(|_, event| {
+            if let tauri::RunEvent::Exit = event {
+                instance::release();
+            }
+        });
 }
 
 fn load_env() {
-    let _ = from_filename("../.env").or_else(|_| from_filename(".env"));
+    match from_filename("../.env").or_else(|_| from_filename(".env")) {
+        Ok(path) => tracing::trace!(path = %path.display(), "loaded launcher .env"),
+        Err(err) => tracing::trace!(%err, "no launcher .env loaded"),
+    }
 }
 
-fn allow_git_pull_in_app() -> bool {
-    let raw = env::var("ALLOW_GIT_PULL_IN_APP").unwrap_or_default();
-    matches!(
-        raw.trim().to_ascii_lowercase().as_str(),
-        "1" | "true" | "yes" | "on"
-    )
+fn fetch_depth() -> Option<u32> {
+    env::var("VENDOR_FETCH_DEPTH")
+        .ok()
+        .and_then(|value| value.trim().parse::<u32>().ok())
+        .filter(|depth| *depth > 0)
 }
 
-fn silly_dir() -> Result<PathBuf, String> {
+fn silly_dir() -> Result<PathBuf, LauncherError> {
+    if let Some(install) = installs::active() {
+        let path = PathBuf::from(&install.path);
+        tracing::debug!(path = %path.display(), install = %install.id, exists = path.exists(), "resolved SillyTavern directory");
+        return if path.exists() {
+            Ok(path)
+        } else {
+            Err(LauncherError::new(
+                ErrorKind::NotConfigured,
+                format!(
+                    "Install \"{}\" no longer exists at {}. Switch to another install or remove it.",
+                    install.name,
+                    path.display()
+                ),
+            ))
+        };
+    }
+    let configured = env::var("SILLYTAVERN_DIR").ok();
+    let source = if configured.is_some() {
+        "SILLYTAVERN_DIR"
+    } else {
+        "default"
+    };
     let path =
-        env::var("SILLYTAVERN_DIR").unwrap_or_else(|_| "./vendor/WeylandTavern/SillyTavern".into());
-    let path = PathBuf::from(path);
+        PathBuf::from(configured.unwrap_or_else(|| "./vendor/WeylandTavern/SillyTavern".into()));
+    tracing::debug!(path = %path.display(), source, exists = path.exists(), "resolved SillyTavern directory");
     if path.exists() {
         Ok(path)
     } else {
-        Err(format!(
-            "SILLYTAVERN_DIR does not exist at {}. Set SILLYTAVERN_DIR in .env",
-            path.display()
+        Err(LauncherError::new(
+            ErrorKind::NotConfigured,
+            format!(
+                "SILLYTAVERN_DIR does not exist at {}. Set SILLYTAVERN_DIR in .env",
+                path.display()
+            ),
         ))
     }
 }
 
-fn vendor_dir() -> Result<PathBuf, String> {
+fn logs_dir() -> PathBuf {
+    PathBuf::from("logs")
+}
+
+fn vendor_dir() -> Result<PathBuf, LauncherError> {
     let silly = silly_dir()?;
-    silly
-        .parent()
-        .map(Path::to_path_buf)
-        .ok_or_else(|| "Unable to determine vendor directory".to_string())
+    if let Some(install) = installs::active() {
+        return Ok(PathBuf::from(install.repo));
+    }
+    silly.parent().map(Path::to_path_buf).ok_or_else(|| {
+        LauncherError::new(
+            ErrorKind::NotConfigured,
+            "Unable to determine vendor directory",
+        )
+    })
+}
+
+async fn run_git(dir: &Path, args: &[&str]) -> Result<std::process::Output, LauncherError> {
+    let mut cmd = TokioCommand::new("git");
+    cmd.args(args).current_dir(dir);
+    trace::spawning(&cmd);
+    let output = cmd.output().await.map_err(|e| {
+        LauncherError::new(
+            ErrorKind::Git,
+            format!("Failed to run git {}: {e}", args.join(" ")),
+        )
+    })?;
+    tracing::debug!(args = %args.join(" "), status = %output.status, "git finished");
+    Ok(output)
 }
 
-async fn run_git(dir: &Path, args: &[&str]) -> Result<std::process::Output, String> {
-    TokioCommand::new("git")
-        .args(args)
-        .current_dir(dir)
-        .output()
+async fn head_commit(repo: &Path) -> Option<String> {
+    let output = run_git(repo, &["rev-parse", "HEAD"]).await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let sha = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!sha.is_empty()).then_some(sha)
+}
+
+async fn record_update(app: &AppHandle, entry: &JournalEntry) {
+    if let Err(err) = journal::append(entry).await {
+        log_line(app, &format!("Failed to write the update journal: {err}")).await;
+    }
+    notify::update_finished(app, entry);
+}
+
+fn has_submodules(repo: &Path) -> bool {
+    repo.join(".gitmodules").is_file()
+}
+
+async fn submodule_status(repo: &Path) -> String {
+    run_git(repo, &["submodule", "status", "--recursive"])
         .await
-        .map_err(|e| e.to_string())
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        .unwrap_or_default()
+}
+
+fn submodule_changes(before: &str, after: &str) -> String {
+    let parse = |raw: &str| -> Vec<(String, String)> {
+        raw.lines()
+            .filter_map(|line| {
+                let mut parts = line.get(1..)?.split_whitespace();
+                let sha = parts.next()?.to_string();
+                let path = parts.next()?.to_string();
+                Some((path, sha))
+            })
+            .collect()
+    };
+    let short = |sha: &str| sha.chars().take(8).collect::<String>();
+    let before = parse(before);
+    parse(after)
+        .into_iter()
+        .filter_map(
+            |(path, sha)| match before.iter().find(|(old, _)| *old == path) {
+                Some((_, old_sha)) if *old_sha == sha => None,
+                Some((_, old_sha)) => {
+                    Some(format!("{path}: {} -> {}", short(old_sha), short(&sha)))
+                }
+                None => Some(format!("{path}: added at {}", short(&sha))),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+async fn append_update_log(log_path: &Path, title: &str, body: &str) -> Result<String, String> {
+    if body.trim().is_empty() {
+        return Ok(String::new());
+    }
+    let section = format!("\n{title}:\n{}\n", body.trim());
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(log_path)
+        .await
+        .map_err(|e| e.to_string())?;
+    file.write_all(section.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(section)
 }
 
-async fn write_update_log(log_path: &Path, pull: &str, diff: &str) -> Result<String, String> {
+async fn write_update_log(
+    log_path: &Path,
+    pull: &str,
+    diff: &str,
+    submodules: &str,
+) -> Result<String, String> {
     let mut file = tokio_fs::File::create(log_path)
         .await
         .map_err(|e| e.to_string())?;
@@ -238,6 +639,11 @@ async fn write_update_log(log_path: &Path, pull: &str, diff: &str) -> Result<Str
         contents.push_str(diff.trim());
         contents.push('\n');
     }
+    if !submodules.trim().is_empty() {
+        contents.push_str("\nSubmodules:\n");
+        contents.push_str(submodules.trim());
+        contents.push('\n');
+    }
     file.write_all(contents.as_bytes())
         .await
         .map_err(|e| e.to_string())?;
@@ -246,18 +652,32 @@ async fn write_update_log(log_path: &Path, pull: &str, diff: &str) -> Result<Str
 }
 
 #[tauri::command]
-async fn update_vendor(app: AppHandle, attempt_overwrite: bool) -> Result<UpdateResponse, String> {
+async fn update_vendor(
+    app: AppHandle,
+    attempt_overwrite: bool,
+    dry_run: Option<bool>,
+) -> Result<UpdateResponse, LauncherError> {
+    let _operation = operation::begin(&app, Operation::VendorUpdate)?;
     load_env();
-    let silly = silly_dir()?;
     let repo = vendor_dir()?;
-    let log_path = silly.join("WTUpdate.log");
 
-    if !allow_git_pull_in_app() {
+    if updates::policy_mode() == UpdatePolicyMode::Frozen {
         let script_hint = env::var("UPDATE_SCRIPT")
             .ok()
             .filter(|value| !value.trim().is_empty());
-        let mut message =
-            String::from("Skipping vendor update: in-app git pull is disabled by policy.");
+        let mut message = match updates::pinned_commit() {
+            Some(pinned) => {
+                let mut message =
+                    format!("Skipping vendor update: the update policy is frozen at {pinned}.");
+                if head_commit(&repo).await.as_deref() != Some(pinned.as_str()) {
+                    message.push_str(
+                        " The checkout has moved away from that commit; set the policy again to re-pin it.",
+                    );
+                }
+                message
+            }
+            None => String::from("Skipping vendor update: the update policy is frozen."),
+        };
         if let Some(script) = script_hint {
             message.push(' ');
             message.push_str(&format!("Use {} to update WeylandTavern manually.", script));
@@ -270,10 +690,170 @@ async fn update_vendor(app: AppHandle, attempt_overwrite: bool) -> Result<Update
             diff: None,
             stash_used: false,
             log_contents: None,
+            attempts: 0,
+            repo_state: None,
+            plan: None,
+            prompt: None,
+        });
+    }
+
+    let connectivity = offline::refresh().await;
+    if connectivity.is_offline() {
+        let message = format!(
+            "Offline: skipping vendor update because {}.",
+            connectivity.describe()
+        );
+        log_line(&app, &message).await;
+        return Ok(UpdateResponse {
+            status: UpdateStatus::Offline,
+            message,
+            log_path: None,
+            diff: None,
+            stash_used: false,
+            log_contents: None,
+            attempts: 0,
+            repo_state: None,
+            plan: None,
+            prompt: None,
+        });
+    }
+
+    if let Some(blocked) = access::blocked() {
+        let message = blocked.message();
+        log_line(&app, &message).await;
+        let prompt = Prompt::new(
+            PromptCode::ReadOnlyPath,
+            &[
+                PromptAction::FixPermissions,
+                PromptAction::RelocateVendor,
+                PromptAction::SkipUpdate,
+                PromptAction::Exit,
+            ],
+        )
+        .param("path", blocked.path())
+        .param("detail", &message);
+        return Ok(UpdateResponse {
+            status: UpdateStatus::Failed,
+            message,
+            log_path: None,
+            diff: None,
+            stash_used: false,
+            log_contents: None,
+            attempts: 0,
+            repo_state: None,
+            plan: None,
+            prompt: Some(prompt),
+        });
+    }
+
+    if let Err(err) = disk::ensure_space(&repo, DiskOperation::GitPull) {
+        let message = err.message;
+        log_line(&app, &message).await;
+        let prompt = Prompt::new(PromptCode::InsufficientDisk, &update_failure_actions(false))
+            .param("detail", &message);
+        return Ok(UpdateResponse {
+            status: UpdateStatus::Failed,
+            message,
+            log_path: None,
+            diff: None,
+            stash_used: false,
+            log_contents: None,
+            attempts: 0,
+            repo_state: None,
+            plan: None,
+            prompt: Some(prompt),
+        });
+    }
+
+    let repo_state = repo::detect(&repo).await;
+    if !repo_state.is_healthy() {
+        let message = repo_state.describe();
+        log_line(&app, &message).await;
+        let prompt = Prompt::new(
+            PromptCode::RepoBroken,
+            &[
+                PromptAction::RepairRepo,
+                PromptAction::SkipUpdate,
+                PromptAction::Exit,
+            ],
+        )
+        .param("detail", &message);
+        return Ok(UpdateResponse {
+            status: UpdateStatus::Failed,
+            message,
+            log_path: None,
+            diff: None,
+            stash_used: false,
+            log_contents: None,
+            attempts: 0,
+            repo_state: Some(repo_state),
+            plan: None,
+            prompt: Some(prompt),
+        });
+    }
+
+    let depth_arg = fetch_depth().map(|depth| format!("--depth={depth}"));
+    let source = installs::pull_source();
+    let mut pull_args = vec!["pull"];
+    pull_args.extend(depth_arg.as_deref());
+    if let Some((remote, branch)) = &source {
+        pull_args.extend([remote.as_str(), branch.as_str()]);
+    }
+    let mut submodule_args = vec!["submodule", "update", "--init", "--recursive"];
+    submodule_args.extend(depth_arg.as_deref());
+
+    if dryrun::requested(dry_run) {
+        let mut plan = DryRun::new("update_vendor");
+        if attempt_overwrite {
+            plan.command("git", &["stash"], &repo);
+            let output = run_git(&repo, &["diff", "--name-only", "HEAD"]).await?;
+            for file in String::from_utf8_lossy(&output.stdout).lines() {
+                plan.file(Change::Replace, &repo.join(file.trim()));
+            }
+        }
+        plan.command("git", &pull_args, &repo);
+        if has_submodules(&repo) {
+            plan.command("git", &submodule_args, &repo);
+        }
+        plan.note("The files the pull changes are only known after fetching; check_for_updates reports how far behind the checkout is.");
+        plan.note("Pending vendor migrations run after a successful pull.");
+        let plan = plan
+            .finish(
+                &app,
+                if attempt_overwrite {
+                    "the update would stash local changes and pull WeylandTavern."
+                } else {
+                    "the update would pull WeylandTavern."
+                },
+            )
+            .await;
+        return Ok(UpdateResponse {
+            status: UpdateStatus::DryRun,
+            message: plan.summary().to_string(),
+            log_path: None,
+            diff: None,
+            stash_used: false,
+            log_contents: None,
+            attempts: 0,
+            repo_state: None,
+            plan: Some(plan),
+            prompt: None,
         });
     }
 
     let mut stash_used = false;
+    let mut entry = JournalEntry::new(if attempt_overwrite {
+        JournalAction::StashAndPull
+    } else {
+        JournalAction::Pull
+    });
+    entry.from_commit = head_commit(&repo).await;
+    let log_path = journal::detail_path(&entry.id).await?;
+    let submodules_before = if has_submodules(&repo) {
+        submodule_status(&repo).await
+    } else {
+        String::new()
+    };
 
     if attempt_overwrite {
         log_line(&app, "Stashing local changes before retrying update...").await;
@@ -284,18 +864,47 @@ async fn update_vendor(app: AppHandle, attempt_overwrite: bool) -> Result<Update
                 String::from_utf8_lossy(&output.stdout),
                 String::from_utf8_lossy(&output.stderr)
             );
-            return Err(if details.trim().is_empty() {
-                "git stash failed".into()
+            let message = if details.trim().is_empty() {
+                "git stash failed".to_string()
             } else {
                 format!("git stash failed: {}", details.trim())
-            });
+            };
+            entry.result = UpdateStatus::Failed.as_str().into();
+            entry.message = message.clone();
+            record_update(&app, &entry).await;
+            return Err(LauncherError::new(ErrorKind::Git, message));
         }
         stash_used = true;
     } else {
         log_line(&app, "Attempting to update WeylandTavern...").await;
     }
 
-    let pull_output = run_git(&repo, &["pull"]).await?;
+    let pull_policy = RetryPolicy::for_class(OperationClass::Git);
+    let pull = pull_policy
+        .run(
+            |attempt| {
+                let app = &app;
+                let repo = &repo;
+                let pull_args = &pull_args;
+                async move {
+                    if attempt > 1 {
+                        log_line(
+                            app,
+                            &format!(
+                                "Retrying git pull (attempt {attempt} of {})...",
+                                pull_policy.max_attempts
+                            ),
+                        )
+                        .await;
+                    }
+                    run_git(repo, pull_args).await
+                }
+            },
+            retry::is_transient_failure,
+        )
+        .await;
+    let attempts = pull.attempts;
+    let pull_output = pull.value?;
     let pull_text = format!(
         "{}{}",
         String::from_utf8_lossy(&pull_output.stdout),
@@ -303,8 +912,100 @@ async fn update_vendor(app: AppHandle, attempt_overwrite: bool) -> Result<Update
     );
 
     if pull_output.status.success() {
+        let mut submodule_report = String::new();
+        if has_submodules(&repo) {
+            log_line(&app, "Updating vendor submodules...").await;
+            let update = pull_policy
+                .run(
+                    |_| run_git(&repo, &submodule_args),
+                    retry::is_transient_failure,
+                )
+                .await;
+            let output = update.value?;
+            if !output.status.success() {
+                let details = format!(
+                    "git submodule update --init --recursive failed:\n{}{}\n\nStatus:\n{}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr),
+                    submodule_status(&repo).await
+                );
+                let message =
+                    "WeylandTavern was pulled, but updating its submodules failed.".to_string();
+                log_line(&app, &message).await;
+                let log_contents = write_update_log(&log_path, &pull_text, "", &details).await?;
+                entry.to_commit = head_commit(&repo).await;
+                entry.result = UpdateStatus::Failed.as_str().into();
+                entry.message = message.clone();
+                entry.stash_used = stash_used;
+                entry.detail_log = Some(log_path.to_string_lossy().into_owned());
+                record_update(&app, &entry).await;
+                return Ok(UpdateResponse {
+                    status: UpdateStatus::Failed,
+                    message,
+                    log_path: Some(log_path.to_string_lossy().into_owned()),
+                    diff: Some(details.trim().to_string()),
+                    stash_used,
+                    log_contents: Some(log_contents),
+                    attempts,
+                    repo_state: None,
+                    plan: None,
+                    prompt: Some(Prompt::new(
+                        PromptCode::SubmodulesFailed,
+                        &update_failure_actions(stash_used),
+                    )),
+                });
+            }
+            submodule_report =
+                submodule_changes(&submodules_before, &submodule_status(&repo).await);
+            if !submodule_report.is_empty() {
+                log_line(&app, &format!("Submodules updated:\n{submodule_report}")).await;
+            }
+        }
+
+        let migrations = migrations::run_pending(&app, &repo).await;
+        if let Some(failure) = migrations.failure {
+            let message = format!("WeylandTavern was updated, but a migration failed: {failure}");
+            log_line(&app, &message).await;
+            let mut log_contents =
+                write_update_log(&log_path, &pull_text, "", &submodule_report).await?;
+            log_contents
+                .push_str(&append_update_log(&log_path, "Migrations", &migrations.log).await?);
+            entry.to_commit = head_commit(&repo).await;
+            entry.result = UpdateStatus::Failed.as_str().into();
+            entry.message = message.clone();
+            entry.stash_used = stash_used;
+            entry.detail_log = Some(log_path.to_string_lossy().into_owned());
+            record_update(&app, &entry).await;
+            return Ok(UpdateResponse {
+                status: UpdateStatus::Failed,
+                message,
+                log_path: Some(log_path.to_string_lossy().into_owned()),
+                diff: Some(migrations.log.trim().to_string()),
+                stash_used,
+                log_contents: Some(log_contents),
+                attempts,
+                repo_state: None,
+                plan: None,
+                prompt: Some(
+                    Prompt::new(
+                        PromptCode::MigrationFailed,
+                        &update_failure_actions(stash_used),
+                    )
+                    .param("detail", &failure),
+                ),
+            });
+        }
+
         let lower = pull_text.to_lowercase();
-        let (status, message) = if lower.contains("already up to date") {
+        let (status, message) = if !migrations.applied.is_empty() {
+            (
+                UpdateStatus::Success,
+                format!(
+                    "WeylandTavern updated successfully and applied {} migration(s).",
+                    migrations.applied.len()
+                ),
+            )
+        } else if lower.contains("already up to date") && submodule_report.is_empty() {
             (
                 UpdateStatus::UpToDate,
                 "WeylandTavern is up to date!".to_string(),
@@ -315,7 +1016,28 @@ async fn update_vendor(app: AppHandle, attempt_overwrite: bool) -> Result<Update
                 "WeylandTavern updated successfully.".to_string(),
             )
         };
+        let message = match retry::attempt_note(attempts) {
+            Some(note) => format!("{message} ({note})"),
+            None => message,
+        };
         log_line(&app, &message).await;
+
+        entry.to_commit = head_commit(&repo).await;
+        let changes = match (&entry.from_commit, &entry.to_commit) {
+            (Some(from), Some(to)) if from != to => {
+                let output = run_git(&repo, &["diff", "--compact-summary", from, to]).await?;
+                String::from_utf8_lossy(&output.stdout).into_owned()
+            }
+            _ => String::new(),
+        };
+        write_update_log(&log_path, &pull_text, &changes, &submodule_report).await?;
+        append_update_log(&log_path, "Migrations", &migrations.log).await?;
+        entry.result = status.as_str().into();
+        entry.message = message.clone();
+        entry.stash_used = stash_used;
+        entry.detail_log = Some(log_path.to_string_lossy().into_owned());
+        record_update(&app, &entry).await;
+
         return Ok(UpdateResponse {
             status,
             message,
@@ -323,11 +1045,24 @@ async fn update_vendor(app: AppHandle, attempt_overwrite: bool) -> Result<Update
             diff: None,
             stash_used,
             log_contents: None,
+            attempts,
+            repo_state: None,
+            plan: None,
+            prompt: stash_used.then(|| {
+                Prompt::new(
+                    PromptCode::RestoreStash,
+                    &[PromptAction::RestoreStash, PromptAction::DiscardStash],
+                )
+            }),
         });
     }
 
     log_line(&app, "There was an error updating WeylandTavern...").await;
-    log_line(&app, "Generating log file SillyTavern/WTUpdate.log...").await;
+    log_line(
+        &app,
+        &format!("Writing update log to {}...", log_path.display()),
+    )
+    .await;
 
     let diff_output = run_git(&repo, &["diff", "--compact-summary"]).await?;
     let diff_text = format!(
@@ -336,7 +1071,12 @@ async fn update_vendor(app: AppHandle, attempt_overwrite: bool) -> Result<Update
         String::from_utf8_lossy(&diff_output.stderr)
     );
 
-    let log_contents = write_update_log(&log_path, &pull_text, &diff_text).await?;
+    let submodule_text = if has_submodules(&repo) {
+        submodule_status(&repo).await
+    } else {
+        String::new()
+    };
+    let log_contents = write_update_log(&log_path, &pull_text, &diff_text, &submodule_text).await?;
 
     let combined = {
         let mut combined = pull_text.trim().to_string();
@@ -346,16 +1086,53 @@ async fn update_vendor(app: AppHandle, attempt_overwrite: bool) -> Result<Update
             }
             combined.push_str(diff_text.trim());
         }
+        if !submodule_text.trim().is_empty() {
+            if !combined.is_empty() {
+                combined.push_str("\n\n");
+            }
+            combined.push_str("Submodules:\n");
+            combined.push_str(submodule_text.trim());
+        }
         combined
     };
 
+    let repo_state = Some(repo::detect(&repo).await).filter(|state| !state.is_healthy());
+    let status = if attempt_overwrite || repo_state.is_some() {
+        UpdateStatus::Failed
+    } else {
+        UpdateStatus::NeedRetry
+    };
+    entry.to_commit = head_commit(&repo).await;
+    entry.result = status.as_str().into();
+    entry.stash_used = stash_used;
+    entry.detail_log = Some(log_path.to_string_lossy().into_owned());
+
+    let prompt = if let Some(state) = &repo_state {
+        let mut actions = vec![PromptAction::RepairRepo];
+        actions.extend(update_failure_actions(stash_used));
+        Prompt::new(PromptCode::RepoBroken, &actions).param("detail", state.describe())
+    } else if attempt_overwrite {
+        Prompt::new(
+            PromptCode::UpdateFailedAfterStash,
+            &update_failure_actions(stash_used),
+        )
+    } else {
+        Prompt::new(
+            PromptCode::UpdateFailed,
+            &[
+                PromptAction::RetryWithStash,
+                PromptAction::SkipUpdate,
+                PromptAction::Exit,
+            ],
+        )
+    }
+    .param("attempts", attempts)
+    .param("logPath", log_path.display());
     let response = UpdateResponse {
-        status: if attempt_overwrite {
-            UpdateStatus::Failed
-        } else {
-            UpdateStatus::NeedRetry
-        },
-        message: if attempt_overwrite {
+        status,
+        message: if let Some(state) = &repo_state {
+            state.describe()
+        } else if attempt_overwrite {
             "Update failed even after stashing local changes.".to_string()
         } else {
             "There was an error updating WeylandTavern.".to_string()
@@ -368,13 +1145,25 @@ async fn update_vendor(app: AppHandle, attempt_overwrite: bool) -> Result<Update
         },
         stash_used,
         log_contents: Some(log_contents),
+        attempts,
+        repo_state,
+        plan: None,
+        prompt: Some(prompt),
     };
 
+    entry.message = response.message.clone();
+    record_update(&app, &entry).await;
+
     Ok(response)
 }
 
 #[tauri::command]
-async fn finalize_stash(app: AppHandle, revert: bool) -> Result<(), String> {
+async fn finalize_stash(
+    app: AppHandle,
+    revert: bool,
+    dry_run: Option<bool>,
+) -> Result<Option<DryRun>, LauncherError> {
+    let _operation = operation::begin(&app, Operation::StashFinalize)?;
     load_env();
     let repo = vendor_dir()?;
     let args: [&str; 2] = if revert {
@@ -382,69 +1171,421 @@ async fn finalize_stash(app: AppHandle, revert: bool) -> Result<(), String> {
     } else {
         ["stash", "clear"]
     };
+    if dryrun::requested(dry_run) {
+        let mut plan = DryRun::new("finalize_stash");
+        plan.command("git", &args, &repo);
+        let listed = run_git(&repo, &["stash", "list"]).await?;
+        let entries = String::from_utf8_lossy(&listed.stdout).lines().count();
+        let summary = if revert {
+            let output = run_git(&repo, &["stash", "show", "--name-only"]).await?;
+            let files = String::from_utf8_lossy(&output.stdout).into_owned();
+            let files: Vec<&str> = files
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect();
+            for file in &files {
+                plan.file(Change::Replace, &repo.join(file));
+            }
+            format!(
+                "restoring the latest of {entries} stash entr(ies) would rewrite {} file(s).",
+                files.len()
+            )
+        } else {
+            plan.note("Cleared stash entries cannot be recovered from the launcher.");
+            format!("discarding would drop {entries} stash entr(ies).")
+        };
+        return Ok(Some(plan.finish(&app, summary).await));
+    }
     if revert {
         log_line(&app, "Reverting differing files post update...").await;
     } else {
         log_line(&app, "Discarding stashed changes...").await;
     }
+    let mut entry = JournalEntry::new(if revert {
+        JournalAction::StashPop
+    } else {
+        JournalAction::StashClear
+    });
+    entry.from_commit = head_commit(&repo).await;
+    entry.stash_used = true;
     let output = run_git(&repo, &args).await?;
+    entry.to_commit = head_commit(&repo).await;
+    let details = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
     if !output.status.success() {
-        let details = format!(
-            "{}{}",
-            String::from_utf8_lossy(&output.stdout),
-            String::from_utf8_lossy(&output.stderr)
-        );
-        return Err(if details.trim().is_empty() {
-            "Failed to finalize stash".into()
+        let message = if details.trim().is_empty() {
+            "Failed to finalize stash".to_string()
         } else {
             details.trim().to_string()
+        };
+        entry.result = "failed".into();
+        entry.message = message.clone();
+        record_update(&app, &entry).await;
+        return Err(LauncherError::new(ErrorKind::Git, message));
+    }
+    entry.result = "success".into();
+    entry.message = if revert {
+        "Restored stashed changes.".into()
+    } else {
+        "Discarded stashed changes.".into()
+    };
+    record_update(&app, &entry).await;
+    Ok(None)
+}
+
+#[tauri::command]
+async fn bootstrap_vendor(app: AppHandle) -> Result<BootstrapResponse, LauncherError> {
+    let _operation = operation::begin(&app, Operation::VendorClone)?;
+    clone_vendor(app).await
+}
+
+async fn clone_vendor(app: AppHandle) -> Result<BootstrapResponse, LauncherError> {
+    load_env();
+    if silly_dir().is_ok() {
+        onboarding::complete(&app, OnboardingStep::VendorBootstrapped).await;
+        return Ok(BootstrapResponse {
+            cloned: false,
+            shallow: false,
+            message: "WeylandTavern is already installed.".into(),
         });
     }
-    Ok(())
+
+    let target = PathBuf::from(
+        env::var("WEYLANDTAVERN_DIR").unwrap_or_else(|_| "./vendor/WeylandTavern".into()),
+    );
+    let target_has_files = stdfs::read_dir(&target)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    if target_has_files {
+        return Err(LauncherError::new(
+            ErrorKind::NotConfigured,
+            format!(
+                "{} exists but does not contain SillyTavern. Remove it or fix SILLYTAVERN_DIR in .env",
+                target.display()
+            ),
+        ));
+    }
+
+    let connectivity = offline::detect().await;
+    if connectivity.is_offline() {
+        return Err(LauncherError::new(
+            ErrorKind::Offline,
+            format!(
+                "Offline: cannot download WeylandTavern because {}.",
+                connectivity.describe()
+            ),
+        ));
+    }
+
+    disk::ensure_space(&target, DiskOperation::VendorClone)?;
+
+    let url = env::var("VENDOR_REPO_URL")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "https://github.com/Shirubaurufu/WeylandTavern".into());
+    let branch = env::var("VENDOR_BRANCH")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "nightly".into());
+    let depth = fetch_depth();
+    let depth_arg = depth.map(|depth| format!("--depth={depth}"));
+    let target_arg = target.to_string_lossy().into_owned();
+    let mut args = vec!["clone", "--branch", branch.trim(), "--recurse-submodules"];
+    if let Some(depth_arg) = depth_arg.as_deref() {
+        args.push(depth_arg);
+        args.push("--shallow-submodules");
+    }
+    args.push(url.trim());
+    args.push(&target_arg);
+
+    if let Some(parent) = target
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        tokio_fs::create_dir_all(parent)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    log_line(
+        &app,
+        &match depth {
+            Some(depth) => format!("Cloning WeylandTavern (depth {depth}) into {target_arg}..."),
+            None => format!("Cloning WeylandTavern into {target_arg}..."),
+        },
+    )
+    .await;
+
+    let clone = RetryPolicy::for_class(OperationClass::Git)
+        .run(
+            |_| run_git(Path::new("."), &args),
+            retry::is_transient_failure,
+        )
+        .await;
+    let output = clone.value?;
+    if !output.status.success() {
+        let details = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        log_line(&app, &details).await;
+        return Err(LauncherError::new(ErrorKind::Git, "git clone failed.").with_details(details));
+    }
+
+    let message = "WeylandTavern downloaded successfully.".to_string();
+    log_line(&app, &message).await;
+    onboarding::complete(&app, OnboardingStep::VendorBootstrapped).await;
+    Ok(BootstrapResponse {
+        cloned: true,
+        shallow: depth.is_some(),
+        message,
+    })
 }
 
 #[tauri::command]
-async fn run_character_sync(app: AppHandle) -> Result<CharacterResponse, String> {
+async fn unshallow_vendor(app: AppHandle) -> Result<String, LauncherError> {
+    let _operation = operation::begin(&app, Operation::Unshallow)?;
+    unshallow_checkout(app).await
+}
+
+async fn unshallow_checkout(app: AppHandle) -> Result<String, LauncherError> {
+    load_env();
+    let repo = vendor_dir()?;
+    let output = run_git(&repo, &["rev-parse", "--is-shallow-repository"]).await?;
+    if String::from_utf8_lossy(&output.stdout).trim() != "true" {
+        let message = "The vendor checkout already has its full history.".to_string();
+        log_line(&app, &message).await;
+        return Ok(message);
+    }
+
+    let connectivity = offline::detect().await;
+    if connectivity.is_offline() {
+        return Err(LauncherError::new(
+            ErrorKind::Offline,
+            format!(
+                "Offline: cannot fetch vendor history because {}.",
+                connectivity.describe()
+            ),
+        ));
+    }
+
+    log_line(&app, "Fetching the full vendor history...").await;
+    let fetch = RetryPolicy::for_class(OperationClass::Git)
+        .run(
+            |_| run_git(&repo, &["fetch", "--unshallow"]),
+            retry::is_transient_failure,
+        )
+        .await;
+    let output = fetch.value?;
+    if !output.status.success() {
+        let details = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(
+            LauncherError::new(ErrorKind::Git, "git fetch --unshallow failed.")
+                .with_details(details),
+        );
+    }
+
+    if has_submodules(&repo) {
+        log_line(&app, "Fetching the full history of vendor submodules...").await;
+        let output = run_git(
+            &repo,
+            &[
+                "submodule",
+                "foreach",
+                "--recursive",
+                "git fetch --unshallow || true",
+            ],
+        )
+        .await?;
+        if !output.status.success() {
+            log_line(&app, String::from_utf8_lossy(&output.stderr).trim()).await;
+        }
+    }
+
+    let message = "The vendor checkout now has its full history.".to_string();
+    log_line(&app, &message).await;
+    Ok(message)
+}
+
+#[tauri::command]
+async fn run_character_sync(app: AppHandle) -> Result<CharacterResponse, LauncherError> {
+    let _operation = operation::begin(&app, Operation::CharacterSync)?;
     load_env();
     let silly = silly_dir()?;
     let url = env::var("CHARACTER_SYNC_URL")
         .unwrap_or_else(|_| "https://mega.nz/folder/J5ARwZRI#2hnLHnLjXXNk3GGve7fjlw".into());
+    let manifest = characters::manifest_url();
+
+    if manifest.is_none() && url.trim().is_empty() {
+        return Ok(CharacterResponse {
+            success: false,
+            message: "Character sync URL is not configured.".into(),
+            offline: false,
+            attempts: 0,
+        });
+    }
+
+    let connectivity = offline::detect().await;
+    if connectivity.is_offline() {
+        let message = format!(
+            "Offline: skipping character sync because {}.",
+            connectivity.describe()
+        );
+        log_line(&app, &message).await;
+        return Ok(CharacterResponse {
+            success: false,
+            message,
+            offline: true,
+            attempts: 0,
+        });
+    }
 
-    if url.trim().is_empty() {
+    if let Err(err) = disk::ensure_space(&silly, DiskOperation::CharacterSync) {
+        let message = err.message;
+        log_line(&app, &message).await;
         return Ok(CharacterResponse {
             success: false,
-            message: "Character sync URL is not configured.".into(),
+            message,
+            offline: false,
+            attempts: 0,
         });
     }
 
     log_line(&app, "Checking for character updates...").await;
-    let mut cmd = TokioCommand::new("node");
-    cmd.current_dir(&silly);
-    apply_node_env(&mut cmd);
-    cmd.args(["character-downloader.js", &url, "-u"]);
-
-    let output = cmd.output().await.map_err(|e| e.to_string())?;
+    if let Some(manifest) = manifest {
+        let response = match characters::sync(&app, &silly, &manifest).await {
+            Ok(summary) => CharacterResponse {
+                success: summary.rejected == 0,
+                message: summary.message(),
+                offline: false,
+                attempts: 1,
+            },
+            Err(err) => {
+                log_line(&app, &err).await;
+                CharacterResponse {
+                    success: false,
+                    message: err,
+                    offline: false,
+                    attempts: 1,
+                }
+            }
+        };
+        notify::send(
+            &app,
+            NotifyEvent::CharacterSync,
+            if response.success {
+                "Character sync finished"
+            } else {
+                "Character sync failed"
+            },
+            &response.message,
+        );
+        return Ok(response);
+    }
+    let staging = if characters::review_enabled() {
+        Some(characters::staging().await?)
+    } else {
+        None
+    };
+    let script = silly.join("character-downloader.js");
+    let workdir = staging
+        .as_ref()
+        .map(|staging| staging.root().to_path_buf())
+        .unwrap_or_else(|| silly.clone());
+    let sync_policy = RetryPolicy::for_class(OperationClass::CharacterSync);
+    let sync = sync_policy
+        .run(
+            |attempt| {
+                let app = &app;
+                let script = &script;
+                let workdir = &workdir;
+                let url = &url;
+                async move {
+                    if attempt > 1 {
+                        log_line(
+                            app,
+                            &format!(
+                                "Retrying character sync (attempt {attempt} of {})...",
+                                sync_policy.max_attempts
+                            ),
+                        )
+                        .await;
+                    }
+                    let mut cmd = TokioCommand::new("node");
+                    cmd.current_dir(workdir);
+                    apply_node_env(&mut cmd);
+                    cmd.arg(script).args([url.as_str(), "-u"]);
+                    trace::spawning(&cmd);
+                    cmd.output().await.map_err(|e| e.to_string())
+                }
+            },
+            retry::is_transient_failure,
+        )
+        .await;
+    let attempts = sync.attempts;
+    let output = match sync.value {
+        Ok(output) => output,
+        Err(err) => {
+            notify::send(
+                &app,
+                NotifyEvent::CharacterSync,
+                "Character sync failed",
+                &err,
+            );
+            return Err(err.into());
+        }
+    };
+    let mut staged = 0;
+    if let Some(staging) = staging {
+        match characters::stage_downloads(&app, &silly, staging).await {
+            Ok(count) => staged = count,
+            Err(err) => log_line(&app, &err).await,
+        }
+    }
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
 
-    if output.status.success() {
+    let mut response = if output.status.success() {
         if !stdout.trim().is_empty() {
             log_line(&app, stdout.trim()).await;
         }
-        Ok(CharacterResponse {
+        CharacterResponse {
             success: true,
-            message: "Character update completed.".into(),
-        })
+            message: match retry::attempt_note(attempts) {
+                Some(note) => format!("Character update completed ({note})."),
+                None => "Character update completed.".into(),
+            },
+            offline: false,
+            attempts,
+        }
     } else {
         let combined = format!("{}{}", stdout, stderr);
         if !combined.trim().is_empty() {
             log_line(&app, combined.trim()).await;
         }
-        Ok(CharacterResponse {
+        CharacterResponse {
             success: false,
             message: "Character update failed. Check logs for details.".into(),
-        })
+            offline: false,
+            attempts,
+        }
+    };
+    if staged > 0 {
+        response
+            .message
+            .push_str(&format!(" {staged} card(s) are waiting for review."));
     }
+    notify::send(
+        &app,
+        NotifyEvent::CharacterSync,
+        if response.success {
+            "Character sync finished"
+        } else {
+            "Character sync failed"
+        },
+        &response.message,
+    );
+    Ok(response)
 }
 
 #[tauri::command]
@@ -452,107 +1593,286 @@ async fn start_server(
     app: AppHandle,
     state: tauri::State<'_, ServerState>,
     force: Option<bool>,
-) -> Result<(), String> {
+) -> Result<(), LauncherError> {
+    let _operation = operation::begin(&app, Operation::ServerStart)?;
     let force = force.unwrap_or(false);
-    launch(&app, state, force).await
+    let result = launch(&app, state, force).await;
+    if let Err(err) = &result {
+        prompt::for_start_failure(err).emit(&app);
+        if cli::start_minimized() {
+            tray::show_windows(&app);
+        }
+    }
+    result
 }
 
-async fn command_exists(program: &OsStr) -> bool {
-    TokioCommand::new(program.to_os_string())
-        .arg("--version")
-        .status()
-        .await
-        .map(|status| status.success())
-        .unwrap_or(false)
+#[tauri::command]
+async fn server_status(
+    state: tauri::State<'_, ServerState>,
+) -> Result<ServerStatus, LauncherError> {
+    let pid = state
+        .inner()
+        .child
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|child| child.id());
+    Ok(ServerStatus {
+        running: pid.is_some(),
+        pid,
+        tuning: pid.and_then(|_| state.inner().tuning.lock().unwrap().clone()),
+    })
 }
 
-async fn locate_npm(app: &AppHandle) -> Result<NpmTool, String> {
-    if let Some(custom) = env::var_os("NPM_BIN").filter(|value| !value.is_empty()) {
-        if command_exists(custom.as_os_str()).await {
-            let location = PathBuf::from(&custom);
-            log_line(
-                app,
-                &format!(
-                    "Using npm from {} as configured via NPM_BIN.",
-                    location.display()
-                ),
-            )
-            .await;
-            return Ok(NpmTool::Binary(custom));
-        } else {
-            let location = PathBuf::from(&custom);
-            return Err(format!(
-                "Configured NPM_BIN at {} is not executable. Install npm or update NPM_BIN.",
-                location.display()
-            ));
+async fn ensure_command(bin: &str) -> Result<(), LauncherError> {
+    let message = match TokioCommand::new(bin).arg("--version").status().await {
+        Ok(status) if status.success() => return Ok(()),
+        Ok(status) => match status.code() {
+            Some(code) => format!("{bin} --version exited with status {code}"),
+            None => format!("{bin} --version failed"),
+        },
+        Err(err) => {
+            format!("{bin} not found. Install {bin} and ensure it is on your PATH. ({err})")
         }
-    }
+    };
+    Err(LauncherError::new(ErrorKind::NotConfigured, message))
+}
 
-    for candidate in NPM_CANDIDATES {
-        if command_exists(OsStr::new(candidate)).await {
-            return Ok(NpmTool::Binary(OsString::from(candidate)));
-        }
+#[tauri::command]
+async fn check_dependencies(app: AppHandle) -> Result<(), LauncherError> {
+    let _operation = operation::begin(&app, Operation::DependencyCheck)?;
+    load_env();
+    ensure_command("git").await?;
+    ensure_command("node").await?;
+    if let Some(blocked) = access::blocked() {
+        return Err(
+            LauncherError::new(ErrorKind::ReadOnlyPath, blocked.message())
+                .with_details(blocked.path()),
+        );
     }
+    let project = silly_dir().unwrap_or_else(|_| PathBuf::from("."));
+    PackageManager::locate(&app, &project).await?;
+    onboarding::complete(&app, OnboardingStep::DepsVerified).await;
+    Ok(())
+}
+
+fn frozen_install(silly_dir: &Path, kind: PackageManagerKind) -> bool {
+    let npm_mode = env::var("NPM_MODE").unwrap_or_else(|_| "install".into());
+    npm_mode.trim().eq_ignore_ascii_case("ci") && silly_dir.join(kind.lockfile()).exists()
+}
 
+async fn install_packages(
+    app: &AppHandle,
+    silly_dir: &Path,
+    connectivity: offline::Connectivity,
+) -> Result<(), LauncherError> {
+    disk::ensure_space(silly_dir, DiskOperation::NpmInstall)?;
+    let manager = PackageManager::locate(app, silly_dir).await?;
+    let name = manager.kind().name();
+    let lockfile = manager.kind().lockfile();
+    let npm_mode_raw = env::var("NPM_MODE").unwrap_or_else(|_| "install".into());
+    let npm_mode = npm_mode_raw.trim().to_ascii_lowercase();
+    let lock_exists = silly_dir.join(lockfile).exists();
+    let use_ci = frozen_install(silly_dir, manager.kind());
+    if npm_mode == "ci" && !lock_exists {
+        log_line(
+            app,
+            &format!("{lockfile} missing; falling back to {name} install."),
+        )
+        .await;
+    }
+    let install_args = manager.install_args(use_ci, connectivity);
     log_line(
         app,
-        "npm executable not found on PATH; attempting to use the npm-cli.js bundled with Node.",
+        &format!(
+            "Installing Node modules ({name} {})...",
+            install_args.join(" ")
+        ),
     )
     .await;
-
-    let mut node_cmd = TokioCommand::new("node");
-    apply_node_env(&mut node_cmd);
-    let output = node_cmd
-        .args(["-p", "require.resolve('npm/bin/npm-cli.js')"])
-        .output()
-        .await
-        .map_err(|e| format!("Unable to locate npm via node: {e}"))?;
+    let npm_policy = RetryPolicy::for_class(OperationClass::Npm);
+    let install = npm_policy
+        .run(
+            |attempt| {
+                let manager = &manager;
+                let install_args = &install_args;
+                async move {
+                    if attempt > 1 {
+                        log_line(
+                            app,
+                            &format!(
+                                "Retrying {name} install (attempt {attempt} of {})...",
+                                npm_policy.max_attempts
+                            ),
+                        )
+                        .await;
+                    }
+                    let mut cmd = manager.command();
+                    cmd.current_dir(silly_dir);
+                    cmd.args(install_args);
+                    trace::spawning(&cmd);
+                    cmd.output().await.map_err(|e| e.to_string())
+                }
+            },
+            retry::is_transient_failure,
+        )
+        .await;
+    let npm_attempts = install.attempts;
+    let output = install.value?;
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
-    let script = stdout.trim();
-
-    if output.status.success() && !script.is_empty() {
-        let path = PathBuf::from(script);
-        log_line(
+    if !output.status.success() {
+        let combined = format!("{}{}", stdout, stderr);
+        let trimmed = combined.trim();
+        if !trimmed.is_empty() {
+            log_line(app, trimmed).await;
+        }
+        notify::send(
             app,
-            &format!(
-                "Resolved npm-cli.js at {}. Falling back to running npm via node.",
-                path.display()
-            ),
-        )
-        .await;
-        Ok(NpmTool::Script(path))
-    } else {
-        let mut message = String::from(
-            "npm not found. Install Node.js (which includes npm) or set NPM_BIN to the npm executable path.",
+            NotifyEvent::NpmInstall,
+            "Node modules install failed",
+            &format!("{name} install failed. Check the launcher logs for details."),
         );
-        let details = stderr.trim();
-        if !details.is_empty() {
-            message.push(' ');
-            message.push_str(details);
-        }
-        Err(message)
+        let message = if trimmed.is_empty() {
+            format!("{name} install failed. Check logs for details.")
+        } else {
+            format!("{name} install failed.")
+        };
+        return Err(LauncherError::new(ErrorKind::NpmInstallFailed, message).with_details(trimmed));
+    }
+    let success_output = stdout.trim();
+    if !success_output.is_empty() {
+        log_line(app, success_output).await;
+    }
+    let error_output = stderr.trim();
+    if !error_output.is_empty() {
+        log_line(app, error_output).await;
+    }
+    if let Some(note) = retry::attempt_note(npm_attempts) {
+        log_line(app, &format!("{name} install {note}.")).await;
     }
+    if let Err(err) = packages::record_lockfile_hash(silly_dir, manager.kind()) {
+        log_line(app, &format!("Failed to record the {lockfile} hash: {err}")).await;
+    }
+    notify::send(
+        app,
+        NotifyEvent::NpmInstall,
+        "Node modules installed",
+        &format!("{name} install finished."),
+    );
+    Ok(())
 }
 
-async fn ensure_command(bin: &str) -> Result<(), String> {
-    match TokioCommand::new(bin).arg("--version").status().await {
-        Ok(status) if status.success() => Ok(()),
-        Ok(status) => Err(match status.code() {
-            Some(code) => format!("{bin} --version exited with status {code}"),
-            None => format!("{bin} --version failed"),
-        }),
-        Err(err) => Err(format!(
-            "{bin} not found. Install {bin} and ensure it is on your PATH. ({err})"
-        )),
+async fn run_cache_command(
+    app: &AppHandle,
+    manager: &PackageManager,
+    dir: &Path,
+    args: &[&str],
+) -> Result<(), String> {
+    let label = format!("{} {}", manager.kind().name(), args.join(" "));
+    log_line(app, &format!("Running {label}...")).await;
+    let mut cmd = manager.command();
+    cmd.current_dir(dir);
+    cmd.args(args);
+    trace::spawning(&cmd);
+    let output = cmd.output().await.map_err(|e| e.to_string())?;
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let trimmed = combined.trim();
+    if !trimmed.is_empty() {
+        log_line(app, trimmed).await;
+    }
+    if !output.status.success() {
+        return Err(format!("{label} failed. Check logs for details."));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn repair_node_modules(
+    app: AppHandle,
+    state: tauri::State<'_, ServerState>,
+    cache: Option<NpmCacheAction>,
+    dry_run: Option<bool>,
+) -> Result<String, LauncherError> {
+    let _operation = operation::begin(&app, Operation::NodeModulesRepair)?;
+    load_env();
+    let silly_dir = silly_dir()?;
+    installs::ensure_stopped(state.inner(), "repairing node_modules")?;
+
+    let cache = cache.unwrap_or_default();
+    let connectivity = offline::detect().await;
+    if connectivity.is_offline() && matches!(cache, NpmCacheAction::Clean) {
+        return Err(LauncherError::new(
+            ErrorKind::Offline,
+            format!(
+                "Refusing to clean the npm cache while offline ({}): the reinstall would have nothing to install from.",
+                connectivity.describe()
+            ),
+        ));
+    }
+
+    let modules = silly_dir.join("node_modules");
+    if dryrun::requested(dry_run) {
+        let manager = PackageManager::locate(&app, &silly_dir).await?;
+        let name = manager.kind().name();
+        let mut plan = DryRun::new("repair_node_modules");
+        if modules.exists() {
+            plan.file(Change::Remove, &modules);
+        }
+        if let Some(args) = cache.action().and_then(|action| manager.cache_args(action)) {
+            plan.command(name, args, &silly_dir);
+        }
+        let install_args =
+            manager.install_args(frozen_install(&silly_dir, manager.kind()), connectivity);
+        plan.command(name, &install_args, &silly_dir);
+        plan.file(Change::Create, &modules);
+        let plan = plan
+            .finish(
+                &app,
+                format!("repairing node_modules would remove it and reinstall with {name}."),
+            )
+            .await;
+        return Ok(plan.summary().to_string());
+    }
+    if modules.exists() {
+        log_line(&app, "Removing node_modules...").await;
+        tokio_fs::remove_dir_all(&modules)
+            .await
+            .map_err(|e| format!("Failed to remove {}: {e}", modules.display()))?;
+    }
+
+    if let Some(action) = cache.action() {
+        let manager = PackageManager::locate(&app, &silly_dir).await?;
+        let name = manager.kind().name();
+        match manager.cache_args(action) {
+            Some(args) => run_cache_command(&app, &manager, &silly_dir, args).await?,
+            None => {
+                log_line(
+                    &app,
+                    &format!("{name} has no cache verification step; skipping it."),
+                )
+                .await
+            }
+        }
     }
+
+    install_packages(&app, &silly_dir, connectivity).await?;
+    onboarding::complete(&app, OnboardingStep::FirstInstall).await;
+
+    let message = "node_modules repaired successfully.".to_string();
+    log_line(&app, &message).await;
+    Ok(message)
 }
 
 async fn launch(
     app: &AppHandle,
     state: tauri::State<'_, ServerState>,
     force_start: bool,
-) -> Result<(), String> {
+) -> Result<(), LauncherError> {
     load_env();
     let silly_dir = silly_dir()?;
 
@@ -560,14 +1880,39 @@ async fn launch(
         log_line(app, "WeylandTavern is already running.").await;
         return Ok(());
     }
+    if let Some(blocked) = access::blocked() {
+        return Err(
+            LauncherError::new(ErrorKind::ReadOnlyPath, blocked.message())
+                .with_details(blocked.path()),
+        );
+    }
 
     let run_npm = env::var("RUN_NPM_INSTALL").unwrap_or_else(|_| "auto".into());
     let run_npm = run_npm.trim().to_ascii_lowercase();
-    let needs_npm_install = should_npm_install(&run_npm, &silly_dir)?;
+    let decision = should_npm_install(&run_npm, &silly_dir)
+        .map_err(|err| LauncherError::new(ErrorKind::Failed, err))?;
+    log_line(
+        app,
+        &if decision.install {
+            format!("Installing Node modules: {}.", decision.reason)
+        } else {
+            format!("Skipping Node module install: {}.", decision.reason)
+        },
+    )
+    .await;
 
     ensure_command("node").await?;
 
-    if needs_npm_install {
+    let connectivity = offline::detect().await;
+    if connectivity.is_offline() {
+        log_line(
+            app,
+            &format!("Running in offline mode: {}.", connectivity.describe()),
+        )
+        .await;
+    }
+
+    if decision.install {
         if force_start {
             log_line(
                 app,
@@ -575,66 +1920,20 @@ async fn launch(
             )
             .await;
         } else {
-            let npm_tool = locate_npm(app).await?;
-            let npm_mode_raw = env::var("NPM_MODE").unwrap_or_else(|_| "install".into());
-            let npm_mode = npm_mode_raw.trim().to_ascii_lowercase();
-            let lock_exists = silly_dir.join("package-lock.json").exists();
-            let mut cmd = npm_tool.into_command();
-
-            cmd.current_dir(&silly_dir);
-            apply_node_env(&mut cmd);
-            if npm_mode == "ci" && lock_exists {
-                cmd.arg("ci");
-            } else {
-                if npm_mode == "ci" && !lock_exists {
-                    log_line(
-                        app,
-                        "package-lock.json missing; falling back to npm install.",
-                    )
-                    .await;
-                }
-                cmd.args([
-                    "install",
-                    "--no-audit",
-                    "--no-fund",
-                    "--loglevel=error",
-                    "--no-progress",
-                    "--omit=dev",
-                ]);
-            }
-            log_line(app, "Installing Node modules...").await;
-            let output = cmd.output().await.map_err(|e| e.to_string())?;
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if !output.status.success() {
-                let combined = format!("{}{}", stdout, stderr);
-                let trimmed = combined.trim();
-                if !trimmed.is_empty() {
-                    log_line(app, trimmed).await;
-                }
-                return Err(if trimmed.is_empty() {
-                    "NPM_INSTALL_FAILED::npm install failed. Check logs for details.".into()
-                } else {
-                    format!(
-                        "NPM_INSTALL_FAILED::npm install failed. Details: {}",
-                        trimmed
-                    )
-                });
-            } else {
-                let success_output = stdout.trim();
-                if !success_output.is_empty() {
-                    log_line(app, success_output).await;
-                }
-                let error_output = stderr.trim();
-                if !error_output.is_empty() {
-                    log_line(app, error_output).await;
-                }
-            }
+            install_packages(app, &silly_dir, connectivity).await?;
         }
     }
 
+    if !force_start && silly_dir.join("node_modules").is_dir() {
+        onboarding::complete(app, OnboardingStep::FirstInstall).await;
+    }
+
     let host = env::var("SERVER_HOST").unwrap_or_else(|_| "127.0.0.1".into());
-    let port = determine_port(&silly_dir, &host)?;
+    let port = determine_port(&silly_dir, &host)
+        .map_err(|err| LauncherError::new(ErrorKind::NotConfigured, err))?;
+    let url = format!("http://{}:{}/", host, port);
+    let probe = health::HealthProbe::from_env(&url)
+        .map_err(|err| LauncherError::new(ErrorKind::NotConfigured, err))?;
     let mut args: Vec<String> = env::var("SERVER_ARGS")
         .unwrap_or_default()
         .split_whitespace()
@@ -666,13 +1965,61 @@ async fn launch(
     if !args_contains_flag(&args, "--no-open") {
         args.push("--no-open".into());
     }
+    if let Some(root) = userdata::configured() {
+        if !args_contains_flag(&args, userdata::DATA_ROOT_FLAG) {
+            tokio_fs::create_dir_all(&root).await.map_err(|e| {
+                LauncherError::new(
+                    ErrorKind::SpawnFailed,
+                    format!("Failed to create data root {}: {e}", root.display()),
+                )
+            })?;
+            args.push(userdata::DATA_ROOT_FLAG.into());
+            args.push(root.display().to_string());
+        }
+    }
+
+    let hook_ctx = HookContext {
+        host: host.clone(),
+        port,
+        url: url.clone(),
+        silly_dir: silly_dir.clone(),
+    };
+    hooks::run(app, Hook::PreStart, &hook_ctx)
+        .await
+        .map_err(|err| {
+            LauncherError::new(
+                ErrorKind::HookFailed,
+                "Launch aborted: the pre_start hook failed.",
+            )
+            .with_details(err)
+        })?;
 
+    if let Some(session) = recovery::unclean_session() {
+        log_line(
+            app,
+            &format!(
+                "The previous server session (started {}) did not shut down cleanly; checking recent chats.",
+                session.started_at()
+            ),
+        )
+        .await;
+        recovery::verify_recent_chats(app, &silly_dir).await;
+    }
+    settings::snapshot(app, &silly_dir).await;
     log_line(app, "Starting WeylandTavern...").await;
 
-    let logs_dir = PathBuf::from("logs");
+    let spawn_failed = |message: String| {
+        move |e: std::io::Error| {
+            LauncherError::new(ErrorKind::SpawnFailed, format!("{message}: {e}"))
+        }
+    };
+    let logs_dir = logs_dir();
     tokio_fs::create_dir_all(&logs_dir)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(spawn_failed(format!(
+            "Failed to create {}",
+            logs_dir.display()
+        )))?;
     let log_path = logs_dir.join(format!("server-{}.log", Local::now().format("%Y%m%d")));
     let file = Arc::new(AsyncMutex::new(
         OpenOptions::new()
@@ -680,7 +2027,10 @@ async fn launch(
             .append(true)
             .open(&log_path)
             .await
-            .map_err(|e| e.to_string())?,
+            .map_err(spawn_failed(format!(
+                "Failed to open {}",
+                log_path.display()
+            )))?,
     ));
 
     let mut cmd = TokioCommand::new("node");
@@ -699,15 +2049,22 @@ async fn launch(
     }
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
-    let mut child = cmd.spawn().map_err(|e| e.to_string())?;
+    trace::spawning(&cmd);
+    let mut child = cmd
+        .spawn()
+        .map_err(spawn_failed("Failed to start node server.js".into()))?;
 
     let stdout = child.stdout.take();
     let stderr = child.stderr.take();
 
     #[cfg(windows)]
     unsafe {
-        let job_handle = CreateJobObjectW(None, PCWSTR::null())
-            .map_err(|e| format!("CreateJobObjectW failed: {e}"))?;
+        let job_handle = CreateJobObjectW(None, PCWSTR::null()).map_err(|e| {
+            LauncherError::new(
+                ErrorKind::SpawnFailed,
+                format!("CreateJobObjectW failed: {e}"),
+            )
+        })?;
         let job = JobHandle::new(job_handle);
         let mut info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
         info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
@@ -717,13 +2074,26 @@ async fn launch(
             &info as *const _ as *const _,
             std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
         )
-        .map_err(|e| format!("SetInformationJobObject failed: {e}"))?;
-        let pid = child.id().ok_or("pid unavailable")? as u32;
-        let process = OpenProcess(PROCESS_ALL_ACCESS, false, pid)
-            .map_err(|e| format!("OpenProcess failed: {e}"))?;
+        .map_err(|e| {
+            LauncherError::new(
+                ErrorKind::SpawnFailed,
+                format!("SetInformationJobObject failed: {e}"),
+            )
+        })?;
+        let pid = child
+            .id()
+            .ok_or_else(|| LauncherError::new(ErrorKind::SpawnFailed, "pid unavailable"))?;
+        let process = OpenProcess(PROCESS_ALL_ACCESS, false, pid).map_err(|e| {
+            LauncherError::new(ErrorKind::SpawnFailed, format!("OpenProcess failed: {e}"))
+        })?;
         let assign_result = AssignProcessToJobObject(job.raw(), process);
         let _ = CloseHandle(process);
-        assign_result.map_err(|e| format!("AssignProcessToJobObject failed: {e}"))?;
+        assign_result.map_err(|e| {
+            LauncherError::new(
+                ErrorKind::SpawnFailed,
+                format!("AssignProcessToJobObject failed: {e}"),
+            )
+        })?;
         state.inner().job.lock().unwrap().replace(job);
     }
 
@@ -731,8 +2101,8 @@ async fn launch(
         let app_for_logs = app.clone();
         let log_file = file.clone();
         tauri::async_runtime::spawn(async move {
-            let mut reader = BufReader::new(stdout).lines();
-            while let Ok(Some(line)) = reader.next_line().await {
+            let mut reader = LossyLines::new(stdout);
+            while let Some(line) = reader.next_line().await {
                 let _ = append_log(&app_for_logs, &log_file, &line).await;
             }
         });
@@ -742,49 +2112,157 @@ async fn launch(
         let app_for_logs = app.clone();
         let log_file = file.clone();
         tauri::async_runtime::spawn(async move {
-            let mut reader = BufReader::new(stderr).lines();
-            while let Ok(Some(line)) = reader.next_line().await {
+            let mut reader = LossyLines::new(stderr);
+            while let Some(line) = reader.next_line().await {
                 let _ = append_log(&app_for_logs, &log_file, &line).await;
             }
         });
     }
 
-    state.inner().child.lock().unwrap().replace(child);
+    if let Some(pid) = child.id() {
+        let applied = tuning::apply(pid);
+        for warning in applied.warnings() {
+            log_line(app, warning).await;
+        }
+        state.inner().tuning.lock().unwrap().replace(applied);
+    }
 
-    let url = format!("http://{}:{}/", host, port);
-    if wait_for_health(&url).await {
+    let pid = child.id();
+    recovery::mark_running(pid);
+    state.inner().child.lock().unwrap().replace(child);
+    if let Some(pid) = pid {
+        watch_for_exit(app.clone(), pid, silly_dir.clone());
+    }
+    state
+        .inner()
+        .hooks
+        .lock()
+        .unwrap()
+        .replace(hook_ctx.clone());
+
+    let skip_health = connectivity.is_offline() && !offline::is_local_host(&host);
+    if skip_health {
+        log_line(
+            app,
+            &format!("Offline: skipping health probe for remote host {}.", host),
+        )
+        .await;
+    }
+    let (health, health_attempts) = if skip_health {
+        (Ok(()), 0)
+    } else {
+        let health = probe.wait_until_healthy().await;
+        (health.value, health.attempts)
+    };
+    if health.is_ok() {
+        if let Some(note) = retry::attempt_note(health_attempts) {
+            log_line(app, &format!("Health check {note}.")).await;
+        }
         let friendly = format!(
             "WeylandTavern is now active on {}:{} (By default)",
             host, port
         );
         log_line(app, &friendly).await;
-        app.emit("server-ready", &url).ok();
+        match share::start_proxy(app, &host, port).await {
+            Ok(Some(handle)) => {
+                state.inner().share_proxy.lock().unwrap().replace(handle);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                log_line(app, &format!("Guest sharing proxy failed to start: {err}")).await;
+            }
+        }
+        match tls::start(app, &host, port).await {
+            Ok(Some(handle)) => {
+                state.inner().tls_proxy.lock().unwrap().replace(handle);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                log_line(app, &format!("HTTPS proxy failed to start: {err}")).await;
+            }
+        }
+        match tunnel::start(app, &host, port).await {
+            Ok(Some(tunnel)) => {
+                state.inner().tunnel.lock().unwrap().replace(tunnel);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                let hint = err.remediation.clone().unwrap_or_default();
+                log_line(
+                    app,
+                    &format!("Remote access tunnel failed to start: {err} {hint}"),
+                )
+                .await;
+            }
+        }
+        if let Some(handle) = redirect::migrate(app, &host, port).await {
+            state.inner().port_redirect.lock().unwrap().replace(handle);
+        }
+        hooks::run_logged(app, Hook::PostStart, &hook_ctx).await;
+        if !skip_health {
+            if let Some(handle) = health::spawn_liveness(app, probe) {
+                state.inner().liveness.lock().unwrap().replace(handle);
+            }
+        }
+        if let Some(handle) = drift::spawn_watcher(app, silly_dir.clone()) {
+            state.inner().config_watch.lock().unwrap().replace(handle);
+        }
+        state
+            .inner()
+            .playtime
+            .lock()
+            .unwrap()
+            .replace(playtime::start_session());
+        onboarding::complete(app, OnboardingStep::FirstLaunch).await;
+        instance::publish_url(Some(&url));
+        events::emit(app, "server-ready", &url);
         Ok(())
     } else {
+        let reason = health.err().unwrap_or_default();
         let message = format!(
-            "Failed to verify server health at {}. Please check the logs.",
-            url
+            "Failed to verify server health at {} ({reason}). Please check the logs.",
+            probe.url()
         );
         log_line(app, &message).await;
-        shutdown(app.state::<ServerState>()).await;
-        Err(message)
+        shutdown(app, false).await;
+        Err(LauncherError::new(ErrorKind::HealthCheck, message))
+    }
+}
+
+fn emit_log(app: &AppHandle, line: &LogLine) {
+    events::emit(app, "log", line.text.clone());
+    if !line.spans.is_empty() && sanitize::colors_enabled() {
+        events::emit(app, "log-styled", line);
     }
 }
 
 async fn append_log(
     app: &AppHandle,
     file: &Arc<AsyncMutex<tokio::fs::File>>,
-    line: &str,
+    raw: &str,
 ) -> Result<(), ()> {
+    let line = sanitize::parse(raw);
+    if line.text.is_empty() && !raw.is_empty() {
+        return Ok(());
+    }
     let mut f = file.lock().await;
-    let _ = f.write_all(line.as_bytes()).await;
+    let _ = f.write_all(line.text.as_bytes()).await;
     let _ = f.write_all(b"\n").await;
-    let _ = app.emit("log", line.to_string());
+    observer::record(&line.text);
+    emit_log(app, &line);
     Ok(())
 }
 
-async fn log_line(app: &AppHandle, line: &str) {
-    let _ = app.emit("log", line.to_string());
+async fn log_line(app: &AppHandle, raw: &str) {
+    let line = sanitize::parse(raw);
+    if line.text.is_empty() && !raw.is_empty() {
+        return;
+    }
+    observer::record(&line.text);
+    if cli::is_headless() {
+        cli::print_event("log", &line.text);
+    }
+    emit_log(app, &line);
 }
 
 fn parse_port(value: &str) -> Option<u16> {
@@ -834,73 +2312,108 @@ fn is_port_available(host: &str, port: u16) -> bool {
         return false;
     }
 
-    TcpListener::bind((host, port))
-        .map(|listener| drop(listener))
-        .is_ok()
+    TcpListener::bind((host, port)).map(drop).is_ok()
 }
 
 fn determine_port(silly_dir: &Path, host: &str) -> Result<u16, String> {
     if let Some(port) = silly_env_port(silly_dir)? {
+        tracing::info!(port, "port taken from SillyTavern's .env PORT");
         return Ok(port);
     }
 
-    if let Some(port) = env::var("SERVER_PORT")
-        .ok()
-        .and_then(|value| parse_port(&value))
-    {
+    let server_port = env::var("SERVER_PORT").ok();
+    if let Some(port) = server_port.as_deref().and_then(parse_port) {
+        tracing::info!(port, "port taken from SERVER_PORT");
         return Ok(port);
     }
+    if let Some(raw) = &server_port {
+        tracing::info!(value = %raw, "SERVER_PORT is not a valid port; trying fallbacks");
+    }
 
     for candidate in FALLBACK_PORTS {
         if is_port_available(host, *candidate) {
+            tracing::info!(
+                port = *candidate,
+                host,
+                "picked the first free fallback port"
+            );
             return Ok(*candidate);
         }
+        tracing::info!(port = *candidate, host, "fallback port is busy");
     }
 
     Err("Unable to determine an available server port.".into())
 }
 
-fn should_npm_install(mode: &str, dir: &PathBuf) -> Result<bool, String> {
+struct InstallDecision {
+    install: bool,
+    reason: String,
+}
+
+impl InstallDecision {
+    fn new(install: bool, reason: impl Into<String>) -> Self {
+        Self {
+            install,
+            reason: reason.into(),
+        }
+    }
+}
+
+fn should_npm_install(mode: &str, dir: &Path) -> Result<InstallDecision, String> {
     if mode == "never" {
-        return Ok(false);
+        return Ok(InstallDecision::new(
+            false,
+            "RUN_NPM_INSTALL is set to never",
+        ));
     }
     if mode == "always" {
-        return Ok(true);
+        return Ok(InstallDecision::new(
+            true,
+            "RUN_NPM_INSTALL is set to always",
+        ));
     }
     let node_modules = dir.join("node_modules");
     if !node_modules.exists() {
-        return Ok(true);
+        return Ok(InstallDecision::new(true, "node_modules is missing"));
     }
-    let lock_file = dir.join("package-lock.json");
-    if lock_file.exists() {
-        let lm = stdfs::metadata(&lock_file)
-            .map_err(|e| e.to_string())?
-            .modified()
-            .map_err(|e| e.to_string())?;
-        let nm = stdfs::metadata(&node_modules)
-            .map_err(|e| e.to_string())?
-            .modified()
-            .map_err(|e| e.to_string())?;
-        return Ok(lm > nm);
+    let kind = PackageManagerKind::detect(dir);
+    let lockfile = kind.lockfile();
+    let lock_file = dir.join(lockfile);
+    if !lock_file.exists() {
+        return Ok(InstallDecision::new(
+            false,
+            format!("no {lockfile} to compare against"),
+        ));
     }
-    Ok(false)
-}
-
-async fn wait_for_health(url: &str) -> bool {
-    let client = reqwest::Client::new();
-    for i in 0..30u64 {
-        if client
-            .get(url)
-            .send()
-            .await
-            .map(|r| r.status().is_success())
-            .unwrap_or(false)
-        {
-            return true;
-        }
-        sleep(Duration::from_millis(500 + i * 100)).await;
+    if mode == "hash" {
+        let current = packages::lockfile_hash(dir, kind)?;
+        return Ok(match packages::recorded_lockfile_hash(dir) {
+            Some(recorded) if recorded == current => InstallDecision::new(
+                false,
+                format!("{lockfile} matches the hash recorded after the last install"),
+            ),
+            Some(_) => {
+                InstallDecision::new(true, format!("{lockfile} changed since the last install"))
+            }
+            None => InstallDecision::new(
+                true,
+                format!("no install has been recorded for the current {lockfile}"),
+            ),
+        });
     }
-    false
+    let lm = stdfs::metadata(&lock_file)
+        .map_err(|e| e.to_string())?
+        .modified()
+        .map_err(|e| e.to_string())?;
+    let nm = stdfs::metadata(&node_modules)
+        .map_err(|e| e.to_string())?
+        .modified()
+        .map_err(|e| e.to_string())?;
+    Ok(if lm > nm {
+        InstallDecision::new(true, format!("{lockfile} is newer than node_modules"))
+    } else {
+        InstallDecision::new(false, format!("node_modules is newer than {lockfile}"))
+    })
 }
 
 #[cfg(windows)]
@@ -940,11 +2453,82 @@ async fn terminate_process_tree(mut child: TokioChild) {
     }
 }
 
-async fn shutdown(state: tauri::State<'_, ServerState>) {
+fn watch_for_exit(app: AppHandle, pid: u32, silly_dir: PathBuf) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            let exited = {
+                let state = app.state::<ServerState>();
+                let mut guard = state.inner().child.lock().unwrap();
+                match guard.as_mut() {
+                    Some(child) if child.id() == Some(pid) => match child.try_wait() {
+                        Ok(Some(status)) => {
+                            guard.take();
+                            Some(status)
+                        }
+                        _ => None,
+                    },
+                    _ => return,
+                }
+            };
+            let Some(status) = exited else {
+                continue;
+            };
+            let message = format!("WeylandTavern exited unexpectedly ({status}).");
+            log_line(&app, &message).await;
+            shutdown(&app, true).await;
+            events::emit(&app, "server-crashed", &message);
+            notify::server_crashed(&app, &message);
+            recovery::verify_recent_chats(&app, &silly_dir).await;
+            return;
+        }
+    });
+}
+
+// `exited` means the server process is already gone (watch_for_exit took the
+// child), so there is nothing to run pre_stop against but post_stop still runs.
+async fn shutdown(app: &AppHandle, exited: bool) {
+    let state = app.state::<ServerState>();
+    let running = exited || state.inner().child.lock().unwrap().is_some();
+    let hook_ctx = state
+        .inner()
+        .hooks
+        .lock()
+        .unwrap()
+        .take()
+        .filter(|_| running);
+    if let Some(ctx) = hook_ctx.as_ref().filter(|_| !exited) {
+        hooks::run_logged(app, Hook::PreStop, ctx).await;
+    }
+
+    if let Some(proxy) = state.inner().share_proxy.lock().unwrap().take() {
+        let _ = proxy.send(());
+    }
+    if let Some(proxy) = state.inner().tls_proxy.lock().unwrap().take() {
+        let _ = proxy.send(());
+    }
+    let tunnel = state.inner().tunnel.lock().unwrap().take();
+    if let Some(tunnel) = tunnel {
+        tunnel.stop().await;
+    }
+    if let Some(redirect) = state.inner().port_redirect.lock().unwrap().take() {
+        let _ = redirect.send(());
+    }
+    if let Some(liveness) = state.inner().liveness.lock().unwrap().take() {
+        let _ = liveness.send(());
+    }
+    if let Some(watch) = state.inner().config_watch.lock().unwrap().take() {
+        let _ = watch.send(());
+    }
+    if let Some(session) = state.inner().playtime.lock().unwrap().take() {
+        let _ = session.send(());
+    }
+
     let child = {
         let mut guard = state.inner().child.lock().unwrap();
         guard.take()
     };
+    state.inner().tuning.lock().unwrap().take();
 
     #[cfg(windows)]
     let job = {
@@ -970,4 +2554,31 @@ async fn shutdown(state: tauri::State<'_, ServerState>) {
             }
         }
     }
+    if let Some(ctx) = &hook_ctx {
+        hooks::run_logged(app, Hook::PostStop, ctx).await;
+    }
+    instance::publish_url(None);
+    recovery::mark_stopped();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn submodule_changes_lists_moved_and_added_submodules() {
+        let before = " 1111111111aaaa plugins/a (v1)\n 2222222222bbbb plugins/b (v1)\n";
+        let after = " 1111111111aaaa plugins/a (v1)\n+3333333333cccc plugins/b (v2)\n-4444444444dddd plugins/c\n";
+        assert_eq!(
+            submodule_changes(before, after),
+            "plugins/b: 22222222 -> 33333333\nplugins/c: added at 44444444"
+        );
+    }
+
+    #[test]
+    fn submodule_changes_is_empty_without_changes() {
+        let status = " 1111111111aaaa plugins/a (v1)\n";
+        assert_eq!(submodule_changes(status, status), "");
+        assert_eq!(submodule_changes("", ""), "");
+    }
 }
@@ -0,0 +1,384 @@
+use std::{
+    env, fs as stdfs,
+    io::Read,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+use tokio::fs as tokio_fs;
+
+use crate::{
+    error::{ErrorKind, LauncherError},
+    load_env, log_line, offline,
+    operation::{self, Operation},
+    vendor_dir,
+};
+
+const DEFAULT_MANIFEST_NAME: &str = "release-manifest.json";
+const EXCLUDED_DIRS: &[&str] = &[".git", "node_modules", "data", "backups"];
+const EXCLUDED_FILES: &[&str] = &[DEFAULT_MANIFEST_NAME, "WTUpdate.log", ".env"];
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReleaseManifest {
+    version: String,
+    #[serde(default)]
+    base_url: Option<String>,
+    files: Vec<ManifestEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ManifestEntry {
+    path: String,
+    sha256: String,
+    size: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct VerificationReport {
+    manifest_version: String,
+    checked: usize,
+    intact: usize,
+    modified: Vec<String>,
+    missing: Vec<String>,
+    can_redownload: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RepairFailure {
+    path: String,
+    reason: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RepairReport {
+    repaired: Vec<String>,
+    failed: Vec<RepairFailure>,
+}
+
+fn manifest_source(repo: &Path) -> String {
+    env::var("RELEASE_MANIFEST")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| value.trim().to_string())
+        .unwrap_or_else(|| {
+            repo.join(DEFAULT_MANIFEST_NAME)
+                .to_string_lossy()
+                .into_owned()
+        })
+}
+
+fn is_remote(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+async fn load_manifest(source: &str) -> Result<ReleaseManifest, String> {
+    let raw = if is_remote(source) {
+        if offline::detect().await.is_offline() {
+            return Err(format!(
+                "Offline: cannot download the release manifest from {source}."
+            ));
+        }
+        reqwest::get(source)
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Failed to download release manifest: {e}"))?
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read release manifest: {e}"))?
+    } else {
+        tokio_fs::read_to_string(source)
+            .await
+            .map_err(|e| format!("Failed to read release manifest at {source}: {e}"))?
+    };
+    serde_json::from_str(&raw).map_err(|e| format!("Invalid release manifest: {e}"))
+}
+
+fn resolve_entry(repo: &Path, relative: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(relative);
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err(format!(
+            "Manifest path {} escapes the vendor directory",
+            relative.display()
+        ));
+    }
+    Ok(repo.join(relative))
+}
+
+fn hash_file(path: &Path) -> Result<String, String> {
+    let mut file = stdfs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex_digest(&hasher.finalize()))
+}
+
+fn hex_digest(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = stdfs::read_dir(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        let file_type = entry.file_type().map_err(|e| e.to_string())?;
+        if file_type.is_dir() {
+            if !EXCLUDED_DIRS.contains(&name.as_ref()) {
+                collect_files(&path, files)?;
+            }
+        } else if file_type.is_file() && !EXCLUDED_FILES.contains(&name.as_ref()) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn check_entries(
+    repo: &Path,
+    entries: &[ManifestEntry],
+) -> Result<(Vec<String>, Vec<String>), String> {
+    let mut modified = Vec::new();
+    let mut missing = Vec::new();
+    for entry in entries {
+        let path = resolve_entry(repo, &entry.path)?;
+        if !path.is_file() {
+            missing.push(entry.path.clone());
+            continue;
+        }
+        let size = stdfs::metadata(&path).map_err(|e| e.to_string())?.len();
+        if size != entry.size || !hash_file(&path)?.eq_ignore_ascii_case(&entry.sha256) {
+            modified.push(entry.path.clone());
+        }
+    }
+    Ok((modified, missing))
+}
+
+#[tauri::command]
+pub(crate) async fn verify_installation(
+    app: AppHandle,
+) -> Result<VerificationReport, LauncherError> {
+    load_env();
+    let repo = vendor_dir()?;
+    let source = manifest_source(&repo);
+    log_line(&app, &format!("Verifying installation against {source}...")).await;
+    let manifest = load_manifest(&source).await?;
+
+    let entries = manifest.files.clone();
+    let root = repo.clone();
+    let (modified, missing) =
+        tauri::async_runtime::spawn_blocking(move || check_entries(&root, &entries))
+            .await
+            .map_err(|e| e.to_string())??;
+
+    let checked = manifest.files.len();
+    let intact = checked - modified.len() - missing.len();
+    if modified.is_empty() && missing.is_empty() {
+        log_line(
+            &app,
+            &format!("All {checked} files match the release manifest."),
+        )
+        .await;
+    } else {
+        log_line(
+            &app,
+            &format!(
+                "Verification found {} modified and {} missing files.",
+                modified.len(),
+                missing.len()
+            ),
+        )
+        .await;
+        for path in modified.iter() {
+            log_line(&app, &format!("Modified: {path}")).await;
+        }
+        for path in missing.iter() {
+            log_line(&app, &format!("Missing: {path}")).await;
+        }
+    }
+
+    Ok(VerificationReport {
+        manifest_version: manifest.version,
+        checked,
+        intact,
+        modified,
+        missing,
+        can_redownload: manifest.base_url.is_some(),
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn repair_installation(
+    app: AppHandle,
+    files: Vec<String>,
+) -> Result<RepairReport, LauncherError> {
+    let _operation = operation::begin(&app, Operation::InstallationRepair)?;
+    load_env();
+    let repo = vendor_dir()?;
+    let manifest = load_manifest(&manifest_source(&repo)).await?;
+    let base_url = manifest
+        .base_url
+        .as_deref()
+        .map(|url| url.trim_end_matches('/').to_string())
+        .ok_or_else(|| {
+            "The release manifest does not define a baseUrl for downloads.".to_string()
+        })?;
+
+    if offline::detect().await.is_offline() {
+        return Err(LauncherError::new(
+            ErrorKind::Offline,
+            "Offline: cannot re-download files from the release.",
+        ));
+    }
+
+    let client = reqwest::Client::new();
+    let mut repaired = Vec::new();
+    let mut failed = Vec::new();
+
+    for requested in files {
+        let Some(entry) = manifest.files.iter().find(|entry| entry.path == requested) else {
+            failed.push(RepairFailure {
+                path: requested,
+                reason: "Not listed in the release manifest".into(),
+            });
+            continue;
+        };
+        log_line(&app, &format!("Re-downloading {}...", entry.path)).await;
+        match download_entry(&client, &base_url, &repo, entry).await {
+            Ok(()) => repaired.push(entry.path.clone()),
+            Err(reason) => {
+                log_line(&app, &format!("Failed to repair {}: {reason}", entry.path)).await;
+                failed.push(RepairFailure {
+                    path: entry.path.clone(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    log_line(
+        &app,
+        &format!(
+            "Repair finished: {} restored, {} failed.",
+            repaired.len(),
+            failed.len()
+        ),
+    )
+    .await;
+
+    Ok(RepairReport { repaired, failed })
+}
+
+async fn download_entry(
+    client: &reqwest::Client,
+    base_url: &str,
+    repo: &Path,
+    entry: &ManifestEntry,
+) -> Result<(), String> {
+    let target = resolve_entry(repo, &entry.path)?;
+    let url = format!("{}/{}", base_url, entry.path.trim_start_matches('/'));
+    let bytes = client
+        .get(&url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?
+        .bytes()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let digest = hex_digest(&Sha256::digest(&bytes));
+    if !digest.eq_ignore_ascii_case(&entry.sha256) {
+        return Err(format!(
+            "Downloaded file hash {digest} does not match manifest hash {}",
+            entry.sha256
+        ));
+    }
+
+    if let Some(parent) = target.parent() {
+        tokio_fs::create_dir_all(parent)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tokio_fs::write(&target, &bytes)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn generate_release_manifest(
+    app: AppHandle,
+    version: String,
+    base_url: Option<String>,
+) -> Result<String, LauncherError> {
+    let _operation = operation::begin(&app, Operation::ManifestGeneration)?;
+    load_env();
+    let repo = vendor_dir()?;
+    log_line(&app, "Hashing vendor files for the release manifest...").await;
+
+    let root = repo.clone();
+    let files =
+        tauri::async_runtime::spawn_blocking(move || -> Result<Vec<ManifestEntry>, String> {
+            let mut paths = Vec::new();
+            collect_files(&root, &mut paths)?;
+            paths.sort();
+            paths
+                .iter()
+                .map(|path| {
+                    let relative = path
+                        .strip_prefix(&root)
+                        .map_err(|e| e.to_string())?
+                        .components()
+                        .map(|component| component.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    Ok(ManifestEntry {
+                        path: relative,
+                        sha256: hash_file(path)?,
+                        size: stdfs::metadata(path).map_err(|e| e.to_string())?.len(),
+                    })
+                })
+                .collect()
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let manifest = ReleaseManifest {
+        version,
+        base_url: base_url.filter(|url| !url.trim().is_empty()),
+        files,
+    };
+    let output = repo.join(DEFAULT_MANIFEST_NAME);
+    let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    tokio_fs::write(&output, json)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let location = output.to_string_lossy().into_owned();
+    log_line(
+        &app,
+        &format!(
+            "Wrote release manifest with {} files to {location}.",
+            manifest.files.len()
+        ),
+    )
+    .await;
+    Ok(location)
+}
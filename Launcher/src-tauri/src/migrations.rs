@@ -0,0 +1,211 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::{fs as tokio_fs, process::Command as TokioCommand};
+
+use crate::{apply_node_env, log_line, silly_dir, state, trace};
+
+const APPLIED_KEY: &str = "appliedMigrations";
+const DEFAULT_MIGRATIONS_DIR: &str = "launcher-migrations";
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct AppliedMigration {
+    id: String,
+    applied_at: String,
+}
+
+#[derive(Default)]
+pub(crate) struct MigrationReport {
+    pub(crate) applied: Vec<String>,
+    pub(crate) failure: Option<String>,
+    pub(crate) log: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Runner {
+    Node,
+    #[cfg(windows)]
+    PowerShell,
+    #[cfg(not(windows))]
+    Shell,
+}
+
+impl Runner {
+    fn for_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "js" | "mjs" | "cjs" => Some(Self::Node),
+            #[cfg(windows)]
+            "ps1" => Some(Self::PowerShell),
+            #[cfg(not(windows))]
+            "sh" => Some(Self::Shell),
+            _ => None,
+        }
+    }
+
+    fn command(self, script: &Path) -> TokioCommand {
+        let mut cmd = match self {
+            Self::Node => {
+                let mut cmd = TokioCommand::new("node");
+                apply_node_env(&mut cmd);
+                cmd
+            }
+            #[cfg(windows)]
+            Self::PowerShell => {
+                let mut cmd = TokioCommand::new("powershell");
+                cmd.args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File"]);
+                cmd
+            }
+            #[cfg(not(windows))]
+            Self::Shell => TokioCommand::new("sh"),
+        };
+        cmd.arg(script);
+        cmd
+    }
+}
+
+fn migrations_enabled() -> bool {
+    !matches!(
+        env::var("RUN_VENDOR_MIGRATIONS")
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase()
+            .as_str(),
+        "false" | "0" | "no" | "off"
+    )
+}
+
+fn migrations_dir(repo: &Path) -> PathBuf {
+    let configured = env::var("VENDOR_MIGRATIONS_DIR")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| DEFAULT_MIGRATIONS_DIR.into());
+    repo.join(configured)
+}
+
+async fn pending_scripts(
+    dir: &Path,
+    applied: &[AppliedMigration],
+) -> Result<Vec<(String, Runner, PathBuf)>, String> {
+    let mut entries = tokio_fs::read_dir(dir)
+        .await
+        .map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+    let mut scripts: BTreeMap<String, (Runner, PathBuf)> = BTreeMap::new();
+    while let Some(entry) = entries.next_entry().await.map_err(|e| e.to_string())? {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        let (Some(id), Some(runner)) = (
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned()),
+            Runner::for_extension(&extension),
+        ) else {
+            continue;
+        };
+        if applied.iter().any(|migration| migration.id == id) {
+            continue;
+        }
+        match scripts.get(&id) {
+            Some((existing, _)) if *existing <= runner => {}
+            _ => {
+                scripts.insert(id, (runner, path));
+            }
+        }
+    }
+    Ok(scripts
+        .into_iter()
+        .map(|(id, (runner, path))| (id, runner, path))
+        .collect())
+}
+
+pub(crate) async fn run_pending(app: &AppHandle, repo: &Path) -> MigrationReport {
+    let mut report = MigrationReport::default();
+    if !migrations_enabled() {
+        return report;
+    }
+    let dir = migrations_dir(repo);
+    if !dir.is_dir() {
+        return report;
+    }
+
+    let mut applied: Vec<AppliedMigration> = state::load(APPLIED_KEY);
+    let scripts = match pending_scripts(&dir, &applied).await {
+        Ok(scripts) => scripts,
+        Err(err) => {
+            report.failure = Some(err);
+            return report;
+        }
+    };
+    let silly = silly_dir().ok();
+
+    for (id, runner, script) in scripts {
+        log_line(app, &format!("Running vendor migration {id}...")).await;
+        let mut cmd = runner.command(&script);
+        cmd.current_dir(repo)
+            .env("WEYLANDTAVERN_DIR", repo)
+            .env("LAUNCHER_MIGRATION_ID", &id)
+            .stdin(Stdio::null());
+        if let Some(silly) = &silly {
+            cmd.env("SILLYTAVERN_DIR", silly);
+        }
+        trace::spawning(&cmd);
+        let output = match cmd.output().await {
+            Ok(output) => output,
+            Err(err) => {
+                report.failure = Some(format!("Migration {id} could not be started: {err}"));
+                break;
+            }
+        };
+        let text = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        report
+            .log
+            .push_str(&format!("== {id} ==\n{}\n", text.trim()));
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            log_line(app, line).await;
+        }
+        if !output.status.success() {
+            report.failure = Some(format!("Migration {id} failed ({}).", output.status));
+            break;
+        }
+
+        applied.push(AppliedMigration {
+            id: id.clone(),
+            applied_at: Local::now().to_rfc3339(),
+        });
+        if let Err(err) = state::save(APPLIED_KEY, &applied) {
+            report.failure = Some(format!(
+                "Migration {id} ran but could not be recorded: {err}"
+            ));
+            break;
+        }
+        report.applied.push(id);
+    }
+
+    if let Some(failure) = &report.failure {
+        log_line(app, failure).await;
+    } else if !report.applied.is_empty() {
+        log_line(
+            app,
+            &format!("Applied vendor migrations: {}.", report.applied.join(", ")),
+        )
+        .await;
+    }
+    report
+}
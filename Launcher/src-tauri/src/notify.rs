@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::{
+    error::LauncherError,
+    journal::{JournalAction, JournalEntry},
+    state::{self, ChangeOrigin},
+};
+
+const TOGGLES_KEY: &str = "notifications";
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum NotifyEvent {
+    UpdateFinished,
+    NpmInstall,
+    CharacterSync,
+    ServerCrash,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct NotificationToggles {
+    update_finished: bool,
+    npm_install: bool,
+    character_sync: bool,
+    server_crash: bool,
+}
+
+impl Default for NotificationToggles {
+    fn default() -> Self {
+        Self {
+            update_finished: true,
+            npm_install: true,
+            character_sync: true,
+            server_crash: true,
+        }
+    }
+}
+
+impl NotificationToggles {
+    fn slot(&mut self, event: NotifyEvent) -> &mut bool {
+        match event {
+            NotifyEvent::UpdateFinished => &mut self.update_finished,
+            NotifyEvent::NpmInstall => &mut self.npm_install,
+            NotifyEvent::CharacterSync => &mut self.character_sync,
+            NotifyEvent::ServerCrash => &mut self.server_crash,
+        }
+    }
+}
+
+fn enabled(event: NotifyEvent) -> bool {
+    let mut toggles: NotificationToggles = state::load(TOGGLES_KEY);
+    *toggles.slot(event)
+}
+
+fn windows_hidden(app: &AppHandle) -> bool {
+    app.webview_windows().values().all(|window| {
+        !window.is_visible().unwrap_or(true) || window.is_minimized().unwrap_or(false)
+    })
+}
+
+pub(crate) fn send(app: &AppHandle, event: NotifyEvent, title: &str, body: &str) {
+    if !enabled(event) {
+        return;
+    }
+    let _ = app.notification().builder().title(title).body(body).show();
+}
+
+pub(crate) fn update_finished(app: &AppHandle, entry: &JournalEntry) {
+    if !matches!(
+        entry.action,
+        JournalAction::Pull | JournalAction::StashAndPull
+    ) {
+        return;
+    }
+    let title = match entry.result.as_str() {
+        "success" => "WeylandTavern updated",
+        "upToDate" => "WeylandTavern is up to date",
+        _ => "WeylandTavern update failed",
+    };
+    send(app, NotifyEvent::UpdateFinished, title, &entry.message);
+}
+
+pub(crate) fn server_crashed(app: &AppHandle, message: &str) {
+    if windows_hidden(app) {
+        send(
+            app,
+            NotifyEvent::ServerCrash,
+            "WeylandTavern stopped",
+            message,
+        );
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn notification_settings() -> Result<NotificationToggles, LauncherError> {
+    Ok(state::load(TOGGLES_KEY))
+}
+
+#[tauri::command]
+pub(crate) async fn set_notification_enabled(
+    event: NotifyEvent,
+    enabled: bool,
+) -> Result<NotificationToggles, LauncherError> {
+    let (toggles, _) = state::update(
+        TOGGLES_KEY,
+        ChangeOrigin::Ui,
+        |toggles: &mut NotificationToggles| {
+            *toggles.slot(event) = enabled;
+            toggles.clone()
+        },
+    )?;
+    Ok(toggles)
+}
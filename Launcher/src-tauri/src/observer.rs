@@ -0,0 +1,502 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    convert::Infallible,
+    env,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::Utc;
+use dotenvy::from_filename_iter;
+use hyper::{
+    header::{self, HeaderValue},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{async_runtime::JoinHandle, AppHandle, Manager};
+use tokio::{process::Command as TokioCommand, sync::oneshot};
+
+use crate::{
+    disk, error::LauncherError, load_env, log_line, offline, onboarding, packages, share,
+    silly_dir, ServerState,
+};
+
+const LOG_CAPACITY: usize = 500;
+const DEFAULT_LOG_LINES: usize = 200;
+const DEFAULT_PORT: u16 = 8765;
+const MAX_TTL_MINUTES: u32 = 120;
+const SHUTDOWN_WAIT: Duration = Duration::from_secs(10);
+const SENSITIVE_KEYS: &[&str] = &[
+    "password", "passwd", "secret", "token", "auth", "apikey", "api_key", "cookie",
+];
+
+static LOG_BUFFER: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+struct ObserverSession {
+    token_hash: String,
+    expires_at: i64,
+    shutdown: oneshot::Sender<()>,
+    server: JoinHandle<()>,
+}
+
+impl ObserverSession {
+    async fn stop(self) {
+        let _ = self.shutdown.send(());
+        let _ = tokio::time::timeout(SHUTDOWN_WAIT, self.server).await;
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ObserverState(Arc<Mutex<Option<ObserverSession>>>);
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ObserverSessionInfo {
+    token: String,
+    expires_at: i64,
+    url: String,
+}
+
+impl ObserverState {
+    pub(crate) fn is_active(&self) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|session| session.expires_at > Utc::now().timestamp())
+            .unwrap_or(false)
+    }
+}
+
+pub(crate) fn record(line: &str) {
+    let mut buffer = LOG_BUFFER.lock().unwrap();
+    if buffer.len() >= LOG_CAPACITY {
+        buffer.pop_front();
+    }
+    buffer.push_back(line.to_string());
+}
+
+fn observer_port() -> u16 {
+    env::var("OBSERVER_PORT")
+        .ok()
+        .and_then(|value| value.trim().parse::<u16>().ok())
+        .filter(|port| *port != 0)
+        .unwrap_or(DEFAULT_PORT)
+}
+
+fn observer_host() -> IpAddr {
+    env::var("OBSERVER_HOST")
+        .ok()
+        .and_then(|value| value.trim().parse::<IpAddr>().ok())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+fn public_base_url(addr: SocketAddr) -> String {
+    env::var("OBSERVER_PUBLIC_URL")
+        .ok()
+        .map(|value| value.trim().trim_end_matches('/').to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| {
+            let host = if addr.ip().is_unspecified() {
+                IpAddr::V4(Ipv4Addr::LOCALHOST)
+            } else {
+                addr.ip()
+            };
+            format!("http://{}", SocketAddr::new(host, addr.port()))
+        })
+}
+
+fn random_hex(bytes: usize) -> String {
+    (0..bytes)
+        .map(|_| format!("{:02x}", rand::random::<u8>()))
+        .collect()
+}
+
+fn is_sensitive(key: &str) -> bool {
+    let lower = key.to_ascii_lowercase();
+    SENSITIVE_KEYS.iter().any(|needle| lower.contains(needle))
+}
+
+fn redact_word(word: &str) -> String {
+    if let Some((scheme, rest)) = word.split_once("://") {
+        if let Some((credentials, host)) = rest.split_once('@') {
+            if !credentials.contains('/') {
+                return format!("{scheme}://[redacted]@{host}");
+            }
+        }
+    }
+    if let Some(index) = word.find("/_guest/") {
+        return format!("{}/_guest/[redacted]", &word[..index]);
+    }
+    if let Some((key, _)) = word.split_once(['=', ':']) {
+        if !key.is_empty() && is_sensitive(key) {
+            return format!("{key}=[redacted]");
+        }
+    }
+    let bare = word.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+    if bare.len() >= 32 && bare.chars().all(|c| c.is_ascii_hexdigit()) {
+        return word.replace(bare, "[redacted]");
+    }
+    word.to_string()
+}
+
+pub(crate) fn redact_line(line: &str) -> String {
+    let mut redacted = Vec::new();
+    let mut hide_next = false;
+    for word in line.split(' ') {
+        if hide_next && !word.is_empty() {
+            redacted.push("[redacted]".to_string());
+            hide_next = false;
+            continue;
+        }
+        hide_next = word.eq_ignore_ascii_case("bearer") || word.eq_ignore_ascii_case("basic");
+        redacted.push(redact_word(word));
+    }
+    let mut line = redacted.join(" ");
+    if let Some(home) = env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(|home| home.to_string_lossy().into_owned())
+        .filter(|home| home.len() > 1)
+    {
+        line = line.replace(&home, "~");
+    }
+    line
+}
+
+pub(crate) fn redact_value(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let value = if is_sensitive(&key) && !value.is_null() {
+                        Value::String("[redacted]".into())
+                    } else {
+                        redact_value(value)
+                    };
+                    (key, value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact_value).collect()),
+        Value::String(text) => Value::String(redact_line(&text)),
+        other => other,
+    }
+}
+
+fn recent_logs(limit: usize) -> Vec<String> {
+    let buffer = LOG_BUFFER.lock().unwrap();
+    buffer
+        .iter()
+        .skip(buffer.len().saturating_sub(limit))
+        .map(|line| redact_line(line))
+        .collect()
+}
+
+pub(crate) fn launcher_config() -> BTreeMap<String, String> {
+    let Ok(iter) = from_filename_iter("../.env").or_else(|_| from_filename_iter(".env")) else {
+        return BTreeMap::new();
+    };
+    iter.filter_map(Result::ok)
+        .map(|(key, value)| {
+            let value = if is_sensitive(&key) && !value.is_empty() {
+                "[redacted]".to_string()
+            } else {
+                redact_line(&value)
+            };
+            (key, value)
+        })
+        .collect()
+}
+
+async fn command_version(program: &str, args: &[&str]) -> Option<String> {
+    let output = TokioCommand::new(program).args(args).output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn server_status(app: &AppHandle) -> Value {
+    let state = app.state::<ServerState>();
+    let pid = state
+        .child
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|child| child.id());
+    json!({
+        "running": pid.is_some(),
+        "pid": pid,
+        "tuning": pid.and_then(|_| state.tuning.lock().unwrap().clone()),
+    })
+}
+
+async fn status(app: &AppHandle) -> Value {
+    json!({
+        "server": server_status(app),
+        "onboarding": onboarding::get_onboarding_state().await.ok(),
+        "connectivity": offline::detect().await.describe(),
+    })
+}
+
+async fn diagnostics(app: &AppHandle) -> Value {
+    let silly = silly_dir().ok();
+    let vendor_head = match silly.as_deref().and_then(|dir| dir.parent()) {
+        Some(vendor) => {
+            command_version(
+                "git",
+                &[
+                    "-C",
+                    &vendor.to_string_lossy(),
+                    "rev-parse",
+                    "--short",
+                    "HEAD",
+                ],
+            )
+            .await
+        }
+        None => None,
+    };
+    json!({
+        "launcherVersion": app.package_info().version.to_string(),
+        "os": env::consts::OS,
+        "arch": env::consts::ARCH,
+        "server": server_status(app),
+        "sillyTavernFound": silly.is_some(),
+        "vendorHead": vendor_head,
+        "git": command_version("git", &["--version"]).await,
+        "node": command_version("node", &["--version"]).await,
+        "packageManager": silly.as_deref().map(|dir| packages::PackageManagerKind::detect(dir).name()),
+        "freeDiskBytes": silly.as_deref().and_then(|dir| disk::available_bytes(dir).ok()),
+        "connectivity": offline::detect().await.describe(),
+        "config": launcher_config(),
+    })
+}
+
+fn json_response(status: StatusCode, body: Value) -> Response<Body> {
+    let mut response = Response::new(Body::from(body.to_string()));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    response
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    json_response(status, json!({ "error": message }))
+}
+
+fn request_token(req: &Request<Body>) -> Option<String> {
+    if let Some(token) = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    {
+        return Some(token.trim().to_string());
+    }
+    query_param(req, "token")
+}
+
+fn query_param(req: &Request<Body>, name: &str) -> Option<String> {
+    req.uri()
+        .query()?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+}
+
+fn authorised(sessions: &Mutex<Option<ObserverSession>>, candidate: Option<String>) -> bool {
+    let guard = sessions.lock().unwrap();
+    match (guard.as_ref(), candidate) {
+        (Some(session), Some(candidate)) => {
+            share::token_matches(&candidate, &session.token_hash)
+                && session.expires_at > Utc::now().timestamp()
+        }
+        _ => false,
+    }
+}
+
+async fn handle(req: Request<Body>, app: AppHandle) -> Result<Response<Body>, Infallible> {
+    if req.method() != Method::GET {
+        let mut response = error_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Observer sessions are read-only.",
+        );
+        response
+            .headers_mut()
+            .insert(header::ALLOW, HeaderValue::from_static("GET"));
+        return Ok(response);
+    }
+    let sessions = app.state::<ObserverState>().0.clone();
+    if !authorised(&sessions, request_token(&req)) {
+        return Ok(error_response(
+            StatusCode::UNAUTHORIZED,
+            "Observer token missing or expired. Ask the user for a new one.",
+        ));
+    }
+
+    let response = match req.uri().path().trim_end_matches('/') {
+        "" => json_response(
+            StatusCode::OK,
+            json!({ "endpoints": ["/status", "/logs", "/diagnostics"] }),
+        ),
+        "/status" => json_response(StatusCode::OK, status(&app).await),
+        "/logs" => {
+            let lines = query_param(&req, "lines")
+                .and_then(|value| value.parse::<usize>().ok())
+                .unwrap_or(DEFAULT_LOG_LINES)
+                .min(LOG_CAPACITY);
+            json_response(StatusCode::OK, json!({ "lines": recent_logs(lines) }))
+        }
+        "/diagnostics" => json_response(StatusCode::OK, diagnostics(&app).await),
+        _ => error_response(StatusCode::NOT_FOUND, "Unknown observer endpoint."),
+    };
+    Ok(response)
+}
+
+async fn stop_session(state: &ObserverState) -> bool {
+    let session = state.0.lock().unwrap().take();
+    match session {
+        Some(session) => {
+            session.stop().await;
+            true
+        }
+        None => false,
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn start_observer_session(
+    app: AppHandle,
+    state: tauri::State<'_, ObserverState>,
+    ttl_minutes: Option<u32>,
+) -> Result<ObserverSessionInfo, LauncherError> {
+    load_env();
+    let ttl = ttl_minutes.unwrap_or(30);
+    if ttl == 0 {
+        return Err("Observer sessions need a lifetime of at least one minute.".into());
+    }
+    let ttl = ttl.min(MAX_TTL_MINUTES);
+    stop_session(&state).await;
+
+    let addr = SocketAddr::new(observer_host(), observer_port());
+    let app_for_service = app.clone();
+    let make_service = make_service_fn(move |_| {
+        let app = app_for_service.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle(req, app.clone()))) }
+    });
+    let server = Server::try_bind(&addr)
+        .map_err(|e| format!("Unable to bind observer API on {addr}: {e}"))?
+        .serve(make_service);
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let graceful = server.with_graceful_shutdown(async {
+        let _ = shutdown_rx.await;
+    });
+    let app_for_errors = app.clone();
+    let server = tauri::async_runtime::spawn(async move {
+        if let Err(err) = graceful.await {
+            log_line(&app_for_errors, &format!("Observer API stopped: {err}")).await;
+        }
+    });
+
+    let token = random_hex(16);
+    let expires_at = Utc::now().timestamp() + i64::from(ttl) * 60;
+    let token_hash = share::token_digest(&token);
+    state.0.lock().unwrap().replace(ObserverSession {
+        token_hash: token_hash.clone(),
+        expires_at,
+        shutdown: shutdown_tx,
+        server,
+    });
+
+    let sessions = state.0.clone();
+    let expiring = token_hash;
+    let app_for_expiry = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(u64::from(ttl) * 60)).await;
+        let expired = {
+            let mut guard = sessions.lock().unwrap();
+            if guard.as_ref().map(|session| session.token_hash == expiring) == Some(true) {
+                guard.take()
+            } else {
+                None
+            }
+        };
+        if let Some(session) = expired {
+            session.stop().await;
+            log_line(&app_for_expiry, "Observer session expired.").await;
+        }
+    });
+
+    log_line(
+        &app,
+        &format!("Read-only observer session started on {addr} for {ttl} minutes."),
+    )
+    .await;
+    Ok(ObserverSessionInfo {
+        token,
+        expires_at,
+        url: public_base_url(addr),
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn stop_observer_session(
+    app: AppHandle,
+    state: tauri::State<'_, ObserverState>,
+) -> Result<(), LauncherError> {
+    if stop_session(&state).await {
+        log_line(&app, "Observer session ended.").await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redact_value_hides_sensitive_keys_and_strings() {
+        let value = json!({
+            "apiKey": "abc",
+            "port": 8000,
+            "nested": {
+                "password": null,
+                "remote": "https://user:pw@example.com/repo.git",
+            },
+            "lines": ["sent Bearer abcdef", "token=abc", "plain text"],
+        });
+        assert_eq!(
+            redact_value(value),
+            json!({
+                "apiKey": "[redacted]",
+                "port": 8000,
+                "nested": {
+                    "password": null,
+                    "remote": "https://[redacted]@example.com/repo.git",
+                },
+                "lines": ["sent Bearer [redacted]", "token=[redacted]", "plain text"],
+            })
+        );
+    }
+
+    #[test]
+    fn redact_line_hides_guest_links_and_hex_secrets() {
+        let secret = "0123456789abcdef0123456789abcdef";
+        assert_eq!(
+            redact_line(&format!("open http://host/_guest/{secret}/ now")),
+            "open http://host/_guest/[redacted] now"
+        );
+        assert_eq!(redact_line(&format!("key {secret}.")), "key [redacted].");
+    }
+}
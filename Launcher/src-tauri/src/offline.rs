@@ -0,0 +1,111 @@
+use std::{
+    env,
+    net::{IpAddr, Ipv4Addr},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+const DEFAULT_PROBE_URL: &str = "https://github.com";
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const PROBE_CACHE: Duration = Duration::from_secs(60);
+
+static LAST_PROBE: Mutex<Option<(Instant, bool)>> = Mutex::new(None);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Connectivity {
+    Online,
+    Forced,
+    Detected,
+}
+
+impl Connectivity {
+    pub(crate) fn is_offline(self) -> bool {
+        self != Self::Online
+    }
+
+    pub(crate) fn describe(self) -> &'static str {
+        match self {
+            Self::Online => "online",
+            Self::Forced => "offline mode is enabled in .env",
+            Self::Detected => "no network connection was detected",
+        }
+    }
+}
+
+pub(crate) async fn detect() -> Connectivity {
+    let raw = env::var("OFFLINE_MODE").unwrap_or_else(|_| "auto".into());
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Connectivity::Forced,
+        "0" | "false" | "no" | "off" => Connectivity::Online,
+        _ => {
+            if cached_probe().await {
+                Connectivity::Online
+            } else {
+                Connectivity::Detected
+            }
+        }
+    }
+}
+
+pub(crate) async fn refresh() -> Connectivity {
+    invalidate();
+    detect().await
+}
+
+pub(crate) fn invalidate() {
+    LAST_PROBE.lock().unwrap().take();
+}
+
+async fn cached_probe() -> bool {
+    let cached = *LAST_PROBE.lock().unwrap();
+    if let Some((at, reachable)) = cached {
+        if at.elapsed() < PROBE_CACHE {
+            return reachable;
+        }
+    }
+    let reachable = probe().await;
+    LAST_PROBE
+        .lock()
+        .unwrap()
+        .replace((Instant::now(), reachable));
+    reachable
+}
+
+async fn probe() -> bool {
+    let url = env::var("OFFLINE_PROBE_URL")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PROBE_URL.into());
+    let client = match reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(_) => return false,
+    };
+    client.head(url.trim()).send().await.is_ok()
+}
+
+pub(crate) fn npm_flag(connectivity: Connectivity) -> Option<&'static str> {
+    match connectivity {
+        Connectivity::Online => None,
+        Connectivity::Forced => Some("--offline"),
+        Connectivity::Detected => Some("--prefer-offline"),
+    }
+}
+
+pub(crate) fn is_local_host(host: &str) -> bool {
+    let host = host.trim().trim_start_matches('[').trim_end_matches(']');
+    if host.eq_ignore_ascii_case("localhost") {
+        return true;
+    }
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip == Ipv4Addr::UNSPECIFIED
+        }
+        Ok(IpAddr::V6(ip)) => {
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_unique_local()
+                || ip.is_unicast_link_local()
+        }
+        Err(_) => false,
+    }
+}
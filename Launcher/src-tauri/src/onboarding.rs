@@ -0,0 +1,100 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::{error::LauncherError, log_line, state};
+
+const ONBOARDING_KEY: &str = "onboarding";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum OnboardingStep {
+    DepsVerified,
+    VendorBootstrapped,
+    FirstInstall,
+    FirstLaunch,
+}
+
+impl OnboardingStep {
+    const ORDER: [Self; 4] = [
+        Self::DepsVerified,
+        Self::VendorBootstrapped,
+        Self::FirstInstall,
+        Self::FirstLaunch,
+    ];
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::DepsVerified => "dependencies verified",
+            Self::VendorBootstrapped => "WeylandTavern downloaded",
+            Self::FirstInstall => "Node modules installed",
+            Self::FirstLaunch => "first successful launch",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+struct OnboardingProgress {
+    deps_verified: Option<String>,
+    vendor_bootstrapped: Option<String>,
+    first_install: Option<String>,
+    first_launch: Option<String>,
+}
+
+impl OnboardingProgress {
+    fn slot(&mut self, step: OnboardingStep) -> &mut Option<String> {
+        match step {
+            OnboardingStep::DepsVerified => &mut self.deps_verified,
+            OnboardingStep::VendorBootstrapped => &mut self.vendor_bootstrapped,
+            OnboardingStep::FirstInstall => &mut self.first_install,
+            OnboardingStep::FirstLaunch => &mut self.first_launch,
+        }
+    }
+
+    fn next_step(&mut self) -> Option<OnboardingStep> {
+        OnboardingStep::ORDER
+            .into_iter()
+            .find(|step| self.slot(*step).is_none())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OnboardingState {
+    #[serde(flatten)]
+    progress: OnboardingProgress,
+    next_step: Option<OnboardingStep>,
+    complete: bool,
+}
+
+pub(crate) async fn complete(app: &AppHandle, step: OnboardingStep) {
+    let mut progress: OnboardingProgress = state::load(ONBOARDING_KEY);
+    let slot = progress.slot(step);
+    if slot.is_some() {
+        return;
+    }
+    slot.replace(Local::now().to_rfc3339());
+    match state::save(ONBOARDING_KEY, &progress) {
+        Ok(()) => log_line(app, &format!("Onboarding: {}.", step.describe())).await,
+        Err(err) => log_line(app, &format!("Failed to record onboarding progress: {err}")).await,
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn get_onboarding_state() -> Result<OnboardingState, LauncherError> {
+    let mut progress: OnboardingProgress = state::load(ONBOARDING_KEY);
+    let next_step = progress.next_step();
+    Ok(OnboardingState {
+        progress,
+        next_step,
+        complete: next_step.is_none(),
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn reset_onboarding(app: AppHandle) -> Result<(), LauncherError> {
+    state::save(ONBOARDING_KEY, &OnboardingProgress::default())?;
+    log_line(&app, "Onboarding progress reset.").await;
+    Ok(())
+}
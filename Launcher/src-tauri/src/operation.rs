@@ -0,0 +1,116 @@
+use chrono::Local;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::{error::LauncherError, events, ServerState};
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Operation {
+    VendorUpdate,
+    StashFinalize,
+    VendorClone,
+    Unshallow,
+    UpdateCheck,
+    PolicyChange,
+    RepoRepair,
+    VendorRelocate,
+    InstallationRepair,
+    NodeModulesRepair,
+    VendorScript,
+    CharacterSync,
+    BundleImport,
+    ServerStart,
+    DataMigration,
+    DataImport,
+    BundleExport,
+    Backup,
+    ManifestGeneration,
+    DependencyCheck,
+}
+
+impl Operation {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::VendorUpdate => "updating WeylandTavern",
+            Self::StashFinalize => "restoring or discarding stashed changes",
+            Self::VendorClone => "downloading WeylandTavern",
+            Self::Unshallow => "fetching the full git history",
+            Self::UpdateCheck => "checking for updates",
+            Self::PolicyChange => "changing the update policy",
+            Self::RepoRepair => "repairing the vendor checkout",
+            Self::VendorRelocate => "moving the vendor checkout",
+            Self::InstallationRepair => "repairing installation files",
+            Self::NodeModulesRepair => "reinstalling node_modules",
+            Self::VendorScript => "running a vendor script",
+            Self::CharacterSync => "syncing characters",
+            Self::BundleImport => "importing a bundle",
+            Self::ServerStart => "starting the server",
+            Self::DataMigration => "moving the user data folder",
+            Self::DataImport => "importing from another install",
+            Self::BundleExport => "exporting a bundle",
+            Self::Backup => "backing up user data",
+            Self::ManifestGeneration => "generating the release manifest",
+            Self::DependencyCheck => "checking dependencies",
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CurrentOperation {
+    operation: Operation,
+    label: &'static str,
+    started_at: i64,
+}
+
+pub(crate) struct OperationGuard {
+    app: AppHandle,
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        self.app
+            .state::<ServerState>()
+            .operation
+            .lock()
+            .unwrap()
+            .take();
+        events::emit(&self.app, "operation-changed", None::<CurrentOperation>);
+    }
+}
+
+pub(crate) fn begin(
+    app: &AppHandle,
+    operation: Operation,
+) -> Result<OperationGuard, LauncherError> {
+    let state = app.state::<ServerState>();
+    let mut current = state.operation.lock().unwrap();
+    if let Some(running) = *current {
+        return Err(LauncherError::busy(running.operation));
+    }
+    let started = CurrentOperation {
+        operation,
+        label: operation.label(),
+        started_at: Local::now().timestamp(),
+    };
+    *current = Some(started);
+    drop(current);
+    events::emit(app, "operation-changed", Some(started));
+    Ok(OperationGuard { app: app.clone() })
+}
+
+pub(crate) fn current(app: &AppHandle) -> Option<Operation> {
+    app.state::<ServerState>()
+        .operation
+        .lock()
+        .unwrap()
+        .map(|running| running.operation)
+}
+
+#[tauri::command]
+pub(crate) async fn get_current_operation(
+    state: State<'_, ServerState>,
+) -> Result<Option<CurrentOperation>, LauncherError> {
+    Ok(*state.operation.lock().unwrap())
+}
@@ -0,0 +1,365 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    ffi::{OsStr, OsString},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+use tokio::process::Command as TokioCommand;
+
+use crate::{
+    apply_node_env, log_line,
+    offline::Connectivity,
+    state::{self, ChangeOrigin},
+};
+
+const LOCKFILE_HASHES_KEY: &str = "lockfileHashes";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PackageManagerKind {
+    Npm,
+    Yarn,
+    YarnBerry,
+    Pnpm,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum CacheAction {
+    Verify,
+    Clean,
+}
+
+impl PackageManagerKind {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Npm => "npm",
+            Self::Yarn | Self::YarnBerry => "yarn",
+            Self::Pnpm => "pnpm",
+        }
+    }
+
+    pub(crate) fn lockfile(self) -> &'static str {
+        match self {
+            Self::Npm => "package-lock.json",
+            Self::Yarn | Self::YarnBerry => "yarn.lock",
+            Self::Pnpm => "pnpm-lock.yaml",
+        }
+    }
+
+    #[cfg(windows)]
+    fn candidates(self) -> &'static [&'static str] {
+        match self {
+            Self::Npm => &["npm.cmd", "npm"],
+            Self::Yarn | Self::YarnBerry => &["yarn.cmd", "yarn"],
+            Self::Pnpm => &["pnpm.cmd", "pnpm"],
+        }
+    }
+
+    #[cfg(not(windows))]
+    fn candidates(self) -> &'static [&'static str] {
+        match self {
+            Self::Npm => &["npm"],
+            Self::Yarn | Self::YarnBerry => &["yarn"],
+            Self::Pnpm => &["pnpm"],
+        }
+    }
+
+    fn bin_env(self) -> &'static str {
+        match self {
+            Self::Npm => "NPM_BIN",
+            Self::Yarn | Self::YarnBerry => "YARN_BIN",
+            Self::Pnpm => "PNPM_BIN",
+        }
+    }
+
+    pub(crate) fn detect(dir: &Path) -> Self {
+        let configured = env::var("PACKAGE_MANAGER").unwrap_or_default();
+        let kind = match configured.trim().to_ascii_lowercase().as_str() {
+            "npm" => Self::Npm,
+            "yarn" => Self::Yarn,
+            "pnpm" => Self::Pnpm,
+            _ => {
+                if dir.join(Self::Pnpm.lockfile()).exists() {
+                    Self::Pnpm
+                } else if dir.join(Self::Yarn.lockfile()).exists() {
+                    Self::Yarn
+                } else {
+                    Self::Npm
+                }
+            }
+        };
+        if kind == Self::Yarn && is_yarn_berry(dir) {
+            Self::YarnBerry
+        } else {
+            kind
+        }
+    }
+}
+
+fn is_yarn_berry(dir: &Path) -> bool {
+    if dir.join(".yarnrc.yml").exists() {
+        return true;
+    }
+    std::fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .and_then(|manifest| {
+            manifest
+                .get("packageManager")
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+        })
+        .and_then(|spec| {
+            spec.strip_prefix("yarn@")
+                .and_then(|version| version.split('.').next()?.parse::<u32>().ok())
+        })
+        .map(|major| major >= 2)
+        .unwrap_or(false)
+}
+
+enum Tool {
+    Binary(OsString),
+    Script(PathBuf),
+    Corepack(OsString),
+}
+
+pub(crate) struct PackageManager {
+    kind: PackageManagerKind,
+    tool: Tool,
+}
+
+async fn command_exists(program: &OsStr) -> bool {
+    TokioCommand::new(program)
+        .arg("--version")
+        .status()
+        .await
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+impl PackageManager {
+    pub(crate) fn kind(&self) -> PackageManagerKind {
+        self.kind
+    }
+
+    pub(crate) async fn locate(app: &AppHandle, dir: &Path) -> Result<Self, String> {
+        let kind = PackageManagerKind::detect(dir);
+        let name = kind.name();
+        let bin_env = kind.bin_env();
+
+        if let Some(custom) = env::var_os(bin_env).filter(|value| !value.is_empty()) {
+            let location = PathBuf::from(&custom);
+            if command_exists(custom.as_os_str()).await {
+                log_line(
+                    app,
+                    &format!(
+                        "Using {name} from {} as configured via {bin_env}.",
+                        location.display()
+                    ),
+                )
+                .await;
+                return Ok(Self {
+                    kind,
+                    tool: Tool::Binary(custom),
+                });
+            }
+            return Err(format!(
+                "Configured {bin_env} at {} is not executable. Install {name} or update {bin_env}.",
+                location.display()
+            ));
+        }
+
+        for candidate in kind.candidates() {
+            if command_exists(OsStr::new(candidate)).await {
+                return Ok(Self {
+                    kind,
+                    tool: Tool::Binary(OsString::from(candidate)),
+                });
+            }
+        }
+
+        if kind == PackageManagerKind::Npm {
+            return locate_bundled_npm(app).await.map(|path| Self {
+                kind,
+                tool: Tool::Script(path),
+            });
+        }
+
+        for candidate in corepack_candidates() {
+            if command_exists(OsStr::new(candidate)).await {
+                log_line(
+                    app,
+                    &format!("{name} not found on PATH; running it through corepack."),
+                )
+                .await;
+                return Ok(Self {
+                    kind,
+                    tool: Tool::Corepack(OsString::from(candidate)),
+                });
+            }
+        }
+
+        Err(format!(
+            "{name} not found. Install {name}, enable it with `corepack enable`, or set {bin_env} to its executable path."
+        ))
+    }
+
+    pub(crate) fn command(&self) -> TokioCommand {
+        let mut cmd = match &self.tool {
+            Tool::Binary(bin) => TokioCommand::new(bin),
+            Tool::Script(path) => {
+                let mut cmd = TokioCommand::new("node");
+                cmd.arg(path.as_os_str());
+                cmd
+            }
+            Tool::Corepack(corepack) => {
+                let mut cmd = TokioCommand::new(corepack);
+                cmd.env("COREPACK_ENABLE_DOWNLOAD_PROMPT", "0");
+                cmd.arg(self.kind.name());
+                cmd
+            }
+        };
+        apply_node_env(&mut cmd);
+        cmd
+    }
+
+    pub(crate) fn install_args(
+        &self,
+        frozen: bool,
+        connectivity: Connectivity,
+    ) -> Vec<&'static str> {
+        let mut args = match (self.kind, frozen) {
+            (PackageManagerKind::Npm, true) => vec!["ci"],
+            (PackageManagerKind::Npm, false) => vec![
+                "install",
+                "--no-audit",
+                "--no-fund",
+                "--loglevel=error",
+                "--no-progress",
+                "--omit=dev",
+            ],
+            (PackageManagerKind::Yarn, true) => vec![
+                "install",
+                "--frozen-lockfile",
+                "--production",
+                "--non-interactive",
+            ],
+            (PackageManagerKind::Yarn, false) => {
+                vec!["install", "--production", "--non-interactive"]
+            }
+            (PackageManagerKind::YarnBerry, true) => vec!["install", "--immutable"],
+            (PackageManagerKind::YarnBerry, false) => vec!["install"],
+            (PackageManagerKind::Pnpm, true) => vec!["install", "--frozen-lockfile", "--prod"],
+            (PackageManagerKind::Pnpm, false) => vec!["install", "--prod"],
+        };
+        if self.kind != PackageManagerKind::YarnBerry {
+            args.extend(crate::offline::npm_flag(connectivity));
+        }
+        args
+    }
+
+    pub(crate) fn cache_args(&self, action: CacheAction) -> Option<&'static [&'static str]> {
+        match (self.kind, action) {
+            (PackageManagerKind::Npm, CacheAction::Verify) => Some(&["cache", "verify"]),
+            (PackageManagerKind::Npm, CacheAction::Clean) => Some(&["cache", "clean", "--force"]),
+            (PackageManagerKind::Yarn | PackageManagerKind::YarnBerry, CacheAction::Verify) => None,
+            (PackageManagerKind::Yarn, CacheAction::Clean) => Some(&["cache", "clean"]),
+            (PackageManagerKind::YarnBerry, CacheAction::Clean) => {
+                Some(&["cache", "clean", "--all"])
+            }
+            (PackageManagerKind::Pnpm, CacheAction::Verify) => Some(&["store", "status"]),
+            (PackageManagerKind::Pnpm, CacheAction::Clean) => Some(&["store", "prune"]),
+        }
+    }
+}
+
+#[cfg(windows)]
+fn corepack_candidates() -> &'static [&'static str] {
+    &["corepack.cmd", "corepack"]
+}
+
+#[cfg(not(windows))]
+fn corepack_candidates() -> &'static [&'static str] {
+    &["corepack"]
+}
+
+async fn locate_bundled_npm(app: &AppHandle) -> Result<PathBuf, String> {
+    log_line(
+        app,
+        "npm executable not found on PATH; attempting to use the npm-cli.js bundled with Node.",
+    )
+    .await;
+
+    let mut node_cmd = TokioCommand::new("node");
+    apply_node_env(&mut node_cmd);
+    let output = node_cmd
+        .args(["-p", "require.resolve('npm/bin/npm-cli.js')"])
+        .output()
+        .await
+        .map_err(|e| format!("Unable to locate npm via node: {e}"))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let script = stdout.trim();
+
+    if output.status.success() && !script.is_empty() {
+        let path = PathBuf::from(script);
+        log_line(
+            app,
+            &format!(
+                "Resolved npm-cli.js at {}. Falling back to running npm via node.",
+                path.display()
+            ),
+        )
+        .await;
+        Ok(path)
+    } else {
+        let mut message = String::from(
+            "npm not found. Install Node.js (which includes npm) or set NPM_BIN to the npm executable path.",
+        );
+        let details = stderr.trim();
+        if !details.is_empty() {
+            message.push(' ');
+            message.push_str(details);
+        }
+        Err(message)
+    }
+}
+
+fn hash_key(dir: &Path) -> String {
+    fs::canonicalize(dir)
+        .unwrap_or_else(|_| dir.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+pub(crate) fn lockfile_hash(dir: &Path, kind: PackageManagerKind) -> Result<String, String> {
+    let path = dir.join(kind.lockfile());
+    let bytes = fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    Ok(Sha256::digest(&bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+pub(crate) fn recorded_lockfile_hash(dir: &Path) -> Option<String> {
+    let hashes: BTreeMap<String, String> = state::load(LOCKFILE_HASHES_KEY);
+    hashes.get(&hash_key(dir)).cloned()
+}
+
+pub(crate) fn record_lockfile_hash(dir: &Path, kind: PackageManagerKind) -> Result<(), String> {
+    if !dir.join(kind.lockfile()).exists() {
+        return Ok(());
+    }
+    let hash = lockfile_hash(dir, kind)?;
+    state::update(
+        LOCKFILE_HASHES_KEY,
+        ChangeOrigin::Launcher,
+        |hashes: &mut BTreeMap<String, String>| {
+            hashes.insert(hash_key(dir), hash);
+        },
+    )
+    .map(|_| ())
+}
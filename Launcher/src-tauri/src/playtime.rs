@@ -0,0 +1,222 @@
+use std::{
+    env,
+    sync::{
+        atomic::{AtomicBool, AtomicI64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use chrono::{Datelike, Duration as ChronoDuration, Local, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use crate::error::LauncherError;
+use crate::state::{self, ChangeOrigin};
+
+const PLAYTIME_KEY: &str = "playtime";
+const MAX_SESSIONS: usize = 1000;
+const DEFAULT_TICK_SECONDS: u64 = 30;
+const PERSIST_EVERY: Duration = Duration::from_secs(5 * 60);
+
+static FOCUSED: AtomicBool = AtomicBool::new(false);
+static LAST_ACTIVITY: AtomicI64 = AtomicI64::new(0);
+static CURRENT: Mutex<Option<SessionRecord>> = Mutex::new(None);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SessionRecord {
+    started_at: i64,
+    ended_at: Option<i64>,
+    uptime_seconds: u64,
+    active_seconds: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct PlaytimeState {
+    sessions: Vec<SessionRecord>,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct Totals {
+    uptime_seconds: u64,
+    active_seconds: u64,
+    sessions: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PlaytimeStats {
+    today: Totals,
+    this_week: Totals,
+    last_7_days: Totals,
+    all_time: Totals,
+    current: Option<SessionRecord>,
+    idle_seconds: Option<u64>,
+}
+
+fn tick() -> Duration {
+    let seconds = env::var("PLAYTIME_TICK_SECONDS")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|seconds| *seconds > 0)
+        .unwrap_or(DEFAULT_TICK_SECONDS);
+    Duration::from_secs(seconds)
+}
+
+pub(crate) fn set_focused(focused: bool) {
+    FOCUSED.store(focused, Ordering::Relaxed);
+    if focused {
+        touch();
+    }
+}
+
+pub(crate) fn touch() {
+    LAST_ACTIVITY.store(Utc::now().timestamp(), Ordering::Relaxed);
+}
+
+pub(crate) fn idle_for() -> Option<Duration> {
+    let last = LAST_ACTIVITY.load(Ordering::Relaxed);
+    if last == 0 {
+        return None;
+    }
+    if FOCUSED.load(Ordering::Relaxed) {
+        return Some(Duration::ZERO);
+    }
+    let elapsed = Utc::now().timestamp().saturating_sub(last);
+    Some(Duration::from_secs(elapsed.max(0) as u64))
+}
+
+fn persist(record: &SessionRecord) {
+    let _ = state::update(
+        PLAYTIME_KEY,
+        ChangeOrigin::Scheduler,
+        |stored: &mut PlaytimeState| {
+            match stored
+                .sessions
+                .iter_mut()
+                .rev()
+                .find(|session| session.started_at == record.started_at)
+            {
+                Some(session) => *session = record.clone(),
+                None => stored.sessions.push(record.clone()),
+            }
+            if stored.sessions.len() > MAX_SESSIONS {
+                let excess = stored.sessions.len() - MAX_SESSIONS;
+                stored.sessions.drain(..excess);
+            }
+        },
+    );
+}
+
+async fn persist_off_thread(record: SessionRecord) {
+    let _ = tauri::async_runtime::spawn_blocking(move || persist(&record)).await;
+}
+
+fn advance(tick: Duration) -> Option<SessionRecord> {
+    let mut current = CURRENT.lock().unwrap();
+    let record = current.as_mut()?;
+    let seconds = tick.as_secs();
+    record.uptime_seconds += seconds;
+    let active = idle_for().is_some_and(|idle| idle < tick);
+    if active {
+        record.active_seconds += seconds;
+    }
+    Some(record.clone())
+}
+
+async fn finish() {
+    let Some(mut record) = CURRENT.lock().unwrap().take() else {
+        return;
+    };
+    record.ended_at = Some(Utc::now().timestamp());
+    persist_off_thread(record).await;
+}
+
+pub(crate) fn start_session() -> oneshot::Sender<()> {
+    let record = SessionRecord {
+        started_at: Utc::now().timestamp(),
+        ended_at: None,
+        uptime_seconds: 0,
+        active_seconds: 0,
+    };
+    CURRENT.lock().unwrap().replace(record.clone());
+    let (stop_tx, mut stop_rx) = oneshot::channel::<()>();
+    let tick = tick();
+    tauri::async_runtime::spawn(async move {
+        persist_off_thread(record).await;
+        let mut unsaved = Duration::ZERO;
+        loop {
+            tokio::select! {
+                _ = &mut stop_rx => break,
+                _ = tokio::time::sleep(tick) => {
+                    let Some(record) = advance(tick) else {
+                        continue;
+                    };
+                    unsaved += tick;
+                    if unsaved >= PERSIST_EVERY {
+                        unsaved = Duration::ZERO;
+                        persist_off_thread(record).await;
+                    }
+                }
+            }
+        }
+        finish().await;
+    });
+    stop_tx
+}
+
+fn last_seen(session: &SessionRecord) -> i64 {
+    session
+        .ended_at
+        .unwrap_or(session.started_at + session.uptime_seconds as i64)
+}
+
+fn totals<'a>(sessions: impl Iterator<Item = &'a SessionRecord>, since: i64) -> Totals {
+    sessions.filter(|session| last_seen(session) >= since).fold(
+        Totals::default(),
+        |mut totals, session| {
+            totals.uptime_seconds += session.uptime_seconds;
+            totals.active_seconds += session.active_seconds;
+            totals.sessions += 1;
+            totals
+        },
+    )
+}
+
+fn local_midnight(days_back: i64) -> i64 {
+    let date = Local::now().date_naive() - ChronoDuration::days(days_back);
+    Local
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .earliest()
+        .map(|midnight| midnight.timestamp())
+        .unwrap_or_else(|| Utc::now().timestamp())
+}
+
+#[tauri::command]
+pub(crate) async fn playtime_stats() -> Result<PlaytimeStats, LauncherError> {
+    let stored: PlaytimeState = state::load(PLAYTIME_KEY);
+    let current = CURRENT.lock().unwrap().clone();
+    let mut sessions = stored.sessions;
+    if let Some(record) = &current {
+        match sessions
+            .iter_mut()
+            .rev()
+            .find(|session| session.started_at == record.started_at)
+        {
+            Some(session) => *session = record.clone(),
+            None => sessions.push(record.clone()),
+        }
+    }
+    let weekday = i64::from(Local::now().weekday().num_days_from_monday());
+    Ok(PlaytimeStats {
+        today: totals(sessions.iter(), local_midnight(0)),
+        this_week: totals(sessions.iter(), local_midnight(weekday)),
+        last_7_days: totals(sessions.iter(), local_midnight(6)),
+        all_time: totals(sessions.iter(), 0),
+        current,
+        idle_seconds: idle_for().map(|idle| idle.as_secs()),
+    })
+}
@@ -0,0 +1,191 @@
+use std::{collections::BTreeMap, env};
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::{
+    cli,
+    error::{ErrorKind, LauncherError},
+    events,
+};
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum PromptCode {
+    UpdateFailed,
+    UpdateFailedAfterStash,
+    SubmodulesFailed,
+    MigrationFailed,
+    InsufficientDisk,
+    RestoreStash,
+    NpmInstallFailed,
+    ServerStartFailed,
+    ReadOnlyPath,
+    RepoBroken,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum PromptAction {
+    RetryWithStash,
+    ManageStash,
+    SkipUpdate,
+    RestoreStash,
+    DiscardStash,
+    RetryInstall,
+    RepairNodeModules,
+    ForceStart,
+    RetryStart,
+    FixPermissions,
+    RelocateVendor,
+    RepairRepo,
+    Exit,
+}
+
+impl PromptCode {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::UpdateFailed => "updateFailed",
+            Self::UpdateFailedAfterStash => "updateFailedAfterStash",
+            Self::SubmodulesFailed => "submodulesFailed",
+            Self::MigrationFailed => "migrationFailed",
+            Self::InsufficientDisk => "insufficientDisk",
+            Self::RestoreStash => "restoreStash",
+            Self::NpmInstallFailed => "npmInstallFailed",
+            Self::ServerStartFailed => "serverStartFailed",
+            Self::ReadOnlyPath => "readOnlyPath",
+            Self::RepoBroken => "repoBroken",
+        }
+    }
+}
+
+impl PromptAction {
+    const ALL: [Self; 13] = [
+        Self::RetryWithStash,
+        Self::ManageStash,
+        Self::SkipUpdate,
+        Self::RestoreStash,
+        Self::DiscardStash,
+        Self::RetryInstall,
+        Self::RepairNodeModules,
+        Self::ForceStart,
+        Self::RetryStart,
+        Self::FixPermissions,
+        Self::RelocateVendor,
+        Self::RepairRepo,
+        Self::Exit,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::RetryWithStash => "retryWithStash",
+            Self::ManageStash => "manageStash",
+            Self::SkipUpdate => "skipUpdate",
+            Self::RestoreStash => "restoreStash",
+            Self::DiscardStash => "discardStash",
+            Self::RetryInstall => "retryInstall",
+            Self::RepairNodeModules => "repairNodeModules",
+            Self::ForceStart => "forceStart",
+            Self::RetryStart => "retryStart",
+            Self::FixPermissions => "fixPermissions",
+            Self::RelocateVendor => "relocateVendor",
+            Self::RepairRepo => "repairRepo",
+            Self::Exit => "exit",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.as_str().eq_ignore_ascii_case(value))
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Prompt {
+    code: PromptCode,
+    params: BTreeMap<&'static str, String>,
+    actions: Vec<PromptAction>,
+    answer: Option<PromptAction>,
+}
+
+impl Prompt {
+    pub(crate) fn new(code: PromptCode, actions: &[PromptAction]) -> Self {
+        let actions = actions.to_vec();
+        let answer = configured_answer(code).filter(|answer| actions.contains(answer));
+        Self {
+            code,
+            params: BTreeMap::new(),
+            actions,
+            answer,
+        }
+    }
+
+    pub(crate) fn param(mut self, key: &'static str, value: impl ToString) -> Self {
+        self.params.insert(key, value.to_string());
+        self
+    }
+
+    pub(crate) fn answer(&self) -> Option<PromptAction> {
+        self.answer
+    }
+
+    pub(crate) fn emit(&self, app: &AppHandle) {
+        if cli::is_headless() {
+            cli::print_event("prompt", self);
+        }
+        events::emit(app, "prompt", self);
+    }
+}
+
+fn configured_answer(code: PromptCode) -> Option<PromptAction> {
+    let answers = env::var("PROMPT_ANSWERS").ok()?;
+    answers.split(',').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        if key.trim().eq_ignore_ascii_case(code.as_str()) {
+            PromptAction::parse(value.trim())
+        } else {
+            None
+        }
+    })
+}
+
+pub(crate) fn for_start_failure(err: &LauncherError) -> Prompt {
+    match err.kind {
+        ErrorKind::ReadOnlyPath => Prompt::new(
+            PromptCode::ReadOnlyPath,
+            &[
+                PromptAction::FixPermissions,
+                PromptAction::RelocateVendor,
+                PromptAction::Exit,
+            ],
+        )
+        .param("path", err.details.as_deref().unwrap_or_default())
+        .param("detail", &err.message),
+        ErrorKind::NpmInstallFailed => Prompt::new(
+            PromptCode::NpmInstallFailed,
+            &[
+                PromptAction::RetryInstall,
+                PromptAction::RepairNodeModules,
+                PromptAction::ForceStart,
+                PromptAction::Exit,
+            ],
+        )
+        .param("detail", err),
+        ErrorKind::InsufficientDisk => Prompt::new(
+            PromptCode::InsufficientDisk,
+            &[
+                PromptAction::RetryStart,
+                PromptAction::ForceStart,
+                PromptAction::Exit,
+            ],
+        )
+        .param("detail", &err.message),
+        _ => Prompt::new(
+            PromptCode::ServerStartFailed,
+            &[PromptAction::RetryStart, PromptAction::Exit],
+        )
+        .param("detail", err),
+    }
+}
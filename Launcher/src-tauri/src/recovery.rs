@@ -0,0 +1,380 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+use tokio::fs as tokio_fs;
+
+use crate::{
+    dryrun::{self, Change, DryRun},
+    error::LauncherError,
+    events, log_line, logs_dir, silly_dir, state, userdata,
+};
+
+const SESSION_KEY: &str = "serverSession";
+const DEFAULT_SCAN_LIMIT: usize = 20;
+const RECOVERY_DIR: &str = "chat-recovery";
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ServerSession {
+    pid: Option<u32>,
+    started_at: String,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum ChatIssueKind {
+    Repaired,
+    Corrupted,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ChatIssue {
+    path: String,
+    kind: ChatIssueKind,
+    detail: String,
+    preserved_copy: Option<String>,
+    backup: Option<String>,
+}
+
+#[derive(Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ChatReport {
+    scanned: usize,
+    issues: Vec<ChatIssue>,
+}
+
+impl ServerSession {
+    pub(crate) fn started_at(&self) -> &str {
+        &self.started_at
+    }
+}
+
+pub(crate) fn mark_running(pid: Option<u32>) {
+    let session = ServerSession {
+        pid,
+        started_at: Local::now().to_rfc3339(),
+    };
+    let _ = state::save(SESSION_KEY, &Some(session));
+}
+
+pub(crate) fn mark_stopped() {
+    let _ = state::save(SESSION_KEY, &None::<ServerSession>);
+}
+
+pub(crate) fn unclean_session() -> Option<ServerSession> {
+    state::load(SESSION_KEY)
+}
+
+fn scan_limit() -> usize {
+    env::var("CHAT_SCAN_LIMIT")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|limit| *limit > 0)
+        .unwrap_or(DEFAULT_SCAN_LIMIT)
+}
+
+async fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    if let Ok(mut entries) = tokio_fs::read_dir(dir).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if entry.file_type().await.map(|t| t.is_dir()).unwrap_or(false) {
+                found.push(entry.path());
+            }
+        }
+    }
+    found
+}
+
+async fn jsonl_files(dir: &Path, out: &mut Vec<(SystemTime, PathBuf)>) {
+    let Ok(mut entries) = tokio_fs::read_dir(dir).await else {
+        return;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
+            continue;
+        }
+        if let Ok(meta) = entry.metadata().await {
+            if meta.is_file() {
+                out.push((meta.modified().unwrap_or(SystemTime::UNIX_EPOCH), path));
+            }
+        }
+    }
+}
+
+async fn recent_chats(silly_dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for user in subdirs(&userdata::dir(silly_dir)).await {
+        for character in subdirs(&user.join("chats")).await {
+            jsonl_files(&character, &mut files).await;
+        }
+        jsonl_files(&user.join("group chats"), &mut files).await;
+    }
+    files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    files
+        .into_iter()
+        .take(scan_limit())
+        .map(|(_, path)| path)
+        .collect()
+}
+
+fn chat_owner(chat: &Path) -> Option<(&Path, String)> {
+    let parent = chat.parent()?;
+    let (user, owner) = if parent.file_name()? == "group chats" {
+        (parent.parent()?, chat.file_stem()?)
+    } else {
+        (parent.parent()?.parent()?, parent.file_name()?)
+    };
+    let normalized = owner
+        .to_string_lossy()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    Some((user, normalized))
+}
+
+pub(crate) async fn latest_backup(chat: &Path) -> Option<PathBuf> {
+    let (user, owner) = chat_owner(chat)?;
+    let prefix = format!("chat_{owner}_");
+    let mut entries = tokio_fs::read_dir(user.join("backups")).await.ok()?;
+    let mut newest: Option<(SystemTime, PathBuf)> = None;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !name.starts_with(&prefix) || !name.ends_with(".jsonl") {
+            continue;
+        }
+        let modified = entry
+            .metadata()
+            .await
+            .and_then(|meta| meta.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        if newest
+            .as_ref()
+            .map(|(time, _)| modified > *time)
+            .unwrap_or(true)
+        {
+            newest = Some((modified, entry.path()));
+        }
+    }
+    newest.map(|(_, path)| path)
+}
+
+fn preserved_path(silly_dir: &Path, chat: &Path, stamp: &str) -> PathBuf {
+    let mut relative = userdata::relative(silly_dir, chat);
+    if relative.is_absolute() {
+        relative = chat.file_name().map(PathBuf::from).unwrap_or_default();
+    }
+    logs_dir().join(RECOVERY_DIR).join(stamp).join(relative)
+}
+
+async fn preserve(silly_dir: &Path, chat: &Path, stamp: &str) -> Result<PathBuf, String> {
+    let target = preserved_path(silly_dir, chat, stamp);
+    if let Some(parent) = target.parent() {
+        tokio_fs::create_dir_all(parent)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tokio_fs::copy(chat, &target)
+        .await
+        .map_err(|e| format!("Failed to preserve {}: {e}", chat.display()))?;
+    Ok(target)
+}
+
+async fn write_atomically(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let temp = path.with_extension("jsonl.recovering");
+    tokio_fs::write(&temp, contents)
+        .await
+        .map_err(|e| format!("Failed to write {}: {e}", temp.display()))?;
+    tokio_fs::rename(&temp, path)
+        .await
+        .map_err(|e| format!("Failed to replace {}: {e}", path.display()))
+}
+
+async fn check_chat(silly_dir: &Path, chat: &Path, stamp: &str) -> Option<ChatIssue> {
+    let raw = tokio_fs::read(chat).await.ok()?;
+    let display = userdata::relative(silly_dir, chat).display().to_string();
+    let issue = |kind, detail: String, preserved_copy: Option<PathBuf>| ChatIssue {
+        path: display.clone(),
+        kind,
+        detail,
+        preserved_copy: preserved_copy.map(|path| path.display().to_string()),
+        backup: None,
+    };
+
+    if raw.is_empty() {
+        return Some(issue(
+            ChatIssueKind::Corrupted,
+            "The chat file is empty.".into(),
+            None,
+        ));
+    }
+
+    let lines: Vec<&[u8]> = raw.split(|byte| *byte == b'\n').collect();
+    let is_blank = |line: &[u8]| line.iter().all(u8::is_ascii_whitespace);
+    let invalid: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !is_blank(line))
+        .filter(|(_, line)| serde_json::from_slice::<Value>(line).is_err())
+        .map(|(index, _)| index)
+        .collect();
+    let &last_invalid = invalid.last()?;
+
+    let last_content = lines.iter().rposition(|line| !is_blank(line)).unwrap_or(0);
+    if invalid.len() == 1 && last_invalid == last_content && last_invalid > 0 {
+        let cut: usize = lines[..last_invalid]
+            .iter()
+            .map(|line| line.len() + 1)
+            .sum();
+        let repaired = &raw[..cut];
+        let preserved = match preserve(silly_dir, chat, stamp).await {
+            Ok(path) => path,
+            Err(err) => {
+                return Some(issue(
+                    ChatIssueKind::Corrupted,
+                    format!("The last message is truncated, but it was left untouched: {err}"),
+                    None,
+                ))
+            }
+        };
+        return Some(match write_atomically(chat, repaired).await {
+            Ok(()) => issue(
+                ChatIssueKind::Repaired,
+                format!(
+                    "Removed a truncated final message ({} bytes).",
+                    raw.len() - cut
+                ),
+                Some(preserved),
+            ),
+            Err(err) => issue(ChatIssueKind::Corrupted, err, Some(preserved)),
+        });
+    }
+
+    Some(issue(
+        ChatIssueKind::Corrupted,
+        format!(
+            "{} line(s) are not valid JSON (first at line {}).",
+            invalid.len(),
+            invalid[0] + 1
+        ),
+        None,
+    ))
+}
+
+pub(crate) async fn verify_recent_chats(app: &AppHandle, silly_dir: &Path) -> ChatReport {
+    let stamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let chats = recent_chats(silly_dir).await;
+    let mut report = ChatReport {
+        scanned: chats.len(),
+        issues: Vec::new(),
+    };
+    for chat in &chats {
+        if let Some(mut issue) = check_chat(silly_dir, chat, &stamp).await {
+            issue.backup = latest_backup(chat)
+                .await
+                .map(|path| path.display().to_string());
+            log_line(
+                app,
+                &format!("Chat check: {} – {}", issue.path, issue.detail),
+            )
+            .await;
+            report.issues.push(issue);
+        }
+    }
+    if report.issues.is_empty() {
+        log_line(
+            app,
+            &format!(
+                "Checked {} recent chat file(s); no damage found.",
+                report.scanned
+            ),
+        )
+        .await;
+    } else {
+        events::emit(app, "chat-recovery", &report);
+    }
+    report
+}
+
+#[tauri::command]
+pub(crate) async fn verify_chats(app: AppHandle) -> Result<ChatReport, LauncherError> {
+    let silly = silly_dir()?;
+    Ok(verify_recent_chats(&app, &silly).await)
+}
+
+#[tauri::command]
+pub(crate) async fn restore_chat_backup(
+    app: AppHandle,
+    chat: String,
+    backup: String,
+    dry_run: Option<bool>,
+) -> Result<String, LauncherError> {
+    let silly = silly_dir()?;
+    let data = tokio_fs::canonicalize(userdata::dir(&silly))
+        .await
+        .map_err(|e| e.to_string())?;
+    let chat_path = userdata::resolve(&silly, &chat);
+    let chat_path = tokio_fs::canonicalize(&chat_path)
+        .await
+        .map_err(|e| format!("Chat {chat} not found: {e}"))?;
+    let backup_path = tokio_fs::canonicalize(&backup)
+        .await
+        .map_err(|e| format!("Backup {backup} not found: {e}"))?;
+    let in_backups = backup_path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .map(|name| name == "backups")
+        .unwrap_or(false);
+    if !chat_path.starts_with(&data) || !backup_path.starts_with(&data) || !in_backups {
+        return Err("Chats can only be restored from SillyTavern's backups folder.".into());
+    }
+
+    let stamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let silly_canonical = tokio_fs::canonicalize(&silly)
+        .await
+        .map_err(|e| e.to_string())?;
+    if dryrun::requested(dry_run) {
+        let mut plan = DryRun::new("restore_chat_backup");
+        plan.file(
+            Change::Create,
+            &preserved_path(&silly_canonical, &chat_path, &stamp),
+        );
+        plan.file(Change::Replace, &chat_path);
+        let plan = plan
+            .finish(
+                &app,
+                format!(
+                    "restoring {chat} would replace it with {}.",
+                    backup_path.display()
+                ),
+            )
+            .await;
+        return Ok(plan.summary().to_string());
+    }
+    let preserved = preserve(&silly_canonical, &chat_path, &stamp).await?;
+    let contents = tokio_fs::read(&backup_path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {e}", backup_path.display()))?;
+    write_atomically(&chat_path, &contents).await?;
+    let message = format!(
+        "Restored {chat} from {}. The previous version was saved to {}.",
+        backup_path.display(),
+        preserved.display()
+    );
+    log_line(&app, &message).await;
+    Ok(message)
+}
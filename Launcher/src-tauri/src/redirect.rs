@@ -0,0 +1,157 @@
+use std::{
+    convert::Infallible,
+    env,
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use hyper::{
+    header::{self, HeaderValue},
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::sync::oneshot;
+
+use crate::{log_line, state};
+
+const ENDPOINT_KEY: &str = "lastServerEndpoint";
+const DEFAULT_GRACE_MINUTES: u64 = 10;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct ServerEndpoint {
+    host: String,
+    port: u16,
+}
+
+fn grace_period() -> Duration {
+    let minutes = env::var("PORT_REDIRECT_GRACE_MINUTES")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_GRACE_MINUTES);
+    Duration::from_secs(minutes * 60)
+}
+
+fn redirect_target(req: &Request<Body>, new_port: u16, fallback_host: &str) -> String {
+    let host = req
+        .headers()
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| match value.rsplit_once(':') {
+            Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name.to_string(),
+            _ => value.to_string(),
+        })
+        .unwrap_or_else(|| fallback_host.to_string());
+    let path = req
+        .uri()
+        .path_and_query()
+        .map(|value| value.as_str())
+        .unwrap_or("/");
+    format!("http://{host}:{new_port}{path}")
+}
+
+async fn handle(
+    req: Request<Body>,
+    new_port: u16,
+    fallback_host: String,
+) -> Result<Response<Body>, Infallible> {
+    let target = redirect_target(&req, new_port, &fallback_host);
+    let mut response = Response::new(Body::from(format!(
+        "WeylandTavern moved to port {new_port}. Continue at {target}"
+    )));
+    *response.status_mut() = StatusCode::TEMPORARY_REDIRECT;
+    if let Ok(location) = HeaderValue::from_str(&target) {
+        response.headers_mut().insert(header::LOCATION, location);
+    }
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    Ok(response)
+}
+
+pub(crate) async fn migrate(app: &AppHandle, host: &str, port: u16) -> Option<oneshot::Sender<()>> {
+    let current = ServerEndpoint {
+        host: host.to_string(),
+        port,
+    };
+    let previous: Option<ServerEndpoint> = state::load(ENDPOINT_KEY);
+    if let Err(err) = state::save(ENDPOINT_KEY, &Some(current.clone())) {
+        log_line(app, &format!("Failed to record the server port: {err}")).await;
+    }
+
+    let previous = previous.filter(|previous| previous.port != current.port)?;
+    let grace = grace_period();
+    if grace.is_zero() {
+        return None;
+    }
+    let ip = previous.host.parse::<IpAddr>().ok()?;
+    let addr = SocketAddr::new(ip, previous.port);
+
+    let fallback_host = if ip.is_unspecified() {
+        "127.0.0.1".to_string()
+    } else {
+        previous.host.clone()
+    };
+    let make_service = make_service_fn(move |_| {
+        let fallback_host = fallback_host.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                handle(req, port, fallback_host.clone())
+            }))
+        }
+    });
+    let server = match Server::try_bind(&addr) {
+        Ok(builder) => builder.serve(make_service),
+        Err(err) => {
+            log_line(
+                app,
+                &format!(
+                    "Could not hold previous port {} for redirects: {err}",
+                    previous.port
+                ),
+            )
+            .await;
+            return None;
+        }
+    };
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let graceful = server.with_graceful_shutdown(async move {
+        tokio::select! {
+            _ = shutdown_rx => {}
+            _ = tokio::time::sleep(grace) => {}
+        }
+    });
+    let app_for_task = app.clone();
+    let old_port = previous.port;
+    tauri::async_runtime::spawn(async move {
+        match graceful.await {
+            Ok(()) => {
+                log_line(
+                    &app_for_task,
+                    &format!("Stopped redirecting port {old_port}."),
+                )
+                .await
+            }
+            Err(err) => {
+                log_line(
+                    &app_for_task,
+                    &format!("Port redirect on {old_port} stopped: {err}"),
+                )
+                .await
+            }
+        }
+    });
+
+    log_line(
+        app,
+        &format!(
+            "Server moved from port {old_port} to {port}; redirecting the old port for {} minutes.",
+            grace.as_secs() / 60
+        ),
+    )
+    .await;
+    Some(shutdown_tx)
+}
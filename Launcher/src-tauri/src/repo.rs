@@ -0,0 +1,443 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use chrono::Local;
+use serde::Serialize;
+use tauri::{AppHandle, State};
+use tokio::fs as tokio_fs;
+
+use crate::{
+    error::{ErrorKind, LauncherError},
+    fetch_depth, installs, load_env, log_line,
+    operation::{self, Operation},
+    run_git, unshallow_checkout,
+    updates::{self, UpdatePolicyMode},
+    vendor_dir, ServerState,
+};
+
+const STALE_LOCK_AGE: Duration = Duration::from_secs(60);
+const DEFAULT_REMOTE: &str = "origin";
+
+#[derive(Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "state")]
+pub(crate) enum RepoState {
+    Healthy,
+    #[serde(rename_all = "camelCase")]
+    IndexLock {
+        path: String,
+        age_seconds: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    MissingRemote {
+        remote: Option<String>,
+        url: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    DetachedHead {
+        commit: String,
+        branch: Option<String>,
+    },
+    Shallow,
+    #[serde(rename_all = "camelCase")]
+    Diverged {
+        branch: String,
+        upstream: String,
+        ahead: u32,
+        behind: u32,
+    },
+}
+
+impl RepoState {
+    pub(crate) fn is_healthy(&self) -> bool {
+        matches!(self, Self::Healthy)
+    }
+
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Self::Healthy => "The vendor checkout looks healthy.".into(),
+            Self::IndexLock { path, age_seconds } => format!(
+                "A leftover git lock file ({path}, {age_seconds}s old) blocks every git command. It is usually left behind by a crashed or killed git process."
+            ),
+            Self::MissingRemote {
+                remote: Some(remote),
+                ..
+            } => format!("The vendor checkout has no \"{remote}\" remote to pull from."),
+            Self::MissingRemote { remote: None, .. } => {
+                "The vendor checkout's branch does not track any remote branch.".into()
+            }
+            Self::DetachedHead { commit, branch } => format!(
+                "The vendor checkout is not on a branch (detached at {}){}.",
+                &commit[..commit.len().min(10)],
+                branch
+                    .as_deref()
+                    .map(|branch| format!("; it can switch back to {branch}"))
+                    .unwrap_or_default()
+            ),
+            Self::Shallow => {
+                "The vendor checkout is shallow but VENDOR_FETCH_DEPTH is not set, so pulls may fail to merge.".into()
+            }
+            Self::Diverged {
+                branch,
+                upstream,
+                ahead,
+                behind,
+            } => format!(
+                "{branch} has {ahead} local commit(s) that are not on {upstream}, which has {behind} new commit(s). A pull cannot fast-forward."
+            ),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RepairReport {
+    repaired: RepoState,
+    state: RepoState,
+    message: String,
+}
+
+async fn git_text(repo: &Path, args: &[&str]) -> Option<String> {
+    let output = run_git(repo, args).await.ok()?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    output.status.success().then_some(text)
+}
+
+async fn git_ok(repo: &Path, args: &[&str]) -> Result<String, LauncherError> {
+    let output = run_git(repo, args).await?;
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    if output.status.success() {
+        Ok(text.trim().to_string())
+    } else {
+        Err(LauncherError::new(
+            ErrorKind::Git,
+            format!("git {} failed: {}", args.join(" "), text.trim()),
+        ))
+    }
+}
+
+async fn index_lock(repo: &Path) -> Option<PathBuf> {
+    let git_dir = git_text(repo, &["rev-parse", "--git-dir"]).await;
+    let git_dir = git_dir
+        .map(|dir| repo.join(dir))
+        .unwrap_or_else(|| repo.join(".git"));
+    let lock = git_dir.join("index.lock");
+    lock.is_file().then_some(lock)
+}
+
+fn lock_age(lock: &Path) -> u64 {
+    std::fs::metadata(lock)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|age| age.as_secs())
+        .unwrap_or(0)
+}
+
+fn configured_url() -> Option<String> {
+    env::var("VENDOR_REPO_URL")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+async fn default_branch(repo: &Path, remote: &str) -> Option<String> {
+    if let Some((_, branch)) = installs::pull_source().filter(|(_, branch)| branch != "HEAD") {
+        return Some(branch);
+    }
+    if let Some(branch) = env::var("VENDOR_BRANCH")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    {
+        return Some(branch);
+    }
+    git_text(
+        repo,
+        &[
+            "symbolic-ref",
+            "--short",
+            &format!("refs/remotes/{remote}/HEAD"),
+        ],
+    )
+    .await
+    .and_then(|head| head.split_once('/').map(|(_, branch)| branch.to_string()))
+}
+
+fn remote_missing(pinned: bool, wanted: &str, remotes: &[&str]) -> bool {
+    let is_url = wanted.contains("://") || wanted.contains('@');
+    if pinned {
+        !is_url && !remotes.contains(&wanted)
+    } else {
+        remotes.is_empty()
+    }
+}
+
+fn ahead_behind(counts: &str) -> (u32, u32) {
+    let mut counts = counts
+        .split_whitespace()
+        .map(|count| count.parse::<u32>().unwrap_or(0));
+    (counts.next().unwrap_or(0), counts.next().unwrap_or(0))
+}
+
+pub(crate) async fn detect(repo: &Path) -> RepoState {
+    if let Some(lock) = index_lock(repo).await {
+        let age_seconds = lock_age(&lock);
+        if Duration::from_secs(age_seconds) >= STALE_LOCK_AGE {
+            return RepoState::IndexLock {
+                path: lock.display().to_string(),
+                age_seconds,
+            };
+        }
+    }
+
+    let remotes = git_text(repo, &["remote"]).await.unwrap_or_default();
+    let remotes: Vec<&str> = remotes
+        .lines()
+        .map(str::trim)
+        .filter(|remote| !remote.is_empty())
+        .collect();
+    let source = installs::pull_source();
+    let wanted = source
+        .as_ref()
+        .map(|(remote, _)| remote.as_str())
+        .unwrap_or(DEFAULT_REMOTE);
+    if remote_missing(source.is_some(), wanted, &remotes) {
+        return RepoState::MissingRemote {
+            remote: Some(wanted.to_string()),
+            url: configured_url(),
+        };
+    }
+
+    let branch = git_text(repo, &["symbolic-ref", "--quiet", "--short", "HEAD"]).await;
+    let Some(branch) = branch else {
+        let commit = git_text(repo, &["rev-parse", "HEAD"])
+            .await
+            .unwrap_or_default();
+        return RepoState::DetachedHead {
+            commit,
+            branch: default_branch(repo, wanted).await,
+        };
+    };
+
+    if fetch_depth().is_none()
+        && git_text(repo, &["rev-parse", "--is-shallow-repository"])
+            .await
+            .as_deref()
+            == Some("true")
+    {
+        return RepoState::Shallow;
+    }
+
+    if source.is_some() {
+        return RepoState::Healthy;
+    }
+    let Some(upstream) = git_text(repo, &["rev-parse", "--abbrev-ref", "@{u}"]).await else {
+        return RepoState::MissingRemote {
+            remote: None,
+            url: configured_url(),
+        };
+    };
+    let counts = git_text(
+        repo,
+        &["rev-list", "--left-right", "--count", "HEAD...@{u}"],
+    )
+    .await
+    .unwrap_or_default();
+    let (ahead, behind) = ahead_behind(&counts);
+    if ahead > 0 && behind > 0 {
+        return RepoState::Diverged {
+            branch,
+            upstream,
+            ahead,
+            behind,
+        };
+    }
+    RepoState::Healthy
+}
+
+async fn save_local_changes(repo: &Path, stamp: &str) -> Result<Option<String>, LauncherError> {
+    let dirty = git_text(repo, &["status", "--porcelain", "--untracked-files=no"])
+        .await
+        .is_some_and(|status| !status.is_empty());
+    if !dirty {
+        return Ok(None);
+    }
+    let label = format!("launcher repair {stamp}");
+    let Some(commit) = git_text(repo, &["stash", "create", &label]).await else {
+        return Err(LauncherError::new(
+            ErrorKind::Git,
+            "Could not record the local changes before repairing.",
+        ));
+    };
+    let keep = format!("launcher-changes-{stamp}");
+    git_ok(repo, &["branch", &keep, &commit]).await?;
+    git_ok(repo, &["reset", "--hard", "--quiet"]).await?;
+    Ok(Some(keep))
+}
+
+fn saved_changes_note(saved: Option<String>) -> String {
+    saved
+        .map(|keep| {
+            format!(" Local changes were saved as branch {keep}; bring them back with \"git stash apply {keep}\".")
+        })
+        .unwrap_or_default()
+}
+
+fn ensure_not_frozen(state: &RepoState) -> Result<(), LauncherError> {
+    let moves_checkout = matches!(
+        state,
+        RepoState::DetachedHead { .. } | RepoState::Diverged { .. }
+    );
+    if moves_checkout && updates::policy_mode() == UpdatePolicyMode::Frozen {
+        return Err(LauncherError::new(
+            ErrorKind::RepoState,
+            "UPDATE_POLICY is frozen, so the launcher will not move the checkout to another commit. Change the update policy first, or fix the checkout by hand.",
+        ));
+    }
+    Ok(())
+}
+
+async fn repair(app: &AppHandle, repo: &Path, state: &RepoState) -> Result<String, LauncherError> {
+    let stamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    match state {
+        RepoState::Healthy => Ok("Nothing to repair.".into()),
+        RepoState::IndexLock { path, .. } => {
+            tokio_fs::remove_file(path)
+                .await
+                .map_err(|e| format!("Failed to remove {path}: {e}"))?;
+            Ok(format!("Removed the stale lock file {path}."))
+        }
+        RepoState::MissingRemote { remote, url } => {
+            let name = remote.as_deref().unwrap_or(DEFAULT_REMOTE);
+            let exists = git_text(repo, &["remote"])
+                .await
+                .is_some_and(|remotes| remotes.lines().any(|line| line.trim() == name));
+            let mut message = match (exists, url) {
+                (true, Some(url)) if remote.is_some() => {
+                    git_ok(repo, &["remote", "set-url", name, url]).await?;
+                    format!("Remote {name} now points at {url}.")
+                }
+                (true, _) => format!("Remote {name} is present."),
+                (false, Some(url)) => {
+                    git_ok(repo, &["remote", "add", name, url]).await?;
+                    format!("Added remote {name} ({url}).")
+                }
+                (false, None) => return Err(LauncherError::new(
+                    ErrorKind::NotConfigured,
+                    "Set VENDOR_REPO_URL in .env so the launcher knows which remote to restore.",
+                )),
+            };
+            log_line(app, &format!("Fetching {name}...")).await;
+            git_ok(repo, &["fetch", name]).await?;
+            let remote = name;
+            if let (Some(branch), Some(target)) = (
+                git_text(repo, &["symbolic-ref", "--quiet", "--short", "HEAD"]).await,
+                default_branch(repo, remote).await,
+            ) {
+                let upstream = format!("{remote}/{target}");
+                if git_ok(repo, &["branch", "--set-upstream-to", &upstream, &branch])
+                    .await
+                    .is_ok()
+                {
+                    message.push_str(&format!(" {branch} tracks {upstream}."));
+                }
+            }
+            Ok(message)
+        }
+        RepoState::DetachedHead { commit, branch } => {
+            let Some(branch) = branch else {
+                return Err(LauncherError::new(
+                    ErrorKind::NotConfigured,
+                    "Cannot tell which branch to return to. Set VENDOR_BRANCH in .env.",
+                ));
+            };
+            let saved = save_local_changes(repo, &stamp).await?;
+            let keep = format!("launcher-detached-{stamp}");
+            git_ok(repo, &["branch", &keep, commit]).await?;
+            git_ok(repo, &["checkout", branch]).await?;
+            let mut message =
+                format!("Switched back to {branch}. The detached commit is kept as branch {keep}.");
+            message.push_str(&saved_changes_note(saved));
+            Ok(message)
+        }
+        RepoState::Shallow => unshallow_checkout(app.clone()).await,
+        RepoState::Diverged {
+            branch, upstream, ..
+        } => {
+            let saved = save_local_changes(repo, &stamp).await?;
+            let keep = format!("launcher-local-{stamp}");
+            git_ok(repo, &["branch", &keep, "HEAD"]).await?;
+            git_ok(repo, &["reset", "--hard", upstream]).await?;
+            let mut message = format!(
+                "Reset {branch} to {upstream}. Your local commits are kept as branch {keep}."
+            );
+            message.push_str(&saved_changes_note(saved));
+            Ok(message)
+        }
+    }
+}
+
+pub(crate) async fn run_repair(app: &AppHandle) -> Result<RepairReport, LauncherError> {
+    let repo = vendor_dir()?;
+    let found = detect(&repo).await;
+    ensure_not_frozen(&found)?;
+    let message = repair(app, &repo, &found).await?;
+    log_line(app, &message).await;
+    Ok(RepairReport {
+        repaired: found,
+        state: detect(&repo).await,
+        message,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn repo_state() -> Result<RepoState, LauncherError> {
+    load_env();
+    let repo = vendor_dir()?;
+    Ok(detect(&repo).await)
+}
+
+#[tauri::command]
+pub(crate) async fn repair_repo(
+    app: AppHandle,
+    state: State<'_, ServerState>,
+) -> Result<RepairReport, LauncherError> {
+    let _operation = operation::begin(&app, Operation::RepoRepair)?;
+    load_env();
+    installs::ensure_stopped(state.inner(), "repairing the vendor checkout")?;
+    run_repair(&app).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_missing_checks_the_pinned_remote() {
+        assert!(!remote_missing(true, "origin", &["origin", "fork"]));
+        assert!(remote_missing(true, "upstream", &["origin"]));
+        assert!(!remote_missing(true, "https://example.com/st.git", &[]));
+        assert!(!remote_missing(true, "git@example.com:st.git", &[]));
+    }
+
+    #[test]
+    fn remote_missing_without_a_pin_needs_any_remote() {
+        assert!(remote_missing(false, DEFAULT_REMOTE, &[]));
+        assert!(!remote_missing(false, DEFAULT_REMOTE, &["fork"]));
+    }
+
+    #[test]
+    fn ahead_behind_parses_rev_list_counts() {
+        assert_eq!(ahead_behind("3\t5"), (3, 5));
+        assert_eq!(ahead_behind("0 0"), (0, 0));
+        assert_eq!(ahead_behind(""), (0, 0));
+        assert_eq!(ahead_behind("x 2"), (0, 2));
+    }
+}
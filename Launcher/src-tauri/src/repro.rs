@@ -0,0 +1,191 @@
+use std::{collections::VecDeque, env, sync::Mutex};
+
+use chrono::Local;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Listener, Manager};
+use tokio::fs as tokio_fs;
+
+use crate::{
+    error::LauncherError, load_env, log_line, logs_dir, observer, onboarding, operation, state,
+    updates, ServerState,
+};
+
+const FORMAT: &str = "weylandtavern-repro";
+const FORMAT_VERSION: u32 = 1;
+const EVENT_CAPACITY: usize = 100;
+const RECORDED_EVENTS: &[&str] = &[
+    "log",
+    "server-ready",
+    "server-health",
+    "server-crashed",
+    "prompt",
+    "chat-recovery",
+    "tunnel-url",
+    "certificate-expiry",
+    "certificate-renewed",
+    "config-drift",
+    "instance-forwarded",
+    "update-available",
+    "operation-changed",
+];
+
+const PERSISTED_KEYS: &[&str] = &[
+    "_revisions",
+    "appliedMigrations",
+    "onboarding",
+    "notifications",
+    "tlsProxyEnabled",
+    "lastUpdateCheck",
+    "lastServerEndpoint",
+    "serverSession",
+    "uiState",
+];
+
+static EVENTS: Mutex<VecDeque<RecordedEvent>> = Mutex::new(VecDeque::new());
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RecordedEvent {
+    at: String,
+    event: &'static str,
+    payload: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ReproCapture {
+    path: String,
+    state: Value,
+}
+
+pub(crate) fn records(event: &str) -> bool {
+    RECORDED_EVENTS.contains(&event)
+}
+
+pub(crate) fn listen(app: &AppHandle) {
+    for &event in RECORDED_EVENTS {
+        app.listen_any(event, move |emitted| {
+            let payload = serde_json::from_str(emitted.payload()).unwrap_or(Value::Null);
+            let mut events = EVENTS.lock().unwrap();
+            if events.len() >= EVENT_CAPACITY {
+                events.pop_front();
+            }
+            events.push_back(RecordedEvent {
+                at: Local::now().to_rfc3339(),
+                event,
+                payload,
+            });
+        });
+    }
+}
+
+fn server_machine(app: &AppHandle) -> Value {
+    let state = app.state::<ServerState>();
+    let state = state.inner();
+    let pid = state
+        .child
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|child| child.id());
+    let endpoint = state
+        .hooks
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|ctx| json!({ "host": ctx.host, "port": ctx.port, "url": ctx.url }));
+    json!({
+        "running": pid.is_some(),
+        "pid": pid,
+        "endpoint": endpoint,
+        "tuning": state.tuning.lock().unwrap().clone(),
+    })
+}
+
+fn active_operations(app: &AppHandle) -> Vec<&'static str> {
+    let state = app.state::<ServerState>();
+    let state = state.inner();
+    let mut active = Vec::new();
+    if state.child.lock().unwrap().is_some() {
+        active.push("server");
+    }
+    if state.share_proxy.lock().unwrap().is_some() {
+        active.push("guestProxy");
+    }
+    if state.tls_proxy.lock().unwrap().is_some() {
+        active.push("tlsProxy");
+    }
+    if state.port_redirect.lock().unwrap().is_some() {
+        active.push("portRedirect");
+    }
+    if state.liveness.lock().unwrap().is_some() {
+        active.push("livenessProbe");
+    }
+    if state.config_watch.lock().unwrap().is_some() {
+        active.push("configWatch");
+    }
+    if state.playtime.lock().unwrap().is_some() {
+        active.push("playtimeSession");
+    }
+    if state.tunnel.lock().unwrap().is_some() {
+        active.push("tunnel");
+    }
+    if app.state::<observer::ObserverState>().is_active() {
+        active.push("observerSession");
+    }
+    active
+}
+
+#[tauri::command]
+pub(crate) async fn capture_repro_state(
+    app: AppHandle,
+    ui_state: Option<Value>,
+) -> Result<ReproCapture, LauncherError> {
+    load_env();
+    let now = Local::now();
+    let events: Vec<RecordedEvent> = EVENTS.lock().unwrap().iter().cloned().collect();
+    let persisted = Value::Object(state::snapshot_of(PERSISTED_KEYS));
+    let captured = json!({
+        "format": FORMAT,
+        "version": FORMAT_VERSION,
+        "capturedAt": now.to_rfc3339(),
+        "launcherVersion": app.package_info().version.to_string(),
+        "os": env::consts::OS,
+        "arch": env::consts::ARCH,
+        "machine": {
+            "server": server_machine(&app),
+            "onboarding": onboarding::get_onboarding_state().await.ok(),
+            "persisted": persisted,
+            "updatePolicy": updates::policy_mode(),
+            "ui": ui_state,
+        },
+        "activeOperations": active_operations(&app),
+        "currentOperation": operation::current(&app),
+        "events": events,
+        "config": observer::launcher_config(),
+    });
+    let captured = observer::redact_value(captured);
+
+    let dir = logs_dir().join("repro");
+    tokio_fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    let path = dir.join(format!("repro-{}.json", now.format("%Y%m%d-%H%M%S")));
+    let body = serde_json::to_string_pretty(&captured).map_err(|e| e.to_string())?;
+    tokio_fs::write(&path, body)
+        .await
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    log_line(
+        &app,
+        &format!(
+            "Saved launcher state for bug reports to {}.",
+            path.display()
+        ),
+    )
+    .await;
+    Ok(ReproCapture {
+        path: path.to_string_lossy().into_owned(),
+        state: captured,
+    })
+}
@@ -0,0 +1,219 @@
+use std::{env, future::Future, process::Output, time::Duration};
+
+use tokio::time::sleep;
+
+use crate::offline;
+
+#[derive(Clone, Copy)]
+pub(crate) enum OperationClass {
+    Health,
+    Git,
+    Npm,
+    CharacterSync,
+}
+
+impl OperationClass {
+    fn env_prefix(self) -> &'static str {
+        match self {
+            Self::Health => "RETRY_HEALTH",
+            Self::Git => "RETRY_GIT",
+            Self::Npm => "RETRY_NPM",
+            Self::CharacterSync => "RETRY_CHARACTER_SYNC",
+        }
+    }
+
+    fn default_policy(self) -> RetryPolicy {
+        match self {
+            Self::Health => RetryPolicy {
+                max_attempts: 30,
+                initial_delay: Duration::from_millis(500),
+                max_delay: Duration::from_millis(3000),
+                backoff: 1.1,
+                jitter: 0.0,
+            },
+            Self::Git => RetryPolicy {
+                max_attempts: 3,
+                initial_delay: Duration::from_millis(2000),
+                max_delay: Duration::from_millis(15000),
+                backoff: 2.0,
+                jitter: 0.2,
+            },
+            Self::Npm => RetryPolicy {
+                max_attempts: 2,
+                initial_delay: Duration::from_millis(3000),
+                max_delay: Duration::from_millis(30000),
+                backoff: 2.0,
+                jitter: 0.2,
+            },
+            Self::CharacterSync => RetryPolicy {
+                max_attempts: 2,
+                initial_delay: Duration::from_millis(2000),
+                max_delay: Duration::from_millis(15000),
+                backoff: 2.0,
+                jitter: 0.2,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct RetryPolicy {
+    pub(crate) max_attempts: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+    backoff: f64,
+    jitter: f64,
+}
+
+pub(crate) struct Attempted<R> {
+    pub(crate) value: R,
+    pub(crate) attempts: u32,
+}
+
+impl RetryPolicy {
+    pub(crate) fn for_class(class: OperationClass) -> Self {
+        let defaults = class.default_policy();
+        let prefix = class.env_prefix();
+        let read = |suffix: &str| {
+            env::var(format!("{prefix}_{suffix}"))
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        let max_attempts = read("ATTEMPTS")
+            .and_then(|value| value.parse::<u32>().ok())
+            .filter(|value| *value > 0)
+            .unwrap_or(defaults.max_attempts);
+        let initial_delay = read("DELAY_MS")
+            .and_then(|value| value.parse::<u64>().ok())
+            .map(Duration::from_millis)
+            .unwrap_or(defaults.initial_delay);
+        let max_delay = read("MAX_DELAY_MS")
+            .and_then(|value| value.parse::<u64>().ok())
+            .map(Duration::from_millis)
+            .unwrap_or(defaults.max_delay);
+        let backoff = read("BACKOFF")
+            .and_then(|value| value.parse::<f64>().ok())
+            .filter(|value| value.is_finite() && *value >= 1.0)
+            .unwrap_or(defaults.backoff);
+        let jitter = read("JITTER")
+            .and_then(|value| value.parse::<f64>().ok())
+            .filter(|value| (0.0..=1.0).contains(value))
+            .unwrap_or(defaults.jitter);
+
+        Self {
+            max_attempts,
+            initial_delay,
+            max_delay,
+            backoff,
+            jitter,
+        }
+    }
+
+    fn delay_for(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(32) as i32;
+        let base = self.initial_delay.as_secs_f64() * self.backoff.powi(exponent);
+        let capped = base.min(self.max_delay.as_secs_f64());
+        let spread = capped * self.jitter * (rand::random::<f64>() * 2.0 - 1.0);
+        Duration::from_secs_f64((capped + spread).max(0.0))
+    }
+
+    pub(crate) async fn run<R, F, Fut>(
+        &self,
+        mut operation: F,
+        should_retry: impl Fn(&R) -> bool,
+    ) -> Attempted<R>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = R>,
+    {
+        let mut attempt = 1;
+        loop {
+            let value = operation(attempt).await;
+            if attempt >= self.max_attempts || !should_retry(&value) {
+                return Attempted {
+                    value,
+                    attempts: attempt,
+                };
+            }
+            sleep(self.delay_for(attempt)).await;
+            attempt += 1;
+        }
+    }
+}
+
+pub(crate) fn attempt_note(attempts: u32) -> Option<String> {
+    (attempts > 1).then(|| format!("succeeded on attempt {attempts}"))
+}
+
+pub(crate) fn is_transient_failure<E>(result: &Result<Output, E>) -> bool {
+    match result {
+        Ok(output) => {
+            let transient = !output.status.success()
+                && is_transient_network_error(&String::from_utf8_lossy(&output.stderr));
+            if transient {
+                offline::invalidate();
+            }
+            transient
+        }
+        Err(_) => false,
+    }
+}
+
+fn is_transient_network_error(output: &str) -> bool {
+    let lower = output.to_ascii_lowercase();
+    [
+        "could not resolve host",
+        "connection timed out",
+        "connection reset",
+        "connection refused",
+        "operation timed out",
+        "early eof",
+        "the remote end hung up unexpectedly",
+        "rpc failed",
+        "tls handshake",
+        "econnreset",
+        "etimedout",
+        "eai_again",
+        "enotfound",
+        "econnrefused",
+        "socket hang up",
+        "network request",
+    ]
+    .iter()
+    .any(|needle| lower.contains(needle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(jitter: f64) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+            backoff: 2.0,
+            jitter,
+        }
+    }
+
+    #[test]
+    fn delay_for_backs_off_up_to_the_cap() {
+        let policy = policy(0.0);
+        let delays: Vec<u128> = (1..=6)
+            .map(|attempt| policy.delay_for(attempt).as_millis())
+            .collect();
+        assert_eq!(delays, vec![100, 200, 400, 800, 1000, 1000]);
+    }
+
+    #[test]
+    fn delay_for_keeps_jitter_within_bounds() {
+        let policy = policy(0.5);
+        for _ in 0..100 {
+            let delay = policy.delay_for(2).as_millis();
+            assert!((100..=300).contains(&delay), "{delay} out of range");
+        }
+    }
+}
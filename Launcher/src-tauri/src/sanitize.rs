@@ -0,0 +1,267 @@
+use std::env;
+
+use serde::Serialize;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+const NAMED_COLORS: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+#[derive(Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StyleSpan {
+    start: usize,
+    end: usize,
+    color: Option<String>,
+    bold: bool,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogLine {
+    pub(crate) text: String,
+    pub(crate) spans: Vec<StyleSpan>,
+}
+
+#[derive(Clone, Default, PartialEq)]
+struct Style {
+    color: Option<String>,
+    bold: bool,
+}
+
+#[derive(Default)]
+struct LineBuilder {
+    text: String,
+    width: usize,
+    spans: Vec<StyleSpan>,
+}
+
+impl LineBuilder {
+    fn clear(&mut self) {
+        self.text.clear();
+        self.width = 0;
+        self.spans.clear();
+    }
+
+    fn push(&mut self, ch: char, style: &Style) {
+        let start = self.width;
+        self.text.push(ch);
+        self.width += ch.len_utf16();
+        if style.color.is_none() && !style.bold {
+            return;
+        }
+        if let Some(last) = self.spans.last_mut() {
+            if last.end == start && last.color == style.color && last.bold == style.bold {
+                last.end = self.width;
+                return;
+            }
+        }
+        self.spans.push(StyleSpan {
+            start,
+            end: self.width,
+            color: style.color.clone(),
+            bold: style.bold,
+        });
+    }
+
+    fn backspace(&mut self) {
+        let Some(ch) = self.text.pop() else {
+            return;
+        };
+        self.width -= ch.len_utf16();
+        if let Some(last) = self.spans.last_mut() {
+            last.end = last.end.min(self.width);
+            if last.start >= last.end {
+                self.spans.pop();
+            }
+        }
+    }
+}
+
+fn palette(index: u32) -> Option<String> {
+    match index {
+        0..=7 => Some(NAMED_COLORS[index as usize].to_string()),
+        8..=15 => Some(bright(NAMED_COLORS[index as usize - 8])),
+        16..=231 => {
+            let cube = index - 16;
+            let level = |value: u32| if value == 0 { 0 } else { value * 40 + 55 };
+            Some(format!(
+                "#{:02x}{:02x}{:02x}",
+                level(cube / 36),
+                level(cube / 6 % 6),
+                level(cube % 6)
+            ))
+        }
+        232..=255 => {
+            let gray = (index - 232) * 10 + 8;
+            Some(format!("#{gray:02x}{gray:02x}{gray:02x}"))
+        }
+        _ => None,
+    }
+}
+
+fn bright(name: &str) -> String {
+    let mut chars = name.chars();
+    let first = chars.next().map(|c| c.to_ascii_uppercase());
+    format!("bright{}{}", first.unwrap_or_default(), chars.as_str())
+}
+
+fn apply_sgr(params: &str, style: &mut Style) {
+    let codes: Vec<u32> = if params.is_empty() {
+        vec![0]
+    } else {
+        params
+            .split([';', ':'])
+            .map(|code| code.parse().unwrap_or(0))
+            .collect()
+    };
+    let mut index = 0;
+    while index < codes.len() {
+        match codes[index] {
+            0 => *style = Style::default(),
+            1 => style.bold = true,
+            22 => style.bold = false,
+            code @ 30..=37 => style.color = Some(NAMED_COLORS[code as usize - 30].to_string()),
+            code @ 90..=97 => style.color = Some(bright(NAMED_COLORS[code as usize - 90])),
+            39 => style.color = None,
+            code @ (38 | 48) => {
+                let color = match codes.get(index + 1) {
+                    Some(5) => {
+                        index += 2;
+                        codes.get(index).and_then(|value| palette(*value))
+                    }
+                    Some(2) => {
+                        index += 4;
+                        match codes.get(index - 2..=index) {
+                            Some([r, g, b]) => Some(format!(
+                                "#{:02x}{:02x}{:02x}",
+                                r.min(&255),
+                                g.min(&255),
+                                b.min(&255)
+                            )),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
+                if code == 38 {
+                    style.color = color;
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+}
+
+fn parse_line(raw: &str, style: &mut Style, out: &mut LineBuilder) {
+    let raw = raw.strip_suffix('\r').unwrap_or(raw);
+    let mut chars = raw.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\x1b' => match chars.next() {
+                Some('[') => {
+                    let mut params = String::new();
+                    let mut terminator = None;
+                    for next in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&next) {
+                            terminator = Some(next);
+                            break;
+                        }
+                        params.push(next);
+                    }
+                    match terminator {
+                        Some('m') => apply_sgr(&params, style),
+                        Some('G') if params.parse::<u32>().unwrap_or(1) <= 1 => out.clear(),
+                        _ => {}
+                    }
+                }
+                Some(']') => {
+                    while let Some(next) = chars.next() {
+                        if next == '\x07' {
+                            break;
+                        }
+                        if next == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\r' => out.clear(),
+            '\x08' => out.backspace(),
+            '\t' => out.push(ch, style),
+            ch if ch.is_control() => {}
+            ch => out.push(ch, style),
+        }
+    }
+}
+
+pub(crate) fn parse(text: &str) -> LogLine {
+    let mut style = Style::default();
+    let mut result = LogLine {
+        text: String::new(),
+        spans: Vec::new(),
+    };
+    let mut offset = 0;
+    let mut first = true;
+    for raw in text.split('\n') {
+        let mut line = LineBuilder::default();
+        parse_line(raw, &mut style, &mut line);
+        if line.text.trim().is_empty() && !raw.trim().is_empty() {
+            continue;
+        }
+        if !first {
+            result.text.push('\n');
+            offset += 1;
+        }
+        first = false;
+        result
+            .spans
+            .extend(line.spans.into_iter().map(|span| StyleSpan {
+                start: span.start + offset,
+                end: span.end + offset,
+                ..span
+            }));
+        result.text.push_str(&line.text);
+        offset += line.width;
+    }
+    result
+}
+
+pub(crate) fn colors_enabled() -> bool {
+    env::var("LOG_COLORS")
+        .map(|value| {
+            !matches!(
+                value.trim().to_ascii_lowercase().as_str(),
+                "false" | "0" | "no"
+            )
+        })
+        .unwrap_or(true)
+}
+
+pub(crate) struct LossyLines<R> {
+    reader: BufReader<R>,
+    buf: Vec<u8>,
+}
+
+impl<R: AsyncRead + Unpin> LossyLines<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            buf: Vec::new(),
+        }
+    }
+
+    pub(crate) async fn next_line(&mut self) -> Option<String> {
+        self.buf.clear();
+        match self.reader.read_until(b'\n', &mut self.buf).await {
+            Ok(0) | Err(_) => None,
+            Ok(_) => {
+                let line = String::from_utf8_lossy(&self.buf);
+                Some(line.strip_suffix('\n').unwrap_or(&line).to_string())
+            }
+        }
+    }
+}
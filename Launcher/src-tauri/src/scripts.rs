@@ -0,0 +1,187 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    process::Stdio,
+    sync::{Arc, Mutex},
+};
+
+use serde::Serialize;
+use tauri::AppHandle;
+use tokio::{fs as tokio_fs, io::AsyncRead};
+
+use crate::{
+    error::LauncherError,
+    head_commit,
+    journal::{self, JournalAction, JournalEntry},
+    load_env, log_line,
+    operation::{self, Operation},
+    packages::PackageManager,
+    record_update,
+    sanitize::LossyLines,
+    silly_dir, trace,
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct VendorScript {
+    name: String,
+    command: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ScriptResult {
+    success: bool,
+    exit_code: Option<i32>,
+    message: String,
+    log_path: String,
+}
+
+async fn read_scripts(dir: &Path) -> Result<BTreeMap<String, String>, String> {
+    let path = dir.join("package.json");
+    let raw = tokio_fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let manifest: serde_json::Value = serde_json::from_str(&raw)
+        .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+    Ok(manifest
+        .get("scripts")
+        .and_then(|scripts| scripts.as_object())
+        .map(|scripts| {
+            scripts
+                .iter()
+                .filter_map(|(name, command)| Some((name.clone(), command.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+async fn capture(
+    app: AppHandle,
+    name: String,
+    reader: impl AsyncRead + Unpin,
+    output: Arc<Mutex<String>>,
+) {
+    let mut lines = LossyLines::new(reader);
+    while let Some(line) = lines.next_line().await {
+        {
+            let mut output = output.lock().unwrap();
+            output.push_str(&line);
+            output.push('\n');
+        }
+        log_line(&app, &format!("[{name}] {line}")).await;
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn list_vendor_scripts() -> Result<Vec<VendorScript>, LauncherError> {
+    load_env();
+    let silly = silly_dir()?;
+    Ok(read_scripts(&silly)
+        .await?
+        .into_iter()
+        .map(|(name, command)| VendorScript { name, command })
+        .collect())
+}
+
+#[tauri::command]
+pub(crate) async fn run_vendor_script(
+    app: AppHandle,
+    name: String,
+    args: Option<Vec<String>>,
+) -> Result<ScriptResult, LauncherError> {
+    let _operation = operation::begin(&app, Operation::VendorScript)?;
+    load_env();
+    let silly = silly_dir()?;
+    let scripts = read_scripts(&silly).await?;
+    let Some(command) = scripts.get(&name) else {
+        let available = scripts.keys().cloned().collect::<Vec<_>>().join(", ");
+        return Err(if available.is_empty() {
+            format!("package.json does not define any scripts, so '{name}' cannot be run.")
+        } else {
+            format!("package.json has no script named '{name}'. Available scripts: {available}.")
+        }
+        .into());
+    };
+
+    let manager = PackageManager::locate(&app, &silly).await?;
+    let tool = manager.kind().name();
+    let args = args.unwrap_or_default();
+    let mut entry = JournalEntry::new(JournalAction::VendorScript);
+    entry.from_commit = head_commit(&silly).await;
+    let log_path = journal::detail_path(&entry.id).await?;
+    log_line(&app, &format!("Running {tool} run {name} ({command})...")).await;
+
+    let mut cmd = manager.command();
+    cmd.current_dir(&silly).arg("run").arg(&name);
+    if !args.is_empty() {
+        cmd.arg("--").args(&args);
+    }
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    trace::spawning(&cmd);
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start {tool} run {name}: {e}"))?;
+
+    let output = Arc::new(Mutex::new(String::new()));
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(tauri::async_runtime::spawn(capture(
+            app.clone(),
+            name.clone(),
+            stdout,
+            output.clone(),
+        )));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(tauri::async_runtime::spawn(capture(
+            app.clone(),
+            name.clone(),
+            stderr,
+            output.clone(),
+        )));
+    }
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| format!("{tool} run {name} failed: {e}"))?;
+    for reader in readers {
+        let _ = reader.await;
+    }
+
+    let message = if status.success() {
+        format!("Script '{name}' finished successfully.")
+    } else {
+        format!("Script '{name}' exited with {status}.")
+    };
+    log_line(&app, &message).await;
+
+    let body = format!(
+        "$ {tool} run {name} {}\n> {command}\n\n{}",
+        args.join(" "),
+        output.lock().unwrap()
+    );
+    tokio_fs::write(&log_path, body)
+        .await
+        .map_err(|e| format!("Failed to write {}: {e}", log_path.display()))?;
+    entry.to_commit = head_commit(&silly).await;
+    entry.result = if status.success() {
+        "success"
+    } else {
+        "failed"
+    }
+    .into();
+    entry.message = message.clone();
+    entry.detail_log = Some(log_path.to_string_lossy().into_owned());
+    record_update(&app, &entry).await;
+
+    Ok(ScriptResult {
+        success: status.success(),
+        exit_code: status.code(),
+        message,
+        log_path: log_path.to_string_lossy().into_owned(),
+    })
+}
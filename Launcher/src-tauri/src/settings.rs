@@ -0,0 +1,260 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+use tokio::fs as tokio_fs;
+
+use crate::{error::LauncherError, log_line, state, userdata};
+
+const SNAPSHOTS_KEY: &str = "settingsSnapshots";
+const SNAPSHOTS_DIR: &str = "settings-snapshots";
+const DEFAULT_SNAPSHOT_LIMIT: usize = 20;
+const SNAPSHOT_FILES: &[&str] = &["config.yaml", "data/default-user/settings.json"];
+const SENSITIVE_KEYS: &[&str] = &["password", "secret", "token"];
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotEntry {
+    id: String,
+    taken_at: String,
+    files: Vec<String>,
+    digest: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SnapshotInfo {
+    id: String,
+    taken_at: String,
+    files: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SettingChange {
+    file: String,
+    path: String,
+    before: Option<Value>,
+    after: Option<Value>,
+}
+
+fn snapshot_limit() -> usize {
+    env::var("SETTINGS_SNAPSHOT_LIMIT")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|limit| *limit > 0)
+        .unwrap_or(DEFAULT_SNAPSHOT_LIMIT)
+}
+
+fn snapshots_dir() -> PathBuf {
+    state::state_dir().join(SNAPSHOTS_DIR)
+}
+
+fn snapshot_path(id: &str) -> PathBuf {
+    snapshots_dir().join(format!("{id}.json"))
+}
+
+fn digest(files: &BTreeMap<String, Value>) -> String {
+    let json = serde_json::to_string(files).unwrap_or_default();
+    Sha256::digest(json.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn unique_id(entries: &[SnapshotEntry], base: String) -> String {
+    let taken = |id: &str| entries.iter().any(|entry| entry.id == id);
+    if !taken(&base) {
+        return base;
+    }
+    (2..)
+        .map(|n| format!("{base}-{n}"))
+        .find(|id| !taken(id))
+        .unwrap_or(base)
+}
+
+async fn read_snapshot(id: &str) -> Result<BTreeMap<String, Value>, String> {
+    let raw = tokio_fs::read_to_string(snapshot_path(id))
+        .await
+        .map_err(|e| format!("Failed to read settings snapshot {id}: {e}"))?;
+    serde_json::from_str(&raw).map_err(|e| format!("Settings snapshot {id} is unreadable: {e}"))
+}
+
+fn redact(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let lower = key.to_ascii_lowercase();
+                    if SENSITIVE_KEYS.iter().any(|needle| lower.contains(needle))
+                        && !value.is_object()
+                    {
+                        let digest = Sha256::digest(value.to_string().as_bytes());
+                        let short: String = digest
+                            .iter()
+                            .take(4)
+                            .map(|byte| format!("{byte:02x}"))
+                            .collect();
+                        (key, Value::String(format!("[redacted:{short}]")))
+                    } else {
+                        (key, redact(value))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact).collect()),
+        other => other,
+    }
+}
+
+fn parse_settings(name: &str, raw: &str) -> Value {
+    let parsed = if name.ends_with(".yaml") || name.ends_with(".yml") {
+        serde_yaml::from_str::<Value>(raw).ok()
+    } else {
+        serde_json::from_str::<Value>(raw).ok()
+    };
+    parsed
+        .map(redact)
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&path, child, out);
+            }
+        }
+        _ => {
+            out.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+pub(crate) async fn snapshot(app: &AppHandle, silly_dir: &Path) {
+    let mut files = BTreeMap::new();
+    for name in SNAPSHOT_FILES {
+        if let Ok(raw) = tokio_fs::read_to_string(userdata::resolve(silly_dir, name)).await {
+            files.insert((*name).to_string(), parse_settings(name, &raw));
+        }
+    }
+    if files.is_empty() {
+        return;
+    }
+
+    let mut entries: Vec<SnapshotEntry> = state::load(SNAPSHOTS_KEY);
+    let digest = digest(&files);
+    if entries
+        .last()
+        .map(|last| last.digest == digest)
+        .unwrap_or(false)
+    {
+        return;
+    }
+    let now = Local::now();
+    let id = unique_id(&entries, now.format("%Y%m%d-%H%M%S-%3f").to_string());
+    let written = match serde_json::to_string_pretty(&files) {
+        Ok(json) => match tokio_fs::create_dir_all(snapshots_dir()).await {
+            Ok(()) => tokio_fs::write(snapshot_path(&id), json)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(err) => Err(err.to_string()),
+        },
+        Err(err) => Err(err.to_string()),
+    };
+    if let Err(err) = written {
+        log_line(app, &format!("Failed to write settings snapshot: {err}")).await;
+        return;
+    }
+    entries.push(SnapshotEntry {
+        id,
+        taken_at: now.to_rfc3339(),
+        files: files.into_keys().collect(),
+        digest,
+    });
+    let limit = snapshot_limit();
+    if entries.len() > limit {
+        for dropped in entries.drain(..entries.len() - limit) {
+            let _ = tokio_fs::remove_file(snapshot_path(&dropped.id)).await;
+        }
+    }
+    if let Err(err) = state::save(SNAPSHOTS_KEY, &entries) {
+        log_line(app, &format!("Failed to store settings snapshot: {err}")).await;
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn list_settings_snapshots() -> Result<Vec<SnapshotInfo>, LauncherError> {
+    let entries: Vec<SnapshotEntry> = state::load(SNAPSHOTS_KEY);
+    Ok(entries
+        .into_iter()
+        .rev()
+        .map(|entry| SnapshotInfo {
+            id: entry.id,
+            taken_at: entry.taken_at,
+            files: entry.files,
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub(crate) async fn diff_settings(
+    snapshot_a: String,
+    snapshot_b: String,
+) -> Result<Vec<SettingChange>, LauncherError> {
+    let entries: Vec<SnapshotEntry> = state::load(SNAPSHOTS_KEY);
+    let find = |id: &str| {
+        entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| entry.id.clone())
+            .ok_or_else(|| format!("No settings snapshot with id {id}."))
+    };
+    let before = read_snapshot(&find(&snapshot_a)?).await?;
+    let after = read_snapshot(&find(&snapshot_b)?).await?;
+
+    let mut changes = Vec::new();
+    let names: Vec<&String> = before
+        .keys()
+        .chain(after.keys().filter(|name| !before.contains_key(*name)))
+        .collect();
+    for name in names {
+        let mut old = BTreeMap::new();
+        let mut new = BTreeMap::new();
+        if let Some(value) = before.get(name) {
+            flatten("", value, &mut old);
+        }
+        if let Some(value) = after.get(name) {
+            flatten("", value, &mut new);
+        }
+        let paths: Vec<&String> = old
+            .keys()
+            .chain(new.keys().filter(|path| !old.contains_key(*path)))
+            .collect();
+        for path in paths {
+            let (was, now) = (old.get(path), new.get(path));
+            if was != now {
+                changes.push(SettingChange {
+                    file: name.clone(),
+                    path: path.clone(),
+                    before: was.cloned(),
+                    after: now.cloned(),
+                });
+            }
+        }
+    }
+    changes.sort_by(|a, b| (&a.file, &a.path).cmp(&(&b.file, &b.path)));
+    Ok(changes)
+}
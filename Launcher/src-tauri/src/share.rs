@@ -0,0 +1,443 @@
+use std::{
+    convert::Infallible,
+    env,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+};
+
+use chrono::Utc;
+use hyper::{
+    client::HttpConnector,
+    header::{self, HeaderValue},
+    server::conn::AddrStream,
+    service::{make_service_fn, service_fn},
+    Body, Client, Request, Response, Server, StatusCode, Uri,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+use crate::{certs, error::LauncherError, load_env, log_line, playtime, state};
+
+const COOKIE_NAME: &str = "wt_guest";
+const TOKEN_PATH_PREFIX: &str = "/_guest/";
+const TOKENS_KEY: &str = "guestTokens";
+const MAX_TTL_MINUTES: u32 = 7 * 24 * 60;
+
+static TUNNEL_URL: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct GuestToken {
+    id: String,
+    #[serde(default)]
+    token_hash: String,
+    #[serde(default, skip_serializing)]
+    token: Option<String>,
+    label: String,
+    created_at: i64,
+    expires_at: i64,
+}
+
+impl GuestToken {
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires_at <= now
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GuestTokenInfo {
+    id: String,
+    label: String,
+    created_at: i64,
+    expires_at: i64,
+    expired: bool,
+    path: Option<String>,
+    url: Option<String>,
+}
+
+impl From<&GuestToken> for GuestTokenInfo {
+    fn from(token: &GuestToken) -> Self {
+        Self {
+            id: token.id.clone(),
+            label: token.label.clone(),
+            created_at: token.created_at,
+            expires_at: token.expires_at,
+            expired: token.is_expired(Utc::now().timestamp()),
+            path: None,
+            url: None,
+        }
+    }
+}
+
+impl GuestTokenInfo {
+    fn with_secret(mut self, secret: &str) -> Self {
+        let path = format!("{TOKEN_PATH_PREFIX}{secret}/");
+        self.url = public_base_url().map(|base| format!("{base}{path}"));
+        self.path = Some(path);
+        self
+    }
+}
+
+pub(crate) fn token_digest(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+pub(crate) fn token_matches(candidate: &str, digest: &str) -> bool {
+    token_digest(candidate)
+        .as_bytes()
+        .ct_eq(digest.as_bytes())
+        .into()
+}
+
+pub(crate) struct GuestTokens(Arc<Mutex<Vec<GuestToken>>>);
+
+impl GuestTokens {
+    pub(crate) fn load() -> Self {
+        let now = Utc::now().timestamp();
+        let mut tokens: Vec<GuestToken> = state::load(TOKENS_KEY);
+        tokens.retain(|token| !token.is_expired(now));
+        let mut migrated = false;
+        for token in &mut tokens {
+            if let Some(secret) = token.token.take() {
+                token.token_hash = token_digest(&secret);
+                migrated = true;
+            }
+        }
+        tokens.retain(|token| !token.token_hash.is_empty());
+        if migrated {
+            let _ = Self::persist(&tokens);
+        }
+        Self(Arc::new(Mutex::new(tokens)))
+    }
+
+    fn persist(tokens: &[GuestToken]) -> Result<(), String> {
+        state::save(TOKENS_KEY, &tokens)
+    }
+}
+
+struct ProxyContext {
+    tokens: Arc<Mutex<Vec<GuestToken>>>,
+    upstream: String,
+    client: Client<HttpConnector>,
+}
+
+fn share_port() -> Option<u16> {
+    env::var("SHARE_PORT")
+        .ok()
+        .and_then(|value| value.trim().parse::<u16>().ok())
+        .filter(|port| *port != 0)
+}
+
+fn share_host() -> IpAddr {
+    env::var("SHARE_HOST")
+        .ok()
+        .and_then(|value| value.trim().parse::<IpAddr>().ok())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+}
+
+fn public_base_url() -> Option<String> {
+    if let Some(url) = env::var("SHARE_PUBLIC_URL")
+        .ok()
+        .map(|value| value.trim().trim_end_matches('/').to_string())
+        .filter(|value| !value.is_empty())
+    {
+        return Some(url);
+    }
+    if let Some(url) = TUNNEL_URL.lock().unwrap().clone() {
+        return Some(url);
+    }
+    let port = share_port()?;
+    let host = share_host();
+    if host.is_unspecified() {
+        return None;
+    }
+    Some(format!("http://{}", SocketAddr::new(host, port)))
+}
+
+pub(crate) fn set_tunnel_url(url: Option<String>) {
+    *TUNNEL_URL.lock().unwrap() = url.map(|url| url.trim_end_matches('/').to_string());
+}
+
+pub(crate) fn local_address() -> Option<SocketAddr> {
+    let port = share_port()?;
+    let host = share_host();
+    let host = if host.is_unspecified() {
+        IpAddr::V4(Ipv4Addr::LOCALHOST)
+    } else {
+        host
+    };
+    Some(SocketAddr::new(host, port))
+}
+
+fn random_hex(bytes: usize) -> String {
+    (0..bytes)
+        .map(|_| format!("{:02x}", rand::random::<u8>()))
+        .collect()
+}
+
+fn remaining_seconds(tokens: &Mutex<Vec<GuestToken>>, candidate: &str) -> Option<i64> {
+    let now = Utc::now().timestamp();
+    tokens
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|token| token_matches(candidate, &token.token_hash) && !token.is_expired(now))
+        .map(|token| token.expires_at - now)
+}
+
+fn cookie_token(req: &Request<Body>) -> Option<String> {
+    req.headers()
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().strip_prefix(COOKIE_NAME)?.strip_prefix('='))
+        .map(str::to_string)
+        .next()
+}
+
+fn strip_guest_cookie(req: &mut Request<Body>) {
+    let remaining: Vec<String> = req
+        .headers()
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty() && !pair.starts_with(&format!("{COOKIE_NAME}=")))
+        .map(str::to_string)
+        .collect();
+    req.headers_mut().remove(header::COOKIE);
+    if !remaining.is_empty() {
+        if let Ok(value) = HeaderValue::from_str(&remaining.join("; ")) {
+            req.headers_mut().insert(header::COOKIE, value);
+        }
+    }
+}
+
+fn text_response(status: StatusCode, body: &'static str) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response
+}
+
+async fn handle(
+    mut req: Request<Body>,
+    ctx: Arc<ProxyContext>,
+    remote: SocketAddr,
+) -> Result<Response<Body>, Infallible> {
+    if let Some(token) = req.uri().path().strip_prefix(certs::CHALLENGE_PATH_PREFIX) {
+        if let Some(response) = certs::http_challenge_response(token) {
+            return Ok(Response::new(Body::from(response)));
+        }
+    }
+
+    if let Some(rest) = req.uri().path().strip_prefix(TOKEN_PATH_PREFIX) {
+        let (candidate, remainder) = rest.split_once('/').unwrap_or((rest, ""));
+        let Some(remaining) = remaining_seconds(&ctx.tokens, candidate) else {
+            return Ok(text_response(
+                StatusCode::FORBIDDEN,
+                "This guest link is invalid or has expired.",
+            ));
+        };
+        let mut location = format!("/{remainder}");
+        if let Some(query) = req.uri().query() {
+            location.push('?');
+            location.push_str(query);
+        }
+        let cookie = format!(
+            "{COOKIE_NAME}={candidate}; Path=/; HttpOnly; SameSite=Lax; Max-Age={remaining}"
+        );
+        let mut response = text_response(StatusCode::FOUND, "");
+        if let (Ok(location), Ok(cookie)) = (
+            HeaderValue::from_str(&location),
+            HeaderValue::from_str(&cookie),
+        ) {
+            response.headers_mut().insert(header::LOCATION, location);
+            response.headers_mut().insert(header::SET_COOKIE, cookie);
+        }
+        return Ok(response);
+    }
+
+    let authorised = cookie_token(&req)
+        .map(|candidate| remaining_seconds(&ctx.tokens, &candidate).is_some())
+        .unwrap_or(false);
+    if !authorised {
+        return Ok(text_response(
+            StatusCode::FORBIDDEN,
+            "Guest access token missing or expired. Ask the host for a new link.",
+        ));
+    }
+    playtime::touch();
+
+    strip_guest_cookie(&mut req);
+    let path = req
+        .uri()
+        .path_and_query()
+        .map(|value| value.as_str())
+        .unwrap_or("/");
+    let Ok(uri) = format!("http://{}{}", ctx.upstream, path).parse::<Uri>() else {
+        return Ok(text_response(
+            StatusCode::BAD_REQUEST,
+            "Invalid request path.",
+        ));
+    };
+    *req.uri_mut() = uri;
+    if let Ok(host) = HeaderValue::from_str(&ctx.upstream) {
+        req.headers_mut().insert(header::HOST, host);
+    }
+    if let Ok(forwarded) = HeaderValue::from_str(&remote.ip().to_string()) {
+        req.headers_mut().insert("x-forwarded-for", forwarded);
+    }
+
+    Ok(ctx.client.request(req).await.unwrap_or_else(|_| {
+        text_response(
+            StatusCode::BAD_GATEWAY,
+            "WeylandTavern is not reachable right now.",
+        )
+    }))
+}
+
+pub(crate) async fn start_proxy(
+    app: &AppHandle,
+    upstream_host: &str,
+    upstream_port: u16,
+) -> Result<Option<oneshot::Sender<()>>, String> {
+    let Some(port) = share_port() else {
+        return Ok(None);
+    };
+    let upstream_ip = upstream_host
+        .parse::<IpAddr>()
+        .ok()
+        .filter(|ip| !ip.is_unspecified())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    let ctx = Arc::new(ProxyContext {
+        tokens: app.state::<GuestTokens>().0.clone(),
+        upstream: SocketAddr::new(upstream_ip, upstream_port).to_string(),
+        client: Client::new(),
+    });
+
+    let make_service = make_service_fn(move |conn: &AddrStream| {
+        let ctx = ctx.clone();
+        let remote = conn.remote_addr();
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle(req, ctx.clone(), remote))) }
+    });
+
+    let addr = SocketAddr::new(share_host(), port);
+    let server = Server::try_bind(&addr)
+        .map_err(|e| format!("Unable to bind guest proxy on {addr}: {e}"))?
+        .serve(make_service);
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let graceful = server.with_graceful_shutdown(async {
+        let _ = shutdown_rx.await;
+    });
+
+    let app_for_errors = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) = graceful.await {
+            log_line(&app_for_errors, &format!("Guest proxy stopped: {err}")).await;
+        }
+    });
+
+    log_line(app, &format!("Guest sharing proxy listening on {addr}.")).await;
+    Ok(Some(shutdown_tx))
+}
+
+#[tauri::command]
+pub(crate) async fn create_guest_token(
+    app: AppHandle,
+    tokens: tauri::State<'_, GuestTokens>,
+    label: Option<String>,
+    ttl_minutes: u32,
+) -> Result<GuestTokenInfo, LauncherError> {
+    load_env();
+    if ttl_minutes == 0 {
+        return Err("Guest tokens need a lifetime of at least one minute.".into());
+    }
+    let now = Utc::now().timestamp();
+    let secret = random_hex(16);
+    let token = GuestToken {
+        id: random_hex(4),
+        token_hash: token_digest(&secret),
+        token: None,
+        label: label
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| "Guest".into()),
+        created_at: now,
+        expires_at: now + i64::from(ttl_minutes.min(MAX_TTL_MINUTES)) * 60,
+    };
+
+    let info = GuestTokenInfo::from(&token).with_secret(&secret);
+    {
+        let mut guard = tokens.0.lock().unwrap();
+        guard.retain(|existing| !existing.is_expired(now));
+        guard.push(token);
+        GuestTokens::persist(&guard)?;
+    }
+
+    if share_port().is_none() {
+        log_line(
+            &app,
+            "Guest token created, but SHARE_PORT is not set so the guest proxy is disabled.",
+        )
+        .await;
+    }
+    log_line(
+        &app,
+        &format!("Created guest token {} ({}).", info.id, info.label),
+    )
+    .await;
+    Ok(info)
+}
+
+#[tauri::command]
+pub(crate) async fn list_guest_tokens(
+    tokens: tauri::State<'_, GuestTokens>,
+) -> Result<Vec<GuestTokenInfo>, LauncherError> {
+    load_env();
+    let guard = tokens.0.lock().unwrap();
+    Ok(guard.iter().map(GuestTokenInfo::from).collect())
+}
+
+#[tauri::command]
+pub(crate) async fn revoke_guest_token(
+    app: AppHandle,
+    tokens: tauri::State<'_, GuestTokens>,
+    id: String,
+) -> Result<(), LauncherError> {
+    {
+        let mut guard = tokens.0.lock().unwrap();
+        let before = guard.len();
+        guard.retain(|token| token.id != id);
+        if guard.len() == before {
+            return Err(format!("No guest token with id {id}.").into());
+        }
+        GuestTokens::persist(&guard)?;
+    }
+    log_line(&app, &format!("Revoked guest token {id}.")).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_matches_only_the_original_token() {
+        let digest = token_digest("guest-secret");
+        assert_eq!(digest.len(), 64);
+        assert_ne!(digest, "guest-secret");
+        assert!(token_matches("guest-secret", &digest));
+        assert!(!token_matches("guest-secreT", &digest));
+        assert!(!token_matches("", &digest));
+        assert!(!token_matches("guest-secret", ""));
+    }
+}
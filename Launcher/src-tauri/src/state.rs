@@ -0,0 +1,272 @@
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+};
+
+use chrono::Utc;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+
+use crate::error::LauncherError;
+use crate::events;
+
+const STATE_FILE: &str = "launcher-state.json";
+const REVISIONS_KEY: &str = "_revisions";
+const UI_PREFIX: &str = "ui.";
+const UI_KEYS: &[&str] = &["uiState", "notifications"];
+
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ChangeOrigin {
+    Launcher,
+    Scheduler,
+    Ui,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SettingsChanged {
+    key: String,
+    revision: u64,
+    origin: ChangeOrigin,
+    changed_at: i64,
+}
+
+pub(crate) struct Versioned<T> {
+    pub(crate) value: T,
+    pub(crate) revision: u64,
+}
+
+pub(crate) enum Swap {
+    Written(u64),
+    Conflict(Versioned<Value>),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SettingValue {
+    key: String,
+    value: Value,
+    revision: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SettingWrite {
+    key: String,
+    written: bool,
+    revision: u64,
+    value: Value,
+}
+
+pub(crate) fn state_dir() -> PathBuf {
+    PathBuf::from("state")
+}
+
+pub(crate) fn attach(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+fn read_all() -> Map<String, Value> {
+    let path = state_dir().join(STATE_FILE);
+    let Ok(raw) = fs::read_to_string(&path) else {
+        return Map::new();
+    };
+    match serde_json::from_str(&raw) {
+        Ok(all) => all,
+        Err(_) => {
+            let stamp = Utc::now().format("%Y%m%d-%H%M%S");
+            let _ = fs::rename(
+                &path,
+                path.with_file_name(format!("{STATE_FILE}.corrupt-{stamp}")),
+            );
+            Map::new()
+        }
+    }
+}
+
+fn write_all(all: &Map<String, Value>) -> Result<(), String> {
+    let dir = state_dir();
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(all).map_err(|e| e.to_string())?;
+    let temp = dir.join(format!("{STATE_FILE}.tmp"));
+    fs::write(&temp, json).map_err(|e| e.to_string())?;
+    fs::rename(&temp, dir.join(STATE_FILE)).map_err(|e| e.to_string())
+}
+
+fn revision(all: &Map<String, Value>, key: &str) -> u64 {
+    all.get(REVISIONS_KEY)
+        .and_then(|revisions| revisions.get(key))
+        .and_then(Value::as_u64)
+        .unwrap_or(0)
+}
+
+fn commit(all: &mut Map<String, Value>, key: &str, value: Value) -> Result<u64, String> {
+    let next = revision(all, key) + 1;
+    all.insert(key.to_string(), value);
+    let revisions = all
+        .entry(REVISIONS_KEY)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !revisions.is_object() {
+        *revisions = Value::Object(Map::new());
+    }
+    if let Some(revisions) = revisions.as_object_mut() {
+        revisions.insert(key.to_string(), Value::from(next));
+    }
+    write_all(all)?;
+    Ok(next)
+}
+
+fn announce(key: &str, revision: u64, origin: ChangeOrigin) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    let change = SettingsChanged {
+        key: key.to_string(),
+        revision,
+        origin,
+        changed_at: Utc::now().timestamp(),
+    };
+    events::emit(app, "settings-changed", &change);
+}
+
+pub(crate) fn snapshot_of(keys: &[&str]) -> Map<String, Value> {
+    let _guard = STORE_LOCK.lock().unwrap();
+    let mut all = read_all();
+    keys.iter()
+        .filter_map(|key| all.remove(*key).map(|value| (key.to_string(), value)))
+        .collect()
+}
+
+pub(crate) fn load<T: DeserializeOwned + Default>(key: &str) -> T {
+    read(key).value
+}
+
+pub(crate) fn read<T: DeserializeOwned + Default>(key: &str) -> Versioned<T> {
+    let _guard = STORE_LOCK.lock().unwrap();
+    let mut all = read_all();
+    let revision = revision(&all, key);
+    let value = all
+        .remove(key)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    Versioned { value, revision }
+}
+
+pub(crate) fn save<T: Serialize>(key: &str, value: &T) -> Result<(), String> {
+    save_from(key, value, ChangeOrigin::Launcher).map(|_| ())
+}
+
+pub(crate) fn save_from<T: Serialize>(
+    key: &str,
+    value: &T,
+    origin: ChangeOrigin,
+) -> Result<u64, String> {
+    let value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+    let revision = {
+        let _guard = STORE_LOCK.lock().unwrap();
+        let mut all = read_all();
+        commit(&mut all, key, value)?
+    };
+    announce(key, revision, origin);
+    Ok(revision)
+}
+
+pub(crate) fn compare_and_swap<T: Serialize>(
+    key: &str,
+    expected: u64,
+    value: &T,
+    origin: ChangeOrigin,
+) -> Result<Swap, String> {
+    let value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+    let revision = {
+        let _guard = STORE_LOCK.lock().unwrap();
+        let mut all = read_all();
+        let current = revision(&all, key);
+        if current != expected {
+            return Ok(Swap::Conflict(Versioned {
+                value: all.remove(key).unwrap_or(Value::Null),
+                revision: current,
+            }));
+        }
+        commit(&mut all, key, value)?
+    };
+    announce(key, revision, origin);
+    Ok(Swap::Written(revision))
+}
+
+pub(crate) fn update<T, R>(
+    key: &str,
+    origin: ChangeOrigin,
+    apply: impl FnOnce(&mut T) -> R,
+) -> Result<(R, u64), String>
+where
+    T: DeserializeOwned + Serialize + Default,
+{
+    let (result, revision) = {
+        let _guard = STORE_LOCK.lock().unwrap();
+        let mut all = read_all();
+        let mut value: T = all
+            .get(key)
+            .cloned()
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default();
+        let result = apply(&mut value);
+        let value = serde_json::to_value(&value).map_err(|e| e.to_string())?;
+        (result, commit(&mut all, key, value)?)
+    };
+    announce(key, revision, origin);
+    Ok((result, revision))
+}
+
+fn check_key(key: &str) -> Result<(), String> {
+    let owned_by_ui = UI_KEYS.contains(&key)
+        || key
+            .strip_prefix(UI_PREFIX)
+            .is_some_and(|rest| !rest.trim().is_empty());
+    if !owned_by_ui {
+        return Err(format!(
+            "\"{key}\" is not a settings key the UI may access. Use a \"{UI_PREFIX}\" key."
+        ));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn read_setting(key: String) -> Result<SettingValue, LauncherError> {
+    check_key(&key)?;
+    let Versioned { value, revision } = read::<Value>(&key);
+    Ok(SettingValue {
+        key,
+        value,
+        revision,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn write_setting(
+    key: String,
+    value: Value,
+    expected_revision: u64,
+) -> Result<SettingWrite, LauncherError> {
+    check_key(&key)?;
+    match compare_and_swap(&key, expected_revision, &value, ChangeOrigin::Ui)? {
+        Swap::Written(revision) => Ok(SettingWrite {
+            key,
+            written: true,
+            revision,
+            value,
+        }),
+        Swap::Conflict(current) => Ok(SettingWrite {
+            key,
+            written: false,
+            revision: current.revision,
+            value: current.value,
+        }),
+    }
+}
@@ -0,0 +1,118 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{image::Image, AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::error::LauncherError;
+use crate::instance::{self, RunningInstance};
+
+const WINDOW_PREFIX: &str = "instance-";
+const DEFAULT_WIDTH: f64 = 1200.0;
+const DEFAULT_HEIGHT: f64 = 800.0;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct InstanceWindow {
+    label: String,
+    title: String,
+    url: String,
+    reused: bool,
+}
+
+pub(crate) fn is_instance_window(label: &str) -> bool {
+    label.starts_with(WINDOW_PREFIX)
+}
+
+fn badge_color(id: &str) -> [u8; 3] {
+    let digest = Sha256::digest(id.as_bytes());
+    let [r, g, b] = [digest[0], digest[1], digest[2]];
+    [r | 0x40, g | 0x40, b | 0x40]
+}
+
+fn badged_icon(app: &AppHandle, id: &str) -> Option<Image<'static>> {
+    let icon = app.default_window_icon()?;
+    let (width, height) = (icon.width(), icon.height());
+    let mut rgba = icon.rgba().to_vec();
+    let color = badge_color(id);
+    let radius = width.min(height) as f64 * 0.22;
+    let (cx, cy) = (width as f64 - radius - 1.0, height as f64 - radius - 1.0);
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (x as f64 + 0.5 - cx, y as f64 + 0.5 - cy);
+            if dx * dx + dy * dy > radius * radius {
+                continue;
+            }
+            let offset = ((y * width + x) * 4) as usize;
+            rgba[offset..offset + 3].copy_from_slice(&color);
+            rgba[offset + 3] = 0xff;
+        }
+    }
+    Some(Image::new_owned(rgba, width, height))
+}
+
+fn window_title(profile: &RunningInstance, url: &str) -> String {
+    let address = url
+        .trim_start_matches("http://")
+        .trim_start_matches("https://")
+        .trim_end_matches('/');
+    let marker = if profile.current {
+        " (this launcher)"
+    } else {
+        ""
+    };
+    format!("WeylandTavern – {}{marker} – {address}", profile.name)
+}
+
+#[tauri::command]
+pub(crate) async fn list_instances() -> Result<Vec<RunningInstance>, LauncherError> {
+    Ok(instance::running().await)
+}
+
+#[tauri::command]
+pub(crate) async fn open_instance_window(
+    app: AppHandle,
+    profile: String,
+) -> Result<InstanceWindow, LauncherError> {
+    let running = instance::running().await;
+    let target = running
+        .iter()
+        .find(|candidate| candidate.id == profile || candidate.name == profile)
+        .ok_or_else(|| format!("No running WeylandTavern profile matches \"{profile}\"."))?;
+    let url = target
+        .url
+        .clone()
+        .ok_or_else(|| format!("{} has not finished starting its server yet.", target.name))?;
+    let label = format!("{WINDOW_PREFIX}{}", target.id);
+    let title = window_title(target, &url);
+
+    if let Some(window) = app.get_webview_window(&label) {
+        window.show().ok();
+        window.unminimize().ok();
+        window.set_focus().ok();
+        return Ok(InstanceWindow {
+            label,
+            title,
+            url,
+            reused: true,
+        });
+    }
+
+    let parsed = url
+        .parse()
+        .map_err(|e| format!("Invalid server URL {url}: {e}"))?;
+    let mut builder = WebviewWindowBuilder::new(&app, &label, WebviewUrl::External(parsed))
+        .title(&title)
+        .inner_size(DEFAULT_WIDTH, DEFAULT_HEIGHT)
+        .resizable(true);
+    if let Some(icon) = badged_icon(&app, &target.id) {
+        builder = builder.icon(icon).map_err(|e| e.to_string())?;
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to open a window for {}: {e}", target.name))?;
+    Ok(InstanceWindow {
+        label,
+        title,
+        url,
+        reused: false,
+    })
+}
@@ -0,0 +1,165 @@
+use std::{
+    env, fs as stdfs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use chrono::Local;
+use tauri::AppHandle;
+use tokio::fs as tokio_fs;
+
+use crate::{disk, instance, log_line};
+
+const DEFAULT_TEMP_DIR: &str = "temp";
+const ENTRY_PREFIX: &str = "weylandtavern-";
+
+pub(crate) struct TempSpace {
+    path: PathBuf,
+    kept: bool,
+}
+
+pub(crate) fn root() -> PathBuf {
+    env::var("TEMP_DIR")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_TEMP_DIR))
+}
+
+fn owner_pid(name: &str) -> Option<u32> {
+    name.strip_prefix(ENTRY_PREFIX)?
+        .rsplit('-')
+        .nth(2)?
+        .parse()
+        .ok()
+}
+
+impl TempSpace {
+    pub(crate) fn allocate(purpose: &str, required_bytes: u64) -> Result<Self, String> {
+        let root = root();
+        stdfs::create_dir_all(&root)
+            .map_err(|e| format!("Failed to create {}: {e}", root.display()))?;
+        if let Ok(available) = disk::available_bytes(&root) {
+            if available < required_bytes {
+                return Err(format!(
+                    "Not enough free space in {} for {purpose}: {} MB free, {} MB needed. Set TEMP_DIR to a folder on a larger drive.",
+                    root.display(),
+                    available / (1024 * 1024),
+                    required_bytes.div_ceil(1024 * 1024)
+                ));
+            }
+        }
+        let name = format!(
+            "{ENTRY_PREFIX}{purpose}-{}-{}-{:04x}",
+            process::id(),
+            Local::now().format("%Y%m%d%H%M%S"),
+            rand::random::<u16>()
+        );
+        let path = root.join(name);
+        stdfs::create_dir(&path)
+            .map_err(|e| format!("Failed to create {}: {e}", path.display()))?;
+        Ok(Self { path, kept: false })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) async fn persist(mut self, target: &Path) -> Result<(), String> {
+        if let Some(parent) = target.parent() {
+            tokio_fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        if tokio_fs::rename(&self.path, target).await.is_ok() {
+            self.kept = true;
+            return Ok(());
+        }
+        let mut pending = vec![self.path.clone()];
+        while let Some(dir) = pending.pop() {
+            let destination = target.join(dir.strip_prefix(&self.path).unwrap_or(&dir));
+            tokio_fs::create_dir_all(&destination)
+                .await
+                .map_err(|e| format!("Failed to create {}: {e}", destination.display()))?;
+            let mut entries = tokio_fs::read_dir(&dir)
+                .await
+                .map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let path = entry.path();
+                match entry.file_type().await {
+                    Ok(kind) if kind.is_dir() => pending.push(path),
+                    Ok(_) => {
+                        tokio_fs::copy(&path, destination.join(entry.file_name()))
+                            .await
+                            .map_err(|e| format!("Failed to copy {}: {e}", path.display()))?;
+                    }
+                    Err(_) => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for TempSpace {
+    fn drop(&mut self) {
+        if !self.kept {
+            let _ = stdfs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+pub(crate) async fn sweep(app: &AppHandle) {
+    let Ok(entries) = stdfs::read_dir(root()) else {
+        return;
+    };
+    let live: Vec<u32> = instance::running()
+        .await
+        .iter()
+        .map(|running| running.pid)
+        .collect();
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some(pid) = owner_pid(&name) else {
+            continue;
+        };
+        if pid == process::id() || live.contains(&pid) {
+            continue;
+        }
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if stdfs::remove_dir_all(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    if removed > 0 {
+        log_line(
+            app,
+            &format!("Removed {removed} temporary folder(s) left behind by an earlier run."),
+        )
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owner_pid_only_reads_launcher_folders() {
+        assert_eq!(
+            owner_pid("weylandtavern-backup-4242-20260101120000-0a1b"),
+            Some(4242)
+        );
+        assert_eq!(
+            owner_pid("weylandtavern-chat-import-7-20260101120000-ffff"),
+            Some(7)
+        );
+        assert_eq!(owner_pid("npm-4242-20260101120000-0a1b"), None);
+        assert_eq!(owner_pid("weylandtavern-notes.txt"), None);
+    }
+}
@@ -0,0 +1,408 @@
+use std::{
+    convert::Infallible,
+    env,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use chrono::{Datelike, Duration as ChronoDuration, Utc};
+use hyper::{
+    client::HttpConnector,
+    header::{self, HeaderValue},
+    server::conn::Http,
+    service::service_fn,
+    Body, Client, Request, Response, StatusCode, Uri,
+};
+use rcgen::{date_time_ymd, CertificateParams, DistinguishedName, DnType, KeyPair};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use tokio::{fs as tokio_fs, net::TcpListener, sync::oneshot};
+use tokio_rustls::{
+    rustls::{
+        crypto::ring,
+        pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+        ServerConfig,
+    },
+    TlsAcceptor,
+};
+use x509_parser::pem::parse_x509_pem;
+
+use crate::{
+    certs,
+    error::LauncherError,
+    load_env, log_line, playtime,
+    state::{self, ChangeOrigin},
+    ServerState,
+};
+
+const ENABLED_KEY: &str = "tlsProxyEnabled";
+const DEFAULT_PORT: u16 = 8443;
+const SELF_SIGNED_DAYS: i64 = 825;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TlsProxyStatus {
+    enabled: bool,
+    running: bool,
+    url: String,
+    cert_path: String,
+    self_signed: bool,
+    fingerprint: Option<String>,
+    not_after: Option<String>,
+    message: Option<String>,
+}
+
+struct ProxyContext {
+    upstream: String,
+    client: Client<HttpConnector>,
+}
+
+struct CertFiles {
+    cert: PathBuf,
+    key: PathBuf,
+    self_signed: bool,
+}
+
+fn proxy_port() -> u16 {
+    env::var("TLS_PROXY_PORT")
+        .ok()
+        .and_then(|value| value.trim().parse::<u16>().ok())
+        .filter(|port| *port != 0)
+        .unwrap_or(DEFAULT_PORT)
+}
+
+fn proxy_host() -> IpAddr {
+    env::var("TLS_PROXY_HOST")
+        .ok()
+        .and_then(|value| value.trim().parse::<IpAddr>().ok())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+fn proxy_url() -> String {
+    let host = proxy_host();
+    let host = if host.is_unspecified() {
+        IpAddr::V4(Ipv4Addr::LOCALHOST)
+    } else {
+        host
+    };
+    format!("https://{}/", SocketAddr::new(host, proxy_port()))
+}
+
+fn enabled() -> bool {
+    state::load::<Option<bool>>(ENABLED_KEY).unwrap_or(false)
+}
+
+fn cert_files() -> CertFiles {
+    let configured = |key: &str| {
+        env::var(key)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+    match (configured("TLS_CERT_PATH"), configured("TLS_KEY_PATH")) {
+        (Some(cert), Some(key)) => CertFiles {
+            cert,
+            key,
+            self_signed: false,
+        },
+        _ => {
+            let dir = state::state_dir().join("tls");
+            CertFiles {
+                cert: dir.join("proxy-cert.pem"),
+                key: dir.join("proxy-key.pem"),
+                self_signed: true,
+            }
+        }
+    }
+}
+
+fn subject_names() -> Vec<String> {
+    let mut names = vec!["localhost".to_string(), "127.0.0.1".to_string()];
+    if let Ok(hostname) = env::var("COMPUTERNAME").or_else(|_| env::var("HOSTNAME")) {
+        if !hostname.trim().is_empty() {
+            names.push(hostname.trim().to_ascii_lowercase());
+        }
+    }
+    if let Ok(interfaces) = if_addrs::get_if_addrs() {
+        names.extend(
+            interfaces
+                .into_iter()
+                .filter(|iface| !iface.is_loopback() && iface.ip().is_ipv4())
+                .map(|iface| iface.ip().to_string()),
+        );
+    }
+    names.dedup();
+    names
+}
+
+async fn generate_self_signed(files: &CertFiles) -> Result<(), String> {
+    let mut params = CertificateParams::new(subject_names()).map_err(|e| e.to_string())?;
+    let mut name = DistinguishedName::new();
+    name.push(DnType::CommonName, "WeylandTavern Launcher");
+    params.distinguished_name = name;
+    let today = Utc::now().date_naive();
+    let expires = today + ChronoDuration::days(SELF_SIGNED_DAYS);
+    params.not_before = date_time_ymd(today.year(), today.month() as u8, today.day() as u8);
+    params.not_after = date_time_ymd(expires.year(), expires.month() as u8, expires.day() as u8);
+    let key = KeyPair::generate().map_err(|e| e.to_string())?;
+    let cert = params.self_signed(&key).map_err(|e| e.to_string())?;
+
+    if let Some(parent) = files.cert.parent() {
+        tokio_fs::create_dir_all(parent)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    let _ = tokio_fs::remove_file(&files.key).await;
+    certs::write_private(&files.key, key.serialize_pem().as_bytes())
+        .await
+        .map_err(|e| format!("Failed to write {}: {e}", files.key.display()))?;
+    tokio_fs::write(&files.cert, cert.pem())
+        .await
+        .map_err(|e| format!("Failed to write {}: {e}", files.cert.display()))
+}
+
+async fn read_pem(path: &Path) -> Result<Vec<u8>, String> {
+    tokio_fs::read(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))
+}
+
+async fn server_config(app: &AppHandle) -> Result<ServerConfig, String> {
+    let files = cert_files();
+    if files.self_signed && !(files.cert.is_file() && files.key.is_file()) {
+        log_line(
+            app,
+            "Generating a self-signed certificate for the HTTPS proxy...",
+        )
+        .await;
+        generate_self_signed(&files).await?;
+    }
+    let cert_pem = read_pem(&files.cert).await?;
+    let certs = CertificateDer::pem_slice_iter(&cert_pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("{} is not a PEM certificate: {e}", files.cert.display()))?;
+    if certs.is_empty() {
+        return Err(format!("{} contains no certificate.", files.cert.display()));
+    }
+    let key = PrivateKeyDer::from_pem_slice(&read_pem(&files.key).await?)
+        .map_err(|e| format!("{} is not a PEM private key: {e}", files.key.display()))?;
+    ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("The certificate and key do not match: {e}"))
+}
+
+fn text_response(status: StatusCode, body: &'static str) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response
+}
+
+async fn forward(
+    mut req: Request<Body>,
+    ctx: Arc<ProxyContext>,
+    remote: SocketAddr,
+) -> Result<Response<Body>, Infallible> {
+    playtime::touch();
+    let path = req
+        .uri()
+        .path_and_query()
+        .map(|value| value.as_str())
+        .unwrap_or("/");
+    let Ok(uri) = format!("http://{}{}", ctx.upstream, path).parse::<Uri>() else {
+        return Ok(text_response(
+            StatusCode::BAD_REQUEST,
+            "Invalid request path.",
+        ));
+    };
+    *req.uri_mut() = uri;
+    if let Ok(host) = HeaderValue::from_str(&ctx.upstream) {
+        req.headers_mut().insert(header::HOST, host);
+    }
+    if let Ok(forwarded) = HeaderValue::from_str(&remote.ip().to_string()) {
+        req.headers_mut().insert("x-forwarded-for", forwarded);
+    }
+    req.headers_mut()
+        .insert("x-forwarded-proto", HeaderValue::from_static("https"));
+
+    Ok(ctx.client.request(req).await.unwrap_or_else(|_| {
+        text_response(
+            StatusCode::BAD_GATEWAY,
+            "WeylandTavern is not reachable right now.",
+        )
+    }))
+}
+
+pub(crate) async fn start(
+    app: &AppHandle,
+    upstream_host: &str,
+    upstream_port: u16,
+) -> Result<Option<oneshot::Sender<()>>, String> {
+    if !enabled() {
+        return Ok(None);
+    }
+    let acceptor = TlsAcceptor::from(Arc::new(server_config(app).await?));
+    let upstream_ip = upstream_host
+        .parse::<IpAddr>()
+        .ok()
+        .filter(|ip| !ip.is_unspecified())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    let upstream = SocketAddr::new(upstream_ip, upstream_port);
+    let ctx = Arc::new(ProxyContext {
+        upstream: upstream.to_string(),
+        client: Client::new(),
+    });
+
+    let addr = SocketAddr::new(proxy_host(), proxy_port());
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("Unable to bind HTTPS proxy on {addr}: {e}"))?;
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let (stream, remote) = tokio::select! {
+                _ = &mut shutdown_rx => break,
+                accepted = listener.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(_) => continue,
+                },
+            };
+            let acceptor = acceptor.clone();
+            let ctx = ctx.clone();
+            tauri::async_runtime::spawn(async move {
+                let Ok(stream) = acceptor.accept(stream).await else {
+                    return;
+                };
+                let service = service_fn(move |req| forward(req, ctx.clone(), remote));
+                let _ = Http::new().serve_connection(stream, service).await;
+            });
+        }
+    });
+
+    log_line(
+        app,
+        &format!("HTTPS proxy listening on {addr}, forwarding to http://{upstream}."),
+    )
+    .await;
+    Ok(Some(shutdown_tx))
+}
+
+async fn restart(app: &AppHandle, state: &ServerState) -> Result<(), String> {
+    if let Some(handle) = state.tls_proxy.lock().unwrap().take() {
+        let _ = handle.send(());
+    }
+    let upstream = state
+        .hooks
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|ctx| (ctx.host.clone(), ctx.port));
+    let Some((host, port)) = upstream else {
+        return Ok(());
+    };
+    if let Some(handle) = start(app, &host, port).await? {
+        state.tls_proxy.lock().unwrap().replace(handle);
+    }
+    Ok(())
+}
+
+async fn current_status(state: &ServerState, message: Option<String>) -> TlsProxyStatus {
+    let files = cert_files();
+    let parsed = tokio_fs::read(&files.cert).await.ok().and_then(|data| {
+        let (_, pem) = parse_x509_pem(&data).ok()?;
+        let not_after = pem
+            .parse_x509()
+            .ok()?
+            .validity()
+            .not_after
+            .to_datetime()
+            .to_string();
+        let fingerprint = Sha256::digest(&pem.contents)
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect::<Vec<_>>()
+            .join(":");
+        Some((fingerprint, not_after))
+    });
+    let (fingerprint, not_after) = parsed.unzip();
+    TlsProxyStatus {
+        enabled: enabled(),
+        running: state.tls_proxy.lock().unwrap().is_some(),
+        url: proxy_url(),
+        cert_path: files.cert.to_string_lossy().into_owned(),
+        self_signed: files.self_signed,
+        fingerprint,
+        not_after,
+        message,
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn tls_proxy_status(
+    state: tauri::State<'_, ServerState>,
+) -> Result<TlsProxyStatus, LauncherError> {
+    load_env();
+    Ok(current_status(state.inner(), None).await)
+}
+
+#[tauri::command]
+pub(crate) async fn enable_tls_proxy(app: AppHandle) -> Result<TlsProxyStatus, LauncherError> {
+    load_env();
+    state::save_from(ENABLED_KEY, &Some(true), ChangeOrigin::Ui)?;
+    let state = app.state::<ServerState>();
+    let message = match restart(&app, state.inner()).await {
+        Ok(()) if state.inner().tls_proxy.lock().unwrap().is_some() => None,
+        Ok(()) => Some("The HTTPS proxy will start with the next launch.".to_string()),
+        Err(err) => {
+            log_line(&app, &format!("HTTPS proxy failed to start: {err}")).await;
+            Some(err)
+        }
+    };
+    Ok(current_status(state.inner(), message).await)
+}
+
+#[tauri::command]
+pub(crate) async fn disable_tls_proxy(app: AppHandle) -> Result<TlsProxyStatus, LauncherError> {
+    load_env();
+    state::save_from(ENABLED_KEY, &Some(false), ChangeOrigin::Ui)?;
+    let state = app.state::<ServerState>();
+    let handle = state.inner().tls_proxy.lock().unwrap().take();
+    if let Some(handle) = handle {
+        let _ = handle.send(());
+        log_line(&app, "HTTPS proxy stopped.").await;
+    }
+    Ok(current_status(state.inner(), None).await)
+}
+
+#[tauri::command]
+pub(crate) async fn regenerate_tls_certificate(
+    app: AppHandle,
+) -> Result<TlsProxyStatus, LauncherError> {
+    load_env();
+    let files = cert_files();
+    if !files.self_signed {
+        return Err(
+            "TLS_CERT_PATH and TLS_KEY_PATH point at your own certificate; replace those files instead."
+                .into(),
+        );
+    }
+    generate_self_signed(&files).await?;
+    log_line(
+        &app,
+        "Generated a new self-signed certificate for the HTTPS proxy.",
+    )
+    .await;
+    let state = app.state::<ServerState>();
+    let running = state.inner().tls_proxy.lock().unwrap().is_some();
+    let message = if running {
+        restart(&app, state.inner()).await.err()
+    } else {
+        None
+    };
+    Ok(current_status(state.inner(), message).await)
+}
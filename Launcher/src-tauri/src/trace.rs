@@ -0,0 +1,100 @@
+use std::{
+    env,
+    fs::{self as stdfs, OpenOptions},
+    sync::{Mutex, OnceLock},
+};
+
+use tokio::process::Command as TokioCommand;
+use tracing_subscriber::{filter::LevelFilter, fmt, prelude::*, reload, Registry};
+
+use crate::error::LauncherError;
+use crate::logs_dir;
+
+const LOG_FILE: &str = "launcher.log";
+const ROTATE_BYTES: u64 = 10 * 1024 * 1024;
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::INFO;
+
+static RELOAD: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+fn parse_level(value: &str) -> Option<LevelFilter> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "off" => Some(LevelFilter::OFF),
+        "error" => Some(LevelFilter::ERROR),
+        "warn" | "warning" => Some(LevelFilter::WARN),
+        "info" => Some(LevelFilter::INFO),
+        "debug" => Some(LevelFilter::DEBUG),
+        "trace" => Some(LevelFilter::TRACE),
+        _ => None,
+    }
+}
+
+fn level_name(level: LevelFilter) -> String {
+    level.to_string().to_ascii_lowercase()
+}
+
+pub(crate) fn init() {
+    let level = env::var("LAUNCHER_LOG_LEVEL")
+        .ok()
+        .and_then(|value| parse_level(&value))
+        .unwrap_or(DEFAULT_LEVEL);
+    let dir = logs_dir();
+    if stdfs::create_dir_all(&dir).is_err() {
+        return;
+    }
+    let path = dir.join(LOG_FILE);
+    if stdfs::metadata(&path).is_ok_and(|meta| meta.len() > ROTATE_BYTES) {
+        let _ = stdfs::rename(&path, dir.join(format!("{LOG_FILE}.1")));
+    }
+    let Ok(file) = OpenOptions::new().create(true).append(true).open(&path) else {
+        return;
+    };
+    let (filter, handle) = reload::Layer::new(level);
+    let layer = fmt::layer()
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .with_target(false);
+    if tracing_subscriber::registry()
+        .with(filter)
+        .with(layer)
+        .try_init()
+        .is_ok()
+    {
+        let _ = RELOAD.set(handle);
+        tracing::info!(pid = std::process::id(), level = %level_name(level), "launcher started");
+    }
+}
+
+pub(crate) fn spawning(cmd: &TokioCommand) {
+    let std = cmd.as_std();
+    let args: Vec<String> = std
+        .get_args()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    tracing::debug!(
+        program = %std.get_program().to_string_lossy(),
+        args = %args.join(" "),
+        cwd = ?std.get_current_dir(),
+        "spawning command"
+    );
+}
+
+#[tauri::command]
+pub(crate) async fn get_log_level() -> Result<String, LauncherError> {
+    let handle = RELOAD.get().ok_or("Launcher tracing is not active.")?;
+    handle
+        .with_current(|level| level_name(*level))
+        .map_err(|e| e.to_string().into())
+}
+
+#[tauri::command]
+pub(crate) async fn set_log_level(level: String) -> Result<String, LauncherError> {
+    let parsed = parse_level(&level).ok_or_else(|| {
+        format!("Unknown log level \"{level}\". Use off, error, warn, info, debug, or trace.")
+    })?;
+    let handle = RELOAD.get().ok_or("Launcher tracing is not active.")?;
+    handle
+        .modify(|current| *current = parsed)
+        .map_err(|e| e.to_string())?;
+    tracing::info!(level = %level_name(parsed), "log level changed");
+    Ok(level_name(parsed))
+}
@@ -0,0 +1,60 @@
+use tauri::{
+    menu::{Menu, MenuItem},
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
+    AppHandle, Manager,
+};
+
+use crate::{layout, shutdown, switcher};
+
+const SHOW_ID: &str = "show";
+const QUIT_ID: &str = "quit";
+
+pub(crate) fn show_windows(app: &AppHandle) {
+    for window in app.webview_windows().values() {
+        window.show().ok();
+        window.unminimize().ok();
+        window.set_focus().ok();
+    }
+}
+
+pub(crate) fn install(app: &AppHandle) -> tauri::Result<()> {
+    let show = MenuItem::with_id(app, SHOW_ID, "Show WeylandTavern", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, QUIT_ID, "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&show, &quit])?;
+
+    let mut builder = TrayIconBuilder::new()
+        .tooltip("WeylandTavern")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            SHOW_ID => show_windows(app),
+            QUIT_ID => {
+                for (label, window) in app.webview_windows() {
+                    if !switcher::is_instance_window(&label) {
+                        layout::capture(&window.as_ref().window());
+                    }
+                }
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    shutdown(&app, false).await;
+                    app.exit(0);
+                });
+            }
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_windows(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use std::env;
+
+use serde::Serialize;
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum PriorityClass {
+    Idle,
+    BelowNormal,
+    Normal,
+    AboveNormal,
+    High,
+}
+
+impl PriorityClass {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "idle" | "low" => Some(Self::Idle),
+            "below_normal" => Some(Self::BelowNormal),
+            "normal" => Some(Self::Normal),
+            "above_normal" => Some(Self::AboveNormal),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    #[cfg(unix)]
+    fn nice(self) -> libc::c_int {
+        match self {
+            Self::Idle => 19,
+            Self::BelowNormal => 10,
+            Self::Normal => 0,
+            Self::AboveNormal => -5,
+            Self::High => -10,
+        }
+    }
+
+    #[cfg(windows)]
+    fn windows_class(self) -> windows::Win32::System::Threading::PROCESS_CREATION_FLAGS {
+        use windows::Win32::System::Threading::{
+            ABOVE_NORMAL_PRIORITY_CLASS, BELOW_NORMAL_PRIORITY_CLASS, HIGH_PRIORITY_CLASS,
+            IDLE_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS,
+        };
+        match self {
+            Self::Idle => IDLE_PRIORITY_CLASS,
+            Self::BelowNormal => BELOW_NORMAL_PRIORITY_CLASS,
+            Self::Normal => NORMAL_PRIORITY_CLASS,
+            Self::AboveNormal => ABOVE_NORMAL_PRIORITY_CLASS,
+            Self::High => HIGH_PRIORITY_CLASS,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppliedTuning {
+    priority: Option<PriorityClass>,
+    affinity: Option<Vec<usize>>,
+    warnings: Vec<String>,
+}
+
+impl AppliedTuning {
+    pub(crate) fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+}
+
+fn read_setting(key: &str) -> Option<String> {
+    env::var(key)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_affinity(value: &str) -> Result<Vec<usize>, String> {
+    let invalid = || format!("Invalid SERVER_CPU_AFFINITY value '{value}'.");
+    let mut cores = Vec::new();
+    if let Some(hex) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        let mask = u64::from_str_radix(hex, 16).map_err(|_| invalid())?;
+        cores.extend((0..64).filter(|core| mask & (1 << core) != 0));
+    } else {
+        for part in value
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            match part.split_once('-') {
+                Some((start, end)) => {
+                    let start = start.trim().parse::<usize>().map_err(|_| invalid())?;
+                    let end = end.trim().parse::<usize>().map_err(|_| invalid())?;
+                    if start > end {
+                        return Err(invalid());
+                    }
+                    cores.extend(start..=end);
+                }
+                None => cores.push(part.parse::<usize>().map_err(|_| invalid())?),
+            }
+        }
+    }
+    cores.sort_unstable();
+    cores.dedup();
+    if cores.is_empty() {
+        return Err(invalid());
+    }
+    let available = std::thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(usize::MAX);
+    if let Some(core) = cores.iter().find(|core| **core >= available) {
+        return Err(format!(
+            "SERVER_CPU_AFFINITY references core {core}, but only {available} cores are available."
+        ));
+    }
+    Ok(cores)
+}
+
+#[cfg(unix)]
+fn set_priority(pid: u32, priority: PriorityClass) -> Result<(), String> {
+    let result =
+        unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, priority.nice()) };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn set_affinity(pid: u32, cores: &[usize]) -> Result<(), String> {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_ZERO(&mut set);
+        for core in cores {
+            libc::CPU_SET(*core, &mut set);
+        }
+        if libc::sched_setaffinity(
+            pid as libc::pid_t,
+            std::mem::size_of::<libc::cpu_set_t>(),
+            &set,
+        ) != 0
+        {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn set_affinity(_pid: u32, _cores: &[usize]) -> Result<(), String> {
+    Err("CPU affinity is not supported on this platform".into())
+}
+
+#[cfg(windows)]
+fn with_process<T>(
+    pid: u32,
+    action: impl FnOnce(windows::Win32::Foundation::HANDLE) -> Result<T, String>,
+) -> Result<T, String> {
+    use windows::Win32::{
+        Foundation::CloseHandle,
+        System::Threading::{
+            OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_SET_INFORMATION,
+        },
+    };
+    unsafe {
+        let process = OpenProcess(
+            PROCESS_SET_INFORMATION | PROCESS_QUERY_LIMITED_INFORMATION,
+            false,
+            pid,
+        )
+        .map_err(|e| format!("OpenProcess failed: {e}"))?;
+        let result = action(process);
+        let _ = CloseHandle(process);
+        result
+    }
+}
+
+#[cfg(windows)]
+fn set_priority(pid: u32, priority: PriorityClass) -> Result<(), String> {
+    use windows::Win32::System::Threading::SetPriorityClass;
+    with_process(pid, |process| unsafe {
+        SetPriorityClass(process, priority.windows_class())
+            .map_err(|e| format!("SetPriorityClass failed: {e}"))
+    })
+}
+
+#[cfg(windows)]
+fn set_affinity(pid: u32, cores: &[usize]) -> Result<(), String> {
+    use windows::Win32::System::Threading::SetProcessAffinityMask;
+    if let Some(core) = cores.iter().find(|core| **core >= usize::BITS as usize) {
+        return Err(format!("core {core} is outside the process affinity mask"));
+    }
+    let mask = cores.iter().fold(0usize, |mask, core| mask | (1 << core));
+    with_process(pid, |process| unsafe {
+        SetProcessAffinityMask(process, mask)
+            .map_err(|e| format!("SetProcessAffinityMask failed: {e}"))
+    })
+}
+
+pub(crate) fn apply(pid: u32) -> AppliedTuning {
+    let mut applied = AppliedTuning::default();
+
+    if let Some(raw) = read_setting("SERVER_PRIORITY") {
+        match PriorityClass::parse(&raw) {
+            Some(priority) => match set_priority(pid, priority) {
+                Ok(()) => applied.priority = Some(priority),
+                Err(err) => applied
+                    .warnings
+                    .push(format!("Could not set server priority to {raw}: {err}")),
+            },
+            None => applied.warnings.push(format!(
+                "Invalid SERVER_PRIORITY value '{raw}'. Use idle, below_normal, normal, above_normal, or high."
+            )),
+        }
+    }
+
+    if let Some(raw) = read_setting("SERVER_CPU_AFFINITY") {
+        match parse_affinity(&raw) {
+            Ok(cores) => match set_affinity(pid, &cores) {
+                Ok(()) => applied.affinity = Some(cores),
+                Err(err) => applied
+                    .warnings
+                    .push(format!("Could not pin the server to cores {raw}: {err}")),
+            },
+            Err(err) => applied.warnings.push(err),
+        }
+    }
+
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_affinity_accepts_lists_ranges_and_masks() {
+        assert_eq!(parse_affinity("0"), Ok(vec![0]));
+        assert_eq!(parse_affinity("0, 0-0,0"), Ok(vec![0]));
+        assert_eq!(parse_affinity("0x1"), Ok(vec![0]));
+    }
+
+    #[test]
+    fn parse_affinity_rejects_bad_values() {
+        for value in ["", "x", "1-0", "0x", "0xzz", "-1"] {
+            assert!(parse_affinity(value).is_err(), "{value} should be rejected");
+        }
+        assert!(parse_affinity("100000")
+            .unwrap_err()
+            .contains("cores are available"));
+    }
+}
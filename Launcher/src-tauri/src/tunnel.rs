@@ -0,0 +1,262 @@
+use std::{
+    env,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    process::Stdio,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::Serialize;
+use tauri::AppHandle;
+use tokio::{
+    io::AsyncRead,
+    process::{Child as TokioChild, Command as TokioCommand},
+};
+
+use crate::{
+    error::{ErrorKind, LauncherError},
+    events, log_line, qr_svg,
+    sanitize::LossyLines,
+    share, trace, ServerState,
+};
+
+const URL_WAIT_SECS: u64 = 30;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum TunnelProvider {
+    Cloudflared,
+    Ngrok,
+}
+
+impl TunnelProvider {
+    fn from_env() -> Option<Self> {
+        match env::var("TUNNEL_PROVIDER")
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "cloudflared" | "cloudflare" => Some(Self::Cloudflared),
+            "ngrok" => Some(Self::Ngrok),
+            _ => None,
+        }
+    }
+
+    fn binary(self) -> String {
+        env::var("TUNNEL_BIN")
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| match self {
+                Self::Cloudflared => "cloudflared".into(),
+                Self::Ngrok => "ngrok".into(),
+            })
+    }
+
+    fn args(self, target: SocketAddr) -> Vec<String> {
+        match self {
+            Self::Cloudflared => vec![
+                "tunnel".into(),
+                "--no-autoupdate".into(),
+                "--url".into(),
+                format!("http://{target}"),
+            ],
+            Self::Ngrok => vec![
+                "http".into(),
+                target.to_string(),
+                "--log".into(),
+                "stdout".into(),
+                "--log-format".into(),
+                "json".into(),
+            ],
+        }
+    }
+
+    fn parse_url(self, line: &str) -> Option<String> {
+        match self {
+            Self::Cloudflared => line
+                .split(|c: char| c.is_whitespace() || c == '|' || c == '"')
+                .find(|word| word.starts_with("https://") && word.ends_with(".trycloudflare.com"))
+                .map(str::to_string),
+            Self::Ngrok => serde_json::from_str::<serde_json::Value>(line)
+                .ok()?
+                .get("url")?
+                .as_str()
+                .filter(|url| url.starts_with("https://"))
+                .map(str::to_string),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Cloudflared => "cloudflared",
+            Self::Ngrok => "ngrok",
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TunnelInfo {
+    provider: TunnelProvider,
+    url: String,
+    target: String,
+    qr_svg: String,
+}
+
+pub(crate) struct Tunnel {
+    child: TokioChild,
+    info: Arc<Mutex<Option<TunnelInfo>>>,
+}
+
+impl Tunnel {
+    pub(crate) async fn stop(mut self) {
+        let _ = self.child.start_kill();
+        let _ = self.child.wait().await;
+        share::set_tunnel_url(None);
+    }
+}
+
+fn looks_like_error(line: &str) -> bool {
+    let lower = line.to_ascii_lowercase();
+    lower.contains(" err ")
+        || lower.contains("error")
+        || lower.contains("failed")
+        || lower.contains("\"lvl\":\"eror\"")
+}
+
+fn watch_output(
+    app: &AppHandle,
+    provider: TunnelProvider,
+    target: SocketAddr,
+    reader: impl AsyncRead + Unpin + Send + 'static,
+    info: Arc<Mutex<Option<TunnelInfo>>>,
+) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut lines = LossyLines::new(reader);
+        while let Some(line) = lines.next_line().await {
+            if info.lock().unwrap().is_none() {
+                if let Some(url) = provider.parse_url(&line) {
+                    let found = TunnelInfo {
+                        provider,
+                        qr_svg: qr_svg(&url),
+                        url: url.clone(),
+                        target: target.to_string(),
+                    };
+                    info.lock().unwrap().replace(found.clone());
+                    share::set_tunnel_url(Some(url.clone()));
+                    log_line(&app, &format!("Remote access tunnel ready at {url}")).await;
+                    events::emit(&app, "tunnel-url", &found);
+                    continue;
+                }
+            }
+            if looks_like_error(&line) {
+                log_line(&app, &format!("[{}] {line}", provider.name())).await;
+            }
+        }
+    });
+}
+
+fn allow_unprotected() -> bool {
+    matches!(
+        env::var("TUNNEL_ALLOW_UNPROTECTED")
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase()
+            .as_str(),
+        "true" | "1" | "yes" | "on"
+    )
+}
+
+pub(crate) async fn start(
+    app: &AppHandle,
+    host: &str,
+    port: u16,
+) -> Result<Option<Tunnel>, LauncherError> {
+    let Some(provider) = TunnelProvider::from_env() else {
+        return Ok(None);
+    };
+    let target = match share::local_address() {
+        Some(proxy) => proxy,
+        None => {
+            if !allow_unprotected() {
+                return Err(LauncherError::new(
+                    ErrorKind::Unprotected,
+                    "Refusing to open a public tunnel straight to SillyTavern without the guest proxy.",
+                ));
+            }
+            log_line(
+                app,
+                "Tunnel is exposing SillyTavern directly because TUNNEL_ALLOW_UNPROTECTED is set.",
+            )
+            .await;
+            let ip = host
+                .parse()
+                .ok()
+                .filter(|ip: &IpAddr| !ip.is_unspecified())
+                .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+            SocketAddr::new(ip, port)
+        }
+    };
+
+    let binary = provider.binary();
+    let mut cmd = TokioCommand::new(&binary);
+    cmd.args(provider.args(target))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    trace::spawning(&cmd);
+    let mut child = cmd.spawn().map_err(|e| {
+        format!(
+            "Unable to start {binary}: {e}. Install {} or set TUNNEL_BIN.",
+            provider.name()
+        )
+    })?;
+
+    let info = Arc::new(Mutex::new(None));
+    if let Some(stdout) = child.stdout.take() {
+        watch_output(app, provider, target, stdout, info.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        watch_output(app, provider, target, stderr, info.clone());
+    }
+
+    let waiting = info.clone();
+    let app_for_timeout = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(URL_WAIT_SECS)).await;
+        if waiting.lock().unwrap().is_none() && Arc::strong_count(&waiting) > 1 {
+            log_line(
+                &app_for_timeout,
+                &format!(
+                    "{} has not reported a public URL after {URL_WAIT_SECS} seconds; check the logs.",
+                    provider.name()
+                ),
+            )
+            .await;
+        }
+    });
+
+    log_line(
+        app,
+        &format!("Starting {} tunnel to {target}...", provider.name()),
+    )
+    .await;
+    Ok(Some(Tunnel { child, info }))
+}
+
+#[tauri::command]
+pub(crate) async fn tunnel_status(
+    state: tauri::State<'_, ServerState>,
+) -> Result<Option<TunnelInfo>, LauncherError> {
+    Ok(state
+        .inner()
+        .tunnel
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|tunnel| tunnel.info.lock().unwrap().clone()))
+}
@@ -0,0 +1,300 @@
+use std::{env, path::Path, time::Duration};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::{
+    access,
+    error::{ErrorKind, LauncherError},
+    events, fetch_depth, head_commit, installs, load_env, log_line, offline,
+    operation::{self, Operation},
+    repo::{self, RepoState},
+    run_git,
+    state::{self, ChangeOrigin},
+    vendor_dir, ServerState,
+};
+
+const LAST_CHECK_KEY: &str = "lastUpdateCheck";
+const DEFAULT_INTERVAL_HOURS: u64 = 24;
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+const BUSY_RETRY: Duration = Duration::from_secs(300);
+const POLICY_KEY: &str = "UPDATE_POLICY";
+const PINNED_KEY: &str = "UPDATE_PINNED_COMMIT";
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UpdateCheck {
+    checked_at: i64,
+    pending: u32,
+    upstream: Option<String>,
+    latest_subject: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum UpdatePolicyMode {
+    Auto,
+    #[default]
+    Prompt,
+    Manual,
+    Frozen,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UpdatePolicy {
+    pub(crate) mode: UpdatePolicyMode,
+    pub(crate) pinned_commit: Option<String>,
+    head: Option<String>,
+}
+
+impl UpdatePolicyMode {
+    fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "prompt" => Some(Self::Prompt),
+            "manual" => Some(Self::Manual),
+            "frozen" => Some(Self::Frozen),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Prompt => "prompt",
+            Self::Manual => "manual",
+            Self::Frozen => "frozen",
+        }
+    }
+}
+
+fn legacy_mode() -> UpdatePolicyMode {
+    let raw = env::var("ALLOW_GIT_PULL_IN_APP").unwrap_or_default();
+    if matches!(
+        raw.trim().to_ascii_lowercase().as_str(),
+        "1" | "true" | "yes" | "on"
+    ) {
+        UpdatePolicyMode::Prompt
+    } else {
+        UpdatePolicyMode::Frozen
+    }
+}
+
+pub(crate) fn policy_mode() -> UpdatePolicyMode {
+    env::var(POLICY_KEY)
+        .ok()
+        .and_then(|raw| UpdatePolicyMode::parse(&raw))
+        .unwrap_or_else(legacy_mode)
+}
+
+pub(crate) fn pinned_commit() -> Option<String> {
+    env::var(PINNED_KEY)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+async fn current_policy() -> UpdatePolicy {
+    let mode = policy_mode();
+    let head = match vendor_dir() {
+        Ok(repo) => head_commit(&repo).await,
+        Err(_) => None,
+    };
+    UpdatePolicy {
+        mode,
+        pinned_commit: (mode == UpdatePolicyMode::Frozen)
+            .then(pinned_commit)
+            .flatten(),
+        head,
+    }
+}
+
+fn check_interval() -> Option<Duration> {
+    let hours = env::var("UPDATE_CHECK_HOURS")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL_HOURS);
+    (hours > 0).then(|| Duration::from_secs(hours * 3600))
+}
+
+async fn git_text(repo: &Path, args: &[&str]) -> Result<String, LauncherError> {
+    let output = run_git(repo, args).await?;
+    if !output.status.success() {
+        return Err(LauncherError::new(
+            ErrorKind::Git,
+            format!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+async fn run_check(app: &AppHandle, origin: ChangeOrigin) -> Result<UpdateCheck, LauncherError> {
+    load_env();
+    let repo = vendor_dir()?;
+    let connectivity = offline::detect().await;
+    if connectivity.is_offline() {
+        return Err(LauncherError::new(
+            ErrorKind::Offline,
+            format!("offline because {}", connectivity.describe()),
+        ));
+    }
+    let state = repo::detect(&repo).await;
+    if matches!(state, RepoState::MissingRemote { .. }) {
+        return Err(LauncherError::new(ErrorKind::RepoState, state.describe()));
+    }
+
+    let depth_arg = fetch_depth().map(|depth| format!("--depth={depth}"));
+    let source = installs::pull_source();
+    let mut fetch_args = vec!["fetch", "--quiet"];
+    fetch_args.extend(depth_arg.as_deref());
+    if let Some((remote, branch)) = &source {
+        fetch_args.extend([remote.as_str(), branch.as_str()]);
+    }
+    git_text(&repo, &fetch_args).await?;
+    let (target, upstream) = match &source {
+        Some((remote, branch)) => ("FETCH_HEAD", Some(format!("{remote} {branch}"))),
+        None => (
+            "@{u}",
+            git_text(&repo, &["rev-parse", "--abbrev-ref", "@{u}"])
+                .await
+                .ok(),
+        ),
+    };
+    let range = format!("HEAD..{target}");
+    let pending = git_text(&repo, &["rev-list", "--count", &range])
+        .await?
+        .parse::<u32>()
+        .unwrap_or(0);
+    let latest_subject = if pending > 0 {
+        git_text(&repo, &["log", "-1", "--format=%s", target])
+            .await
+            .ok()
+    } else {
+        None
+    };
+
+    let check = UpdateCheck {
+        checked_at: Utc::now().timestamp(),
+        pending,
+        upstream,
+        latest_subject,
+    };
+    if let Err(err) = state::save_from(LAST_CHECK_KEY, &check, origin) {
+        log_line(app, &format!("Failed to record the update check: {err}")).await;
+    }
+    if pending > 0 {
+        log_line(
+            app,
+            &format!("{pending} new WeylandTavern commit(s) are available."),
+        )
+        .await;
+        events::emit(app, "update-available", &check);
+    }
+    Ok(check)
+}
+
+pub(crate) fn spawn_scheduler(app: &AppHandle) {
+    let Some(interval) = check_interval() else {
+        return;
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let last: UpdateCheck = state::load(LAST_CHECK_KEY);
+        let elapsed = Utc::now()
+            .timestamp()
+            .saturating_sub(last.checked_at)
+            .max(0) as u64;
+        let mut wait = interval
+            .saturating_sub(Duration::from_secs(elapsed))
+            .max(STARTUP_DELAY);
+        loop {
+            tokio::time::sleep(wait).await;
+            wait = interval;
+            load_env();
+            if policy_mode() == UpdatePolicyMode::Frozen {
+                continue;
+            }
+            let Ok(_operation) = operation::begin(&app, Operation::UpdateCheck) else {
+                wait = BUSY_RETRY;
+                continue;
+            };
+            if let Err(err) = run_check(&app, ChangeOrigin::Scheduler).await {
+                log_line(&app, &format!("Scheduled update check failed: {err}")).await;
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub(crate) async fn check_for_updates(app: AppHandle) -> Result<UpdateCheck, LauncherError> {
+    let _operation = operation::begin(&app, Operation::UpdateCheck)?;
+    run_check(&app, ChangeOrigin::Ui).await
+}
+
+#[tauri::command]
+pub(crate) async fn last_update_check() -> Result<Option<UpdateCheck>, LauncherError> {
+    let last: UpdateCheck = state::load(LAST_CHECK_KEY);
+    Ok((last.checked_at > 0).then_some(last))
+}
+
+#[tauri::command]
+pub(crate) async fn get_update_policy() -> Result<UpdatePolicy, LauncherError> {
+    load_env();
+    Ok(current_policy().await)
+}
+
+#[tauri::command]
+pub(crate) async fn set_update_policy(
+    app: AppHandle,
+    state: State<'_, ServerState>,
+    mode: UpdatePolicyMode,
+    commit: Option<String>,
+) -> Result<UpdatePolicy, LauncherError> {
+    let _operation = operation::begin(&app, Operation::PolicyChange)?;
+    load_env();
+    let mut pinned = String::new();
+    if mode == UpdatePolicyMode::Frozen {
+        let repo = vendor_dir()?;
+        let wanted = commit
+            .map(|commit| commit.trim().to_string())
+            .filter(|commit| !commit.is_empty())
+            .unwrap_or_else(|| "HEAD".into());
+        pinned = git_text(
+            &repo,
+            &["rev-parse", "--verify", &format!("{wanted}^{{commit}}")],
+        )
+        .await
+        .map_err(|_| format!("{wanted} is not a commit in the vendor checkout."))?;
+        if head_commit(&repo).await.as_deref() != Some(pinned.as_str()) {
+            installs::ensure_stopped(state.inner(), "pinning another commit")?;
+            git_text(&repo, &["reset", "--keep", &pinned])
+                .await
+                .map_err(|err| format!("Could not move the checkout to {pinned}: {err}"))?;
+        }
+    }
+    access::set_env_values(
+        &access::env_file(),
+        &[
+            (POLICY_KEY, mode.name().to_string()),
+            (PINNED_KEY, pinned.clone()),
+        ],
+    )?;
+    env::set_var(POLICY_KEY, mode.name());
+    env::set_var(PINNED_KEY, &pinned);
+    log_line(
+        &app,
+        &if pinned.is_empty() {
+            format!("Update policy set to {}.", mode.name())
+        } else {
+            format!("Update policy set to frozen at {pinned}; pulls are refused.")
+        },
+    )
+    .await;
+    Ok(current_policy().await)
+}
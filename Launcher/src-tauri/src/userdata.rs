@@ -0,0 +1,210 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use serde::Serialize;
+use tauri::{AppHandle, State};
+use tokio::fs as tokio_fs;
+
+use crate::{
+    access, disk,
+    error::LauncherError,
+    installs, load_env, log_line,
+    operation::{self, Operation},
+    server_arg, silly_dir, vendor_dir, ServerState,
+};
+
+pub(crate) const DATA_ROOT_FLAG: &str = "--dataRoot";
+const DATA_ROOT_KEY: &str = "DATA_ROOT";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataRootStatus {
+    path: String,
+    configured: bool,
+    inside_checkout: bool,
+    files: u64,
+    bytes: u64,
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    if let Ok(found) = path.canonicalize() {
+        return found;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => {
+            normalize(parent).join(name)
+        }
+        _ => env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf()),
+    }
+}
+
+pub(crate) fn configured() -> Option<PathBuf> {
+    env::var(DATA_ROOT_KEY)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .map(|value| normalize(Path::new(&value)))
+}
+
+pub(crate) fn dir(silly: &Path) -> PathBuf {
+    configured()
+        .or_else(|| server_arg(DATA_ROOT_FLAG).map(|raw| silly.join(raw)))
+        .unwrap_or_else(|| silly.join("data"))
+}
+
+pub(crate) fn resolve(silly: &Path, relative: &str) -> PathBuf {
+    match relative.strip_prefix("data/") {
+        Some(rest) => dir(silly).join(rest),
+        None => silly.join(relative),
+    }
+}
+
+pub(crate) fn relative(silly: &Path, path: &Path) -> PathBuf {
+    if let Ok(inside) = path.strip_prefix(silly) {
+        return inside.to_path_buf();
+    }
+    let data = dir(silly);
+    [normalize(&data), data]
+        .iter()
+        .find_map(|root| path.strip_prefix(root).ok())
+        .map(|inside| Path::new("data").join(inside))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+fn inside_checkout(path: &Path) -> bool {
+    vendor_dir()
+        .map(|repo| normalize(path).starts_with(normalize(&repo)))
+        .unwrap_or(false)
+}
+
+pub(crate) async fn measure(root: &Path) -> (u64, u64) {
+    let (mut files, mut bytes) = (0, 0);
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(mut entries) = tokio_fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            match entry.metadata().await {
+                Ok(meta) if meta.is_dir() => pending.push(entry.path()),
+                Ok(meta) => {
+                    files += 1;
+                    bytes += meta.len();
+                }
+                Err(_) => {}
+            }
+        }
+    }
+    (files, bytes)
+}
+
+async fn status(silly: &Path) -> DataRootStatus {
+    let path = dir(silly);
+    let (files, bytes) = measure(&path).await;
+    DataRootStatus {
+        inside_checkout: inside_checkout(&path),
+        configured: configured().is_some(),
+        path: path.display().to_string(),
+        files,
+        bytes,
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn data_root_status() -> Result<DataRootStatus, LauncherError> {
+    load_env();
+    let silly = silly_dir()?;
+    Ok(status(&silly).await)
+}
+
+#[tauri::command]
+pub(crate) async fn migrate_data_root(
+    app: AppHandle,
+    state: State<'_, ServerState>,
+    target: String,
+) -> Result<DataRootStatus, LauncherError> {
+    let _operation = operation::begin(&app, Operation::DataMigration)?;
+    load_env();
+    installs::ensure_stopped(state.inner(), "moving its data")?;
+    if server_arg(DATA_ROOT_FLAG).is_some() {
+        return Err(format!(
+            "SERVER_ARGS already sets {DATA_ROOT_FLAG}. Remove it there so the launcher can manage the data folder."
+        )
+        .into());
+    }
+    let silly = silly_dir()?;
+    let source = dir(&silly);
+    let target = normalize(Path::new(target.trim()));
+    if normalize(&source) == target {
+        return Err(format!("User data already lives in {}.", target.display()).into());
+    }
+    if inside_checkout(&target) {
+        return Err(format!(
+            "{} is inside the WeylandTavern checkout, where git could still touch it. Pick a folder outside it.",
+            target.display()
+        )
+        .into());
+    }
+    if std::fs::read_dir(&target).is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(format!("{} already exists and is not empty.", target.display()).into());
+    }
+
+    if source.is_dir() {
+        let (files, bytes) = measure(&source).await;
+        if let Ok(available) = disk::available_bytes(&target) {
+            if available < bytes {
+                return Err(format!(
+                    "Not enough free space in {} to move {} MB of user data.",
+                    target.display(),
+                    bytes.div_ceil(1024 * 1024)
+                )
+                .into());
+            }
+        }
+        log_line(
+            &app,
+            &format!(
+                "Copying {files} file(s) of user data from {} to {}...",
+                source.display(),
+                target.display()
+            ),
+        )
+        .await;
+        access::copy_tree(&source, &target).await?;
+    } else {
+        tokio_fs::create_dir_all(&target)
+            .await
+            .map_err(|e| format!("Failed to create {}: {e}", target.display()))?;
+    }
+
+    access::set_env_values(
+        &access::env_file(),
+        &[(DATA_ROOT_KEY, target.display().to_string())],
+    )?;
+    env::set_var(DATA_ROOT_KEY, &target);
+
+    let mut message = format!("User data now lives in {}.", target.display());
+    if source.is_dir() {
+        let parked = source.with_file_name(format!(
+            "data.moved-{}",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        match tokio_fs::rename(&source, &parked).await {
+            Ok(()) => message.push_str(&format!(
+                " The old copy was renamed to {} and can be deleted.",
+                parked.display()
+            )),
+            Err(err) => message.push_str(&format!(
+                " The old copy at {} could not be renamed ({err}); delete it once you have checked the new location.",
+                source.display()
+            )),
+        }
+    }
+    log_line(&app, &message).await;
+    Ok(status(&silly).await)
+}
@@ -1,11 +1,52 @@
-import { useCallback, useEffect, useMemo, useState } from 'react';
-import { listen } from '@tauri-apps/api/event';
+import { useCallback, useEffect, useMemo, useRef, useState, type ReactNode } from 'react';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 import { invoke } from '@tauri-apps/api/core';
 
 const appWindow = getCurrentWindow();
 
-type UpdateStatus = 'success' | 'upToDate' | 'needRetry' | 'failed';
+type UpdateStatus = 'success' | 'upToDate' | 'needRetry' | 'failed' | 'offline' | 'dryRun';
+
+type PromptCode =
+  | 'updateFailed'
+  | 'updateFailedAfterStash'
+  | 'submodulesFailed'
+  | 'migrationFailed'
+  | 'insufficientDisk'
+  | 'restoreStash'
+  | 'npmInstallFailed'
+  | 'serverStartFailed'
+  | 'readOnlyPath'
+  | 'repoBroken';
+
+type PromptAction =
+  | 'retryWithStash'
+  | 'manageStash'
+  | 'skipUpdate'
+  | 'restoreStash'
+  | 'discardStash'
+  | 'retryInstall'
+  | 'repairNodeModules'
+  | 'forceStart'
+  | 'retryStart'
+  | 'fixPermissions'
+  | 'relocateVendor'
+  | 'repairRepo'
+  | 'exit';
+
+interface Prompt {
+  code: PromptCode;
+  params: Record<string, string>;
+  actions: PromptAction[];
+  answer?: PromptAction | null;
+}
+
+type RepoState =
+  | { state: 'healthy' }
+  | { state: 'indexLock'; path: string; ageSeconds: number }
+  | { state: 'missingRemote'; remote: string | null; url: string | null }
+  | { state: 'detachedHead'; commit: string; branch: string | null }
+  | { state: 'shallow' }
+  | { state: 'diverged'; branch: string; upstream: string; ahead: number; behind: number };
 
 interface UpdateResponse {
   status: UpdateStatus;
@@ -14,14 +55,193 @@ interface UpdateResponse {
   logContents?: string;
   diff?: string;
   stashUsed: boolean;
+  attempts?: number;
+  prompt?: Prompt | null;
+  repoState?: RepoState | null;
+  plan?: DryRun | null;
+}
+
+interface DryRun {
+  action: string;
+  summary: string;
+  commands: { command: string; dir: string }[];
+  files: { change: 'create' | 'replace' | 'remove'; path: string }[];
+  notes: string[];
 }
 
 interface CharacterResponse {
   success: boolean;
   message: string;
+  offline?: boolean;
+  attempts?: number;
+}
+
+type OnboardingStep = 'depsVerified' | 'vendorBootstrapped' | 'firstInstall' | 'firstLaunch';
+
+interface OnboardingState {
+  depsVerified?: string | null;
+  vendorBootstrapped?: string | null;
+  firstInstall?: string | null;
+  firstLaunch?: string | null;
+  nextStep: OnboardingStep | null;
+  complete: boolean;
+}
+
+interface ChatIssue {
+  path: string;
+  kind: 'repaired' | 'corrupted';
+  detail: string;
+  preservedCopy?: string | null;
+  backup?: string | null;
+}
+
+interface ChatReport {
+  scanned: number;
+  issues: ChatIssue[];
+}
+
+interface ConfigDrift {
+  files: string[];
+  changes: { key: string; previous?: string | null; current?: string | null }[];
+  runningPort: number;
+  expectedPort: number;
+  reconciled: boolean;
+  restartRequired: boolean;
+  message: string;
+}
+
+interface PlaytimeTotals {
+  uptimeSeconds: number;
+  activeSeconds: number;
+  sessions: number;
+}
+
+interface PlaytimeStats {
+  today: PlaytimeTotals;
+  thisWeek: PlaytimeTotals;
+  last7Days: PlaytimeTotals;
+  allTime: PlaytimeTotals;
+  idleSeconds?: number | null;
+}
+
+interface UpdateCheck {
+  checkedAt: number;
+  pending: number;
+  upstream?: string | null;
+  latestSubject?: string | null;
+}
+
+type UpdatePolicyMode = 'auto' | 'prompt' | 'manual' | 'frozen';
+
+interface UpdatePolicy {
+  mode: UpdatePolicyMode;
+  pinnedCommit: string | null;
+  head: string | null;
+}
+
+const UPDATE_POLICY_LABELS: Record<UpdatePolicyMode, string> = {
+  auto: 'Update automatically',
+  prompt: 'Ask at every start',
+  manual: 'Only when I press Update',
+  frozen: 'Frozen at the current commit',
+};
+
+interface CurrentOperation {
+  operation: string;
+  label: string;
+  startedAt: number;
+}
+
+interface LauncherError {
+  kind:
+    | 'busy'
+    | 'serverRunning'
+    | 'readOnlyPath'
+    | 'npmInstallFailed'
+    | 'insufficientDisk'
+    | 'offline'
+    | 'git'
+    | 'repoState'
+    | 'healthCheck'
+    | 'notConfigured'
+    | 'unprotected'
+    | 'hookFailed'
+    | 'spawnFailed'
+    | 'failed';
+  message: string;
+  details: string | null;
+  remediation: string | null;
+  retryable: boolean;
+  currentOperation?: string;
+}
+
+interface TunnelInfo {
+  provider: 'cloudflared' | 'ngrok';
+  url: string;
+  target: string;
+  qrSvg: string;
+}
+
+interface LanAddress {
+  interface: string;
+  address: string;
+  url: string;
+  qrSvg: string;
+}
+
+type NotifyEvent = 'updateFinished' | 'npmInstall' | 'characterSync' | 'serverCrash';
+
+type NotificationToggles = Record<NotifyEvent, boolean>;
+
+interface SettingsChanged {
+  key: string;
+  revision: number;
+  origin: 'launcher' | 'scheduler' | 'ui';
+  changedAt: number;
+}
+
+const NOTIFY_LABELS: Record<NotifyEvent, string> = {
+  updateFinished: 'Update finished',
+  npmInstall: 'Node modules installed',
+  characterSync: 'Character sync',
+  serverCrash: 'Server crash while minimized',
+};
+
+interface RunningInstance {
+  id: string;
+  name: string;
+  sillyDir: string;
+  pid: number;
+  url?: string | null;
+  current: boolean;
+}
+
+interface AutostartStatus {
+  enabled: boolean;
+  location?: string | null;
+  command?: string | null;
+}
+
+interface DisplayCapabilities {
+  displayServer: 'wayland' | 'x11' | 'native' | 'unknown';
+  desktop?: string | null;
+  clientSideDecorations: boolean;
+  windowPositioning: boolean;
+  appId: string;
+  desktopEntry?: string | null;
+}
+
+interface LanAccessInfo {
+  host: string;
+  port: number;
+  listeningOnLan: boolean;
+  addresses: LanAddress[];
+  firewallRule?: boolean | null;
+  hint?: string | null;
 }
 
 type Step =
+  | 'onboarding'
   | 'updatePrompt'
   | 'updateRunning'
   | 'updateRetryPrompt'
@@ -36,43 +256,239 @@ type ProgressStatus = 'pending' | 'running' | 'success' | 'error' | 'skipped';
 const UPDATE_SKIP_MESSAGE = 'Vendor update skipped by user.';
 const CHARACTER_SKIP_MESSAGE = 'Character sync skipped by user.';
 
+const PROMPT_TEXT: Record<PromptCode, (params: Record<string, string>) => string> = {
+  updateFailed: () => 'There was an error updating WeylandTavern.',
+  updateFailedAfterStash: () => 'Update failed even after stashing local changes.',
+  submodulesFailed: () => 'WeylandTavern was pulled, but updating its submodules failed.',
+  migrationFailed: (p) => `WeylandTavern was updated, but a migration failed: ${p.detail ?? ''}`,
+  insufficientDisk: (p) => p.detail ?? 'Not enough free disk space to update WeylandTavern.',
+  restoreStash: () => 'Restore stashed changes?',
+  npmInstallFailed: () => 'npm install failed. Continue launching anyway?',
+  serverStartFailed: (p) => `Failed to start the server: ${p.detail ?? ''}`,
+  readOnlyPath: (p) => p.detail ?? `${p.path ?? 'A launcher folder'} is not writable.`,
+  repoBroken: (p) => p.detail ?? 'The WeylandTavern checkout needs a repair before it can update.',
+};
+
+const ACTION_LABELS: Record<PromptAction, string> = {
+  retryWithStash: 'Retry with overwrite',
+  manageStash: 'Manage stashed changes',
+  skipUpdate: 'Skip update',
+  restoreStash: 'Yes',
+  discardStash: 'No',
+  retryInstall: 'Retry npm install',
+  repairNodeModules: 'Repair node_modules',
+  forceStart: 'Continue without reinstalling',
+  retryStart: 'Retry start',
+  fixPermissions: 'Fix permissions (administrator)',
+  relocateVendor: 'Move WeylandTavern to my user folder',
+  repairRepo: 'Repair checkout',
+  exit: 'Exit',
+};
+
+interface StyleSpan {
+  start: number;
+  end: number;
+  color?: string | null;
+  bold: boolean;
+}
+
+interface LogEntry {
+  text: string;
+  spans?: StyleSpan[];
+}
+
+const LOG_COLORS: Record<string, string> = {
+  black: '#5c6370',
+  red: '#e06c75',
+  green: '#98c379',
+  yellow: '#e5c07b',
+  blue: '#61afef',
+  magenta: '#c678dd',
+  cyan: '#56b6c2',
+  white: '#dcdfe4',
+  brightBlack: '#7f848e',
+  brightRed: '#ff7b86',
+  brightGreen: '#b5e890',
+  brightYellow: '#ffd68a',
+  brightBlue: '#82c4ff',
+  brightMagenta: '#de9bf0',
+  brightCyan: '#7fd6e0',
+  brightWhite: '#ffffff',
+};
+
+const renderLogEntry = (entry: LogEntry, key: number) => {
+  if (!entry.spans?.length) {
+    return <span key={key}>{entry.text}</span>;
+  }
+  const parts: ReactNode[] = [];
+  let cursor = 0;
+  for (const span of entry.spans) {
+    if (span.start > cursor) {
+      parts.push(entry.text.slice(cursor, span.start));
+    }
+    parts.push(
+      <span
+        key={span.start}
+        style={{
+          color: span.color ? LOG_COLORS[span.color] ?? span.color : undefined,
+          fontWeight: span.bold ? 'bold' : undefined,
+        }}
+      >
+        {entry.text.slice(span.start, span.end)}
+      </span>
+    );
+    cursor = span.end;
+  }
+  parts.push(entry.text.slice(cursor));
+  return <span key={key}>{parts}</span>;
+};
+
+const renderLogs = (logs: LogEntry[]) =>
+  logs.flatMap((entry, index) =>
+    index === 0 ? [renderLogEntry(entry, index)] : ['\n', renderLogEntry(entry, index)]
+  );
+
+type Install = {
+  id: string;
+  name: string;
+  path: string;
+  repo: string;
+  remote: string | null;
+  branch: string | null;
+};
+
+type InstallRegistry = {
+  active: string | null;
+  installs: Install[];
+};
+
+type UiState = {
+  showLogs: boolean;
+  preferences: Record<string, unknown>;
+};
+
+type EventClass = 'logs' | 'status' | 'notices' | 'settings';
+type EventFilters = Partial<Record<EventClass, 'off' | 'basic' | 'full'>>;
+
+const LOG_LEVELS = ['off', 'error', 'warn', 'info', 'debug', 'trace'];
+
+function LogLevelSelect() {
+  const [level, setLevel] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<string>('get_log_level')
+      .then(setLevel)
+      .catch(() => setLevel(null));
+  }, []);
+
+  if (!level) {
+    return null;
+  }
+  return (
+    <label style={{ fontSize: '0.8rem' }}>
+      launcher.log level{' '}
+      <select
+        value={level}
+        onChange={(e) =>
+          invoke<string>('set_log_level', { level: e.target.value })
+            .then(setLevel)
+            .catch(() => undefined)
+        }
+      >
+        {LOG_LEVELS.map((option) => (
+          <option key={option} value={option}>
+            {option}
+          </option>
+        ))}
+      </select>
+    </label>
+  );
+}
+
+const formatDuration = (seconds: number) => {
+  const hours = Math.floor(seconds / 3600);
+  const minutes = Math.floor((seconds % 3600) / 60);
+  return hours > 0 ? `${hours}h ${minutes}m` : `${minutes}m`;
+};
+
+const toLauncherError = (err: unknown): LauncherError => {
+  if (err && typeof err === 'object' && 'kind' in err && 'message' in err) {
+    return err as LauncherError;
+  }
+  return {
+    kind: 'failed',
+    message: err instanceof Error ? err.message : String(err),
+    details: null,
+    remediation: null,
+    retryable: false,
+  };
+};
+
+const errorMessage = (err: unknown) => {
+  const { message, details } = toLauncherError(err);
+  return details ? `${message} Details: ${details}` : message;
+};
+
 function App() {
   const [ready, setReady] = useState(false);
   const [url, setUrl] = useState('');
-  const [logs, setLogs] = useState<string[]>([]);
+  const [logs, setLogs] = useState<LogEntry[]>([]);
   const [showLogs, setShowLogs] = useState(false);
-  const [step, setStep] = useState<Step>('updatePrompt');
+  const uiStateLoaded = useRef(false);
+  const [step, setStep] = useState<Step>('onboarding');
   const [updateResult, setUpdateResult] = useState<UpdateResponse | null>(null);
   const [updateSkipped, setUpdateSkipped] = useState(false);
   const [updateErrorMessage, setUpdateErrorMessage] = useState<string | null>(null);
   const [characterResult, setCharacterResult] = useState<CharacterResponse | null>(null);
   const [characterSkipped, setCharacterSkipped] = useState(false);
   const [error, setError] = useState<string | null>(null);
-  const [serverError, setServerError] = useState<string | null>(null);
+  const [serverError, setServerError] = useState<LauncherError | null>(null);
   const [navigationError, setNavigationError] = useState<string | null>(null);
   const [frameReloadToken, setFrameReloadToken] = useState(0);
   const [isProcessing, setIsProcessing] = useState(false);
   const [serverRequested, setServerRequested] = useState(false);
   const [serverForce, setServerForce] = useState(false);
+  const [chatReport, setChatReport] = useState<ChatReport | null>(null);
+  const [chatMessage, setChatMessage] = useState<string | null>(null);
+  const [tunnel, setTunnel] = useState<TunnelInfo | null>(null);
+  const [lanInfo, setLanInfo] = useState<LanAccessInfo | null>(null);
+  const [lanMessage, setLanMessage] = useState<string | null>(null);
+  const [reproMessage, setReproMessage] = useState<string | null>(null);
+  const [autostart, setAutostart] = useState<AutostartStatus | null>(null);
+  const [autostartMessage, setAutostartMessage] = useState<string | null>(null);
+  const [display, setDisplay] = useState<DisplayCapabilities | null>(null);
+  const [accessMessage, setAccessMessage] = useState<string | null>(null);
+  const [repoMessage, setRepoMessage] = useState<string | null>(null);
+  const [configDrift, setConfigDrift] = useState<ConfigDrift | null>(null);
+  const [playtime, setPlaytime] = useState<PlaytimeStats | null>(null);
+  const [notifications, setNotifications] = useState<NotificationToggles | null>(null);
+  const [instances, setInstances] = useState<RunningInstance[] | null>(null);
+  const [installs, setInstalls] = useState<InstallRegistry | null>(null);
+  const [installMessage, setInstallMessage] = useState<string | null>(null);
+  const [instanceMessage, setInstanceMessage] = useState<string | null>(null);
+  const [updateAvailable, setUpdateAvailable] = useState<UpdateCheck | null>(null);
+  const [updatePolicy, setUpdatePolicy] = useState<UpdatePolicy | null>(null);
+  const [updatePolicyMessage, setUpdatePolicyMessage] = useState<string | null>(null);
+  const [currentOperation, setCurrentOperation] = useState<CurrentOperation | null>(null);
+  const [dryRun, setDryRun] = useState(false);
+  const [lastDryRun, setLastDryRun] = useState<DryRun | null>(null);
+  const [serverPrompt, setServerPrompt] = useState<Prompt | null>(null);
+  const answeredPrompts = useRef(new Set<PromptCode>());
 
   const serverErrorInfo = useMemo(() => {
     if (!serverError) {
       return null;
     }
-    const marker = 'NPM_INSTALL_FAILED::';
-    const markerIndex = serverError.indexOf(marker);
-    if (markerIndex !== -1) {
-      const detail = serverError.slice(markerIndex + marker.length).trim();
-      return {
-        type: 'npm' as const,
-        message: detail || 'npm install failed. Check logs for details.',
-      };
-    }
-    return { type: 'other' as const, message: serverError };
+    return {
+      type: serverError.kind === 'npmInstallFailed' ? ('npm' as const) : ('other' as const),
+      message: errorMessage(serverError),
+      remediation: serverError.remediation,
+    };
   }, [serverError]);
 
   const goToLaunching = useCallback((force: boolean) => {
     setServerError(null);
+    setServerPrompt(null);
     setNavigationError(null);
     setServerForce(force);
     setServerRequested(false);
@@ -80,14 +496,58 @@ function App() {
   }, []);
 
   useEffect(() => {
-    const unlistenReady = listen<string>('server-ready', (e) => {
+    const unlistenReady = appWindow.listen<string>('server-ready', (e) => {
       setUrl(e.payload);
       setReady(true);
       setNavigationError(null);
     });
-    const unlistenLog = listen<string>('log', (e) => {
-      setLogs((prev) => [...prev, e.payload]);
+    const unlistenLog = appWindow.listen<string>('log', (e) => {
+      setLogs((prev) => [...prev, { text: e.payload }]);
+    });
+    const unlistenStyled = appWindow.listen<LogEntry>('log-styled', (e) => {
+      setLogs((prev) => {
+        const last = prev[prev.length - 1];
+        if (!last || last.text !== e.payload.text) {
+          return prev;
+        }
+        return [...prev.slice(0, -1), e.payload];
+      });
+    });
+    const unlistenTunnel = appWindow.listen<TunnelInfo>('tunnel-url', (e) => {
+      setTunnel(e.payload);
+    });
+    const unlistenPrompt = appWindow.listen<Prompt>('prompt', (e) => {
+      setServerPrompt(e.payload);
+    });
+    const unlistenDrift = appWindow.listen<ConfigDrift>('config-drift', (e) => {
+      setConfigDrift(e.payload);
+    });
+    const unlistenUpdates = appWindow.listen<UpdateCheck>('update-available', (e) => {
+      setUpdateAvailable(e.payload);
     });
+    const unlistenSettings = appWindow.listen<SettingsChanged>('settings-changed', (e) => {
+      if (e.payload.key === 'notifications' && e.payload.origin !== 'ui') {
+        void invoke<NotificationToggles>('notification_settings').then((toggles) =>
+          setNotifications((prev) => (prev ? toggles : prev))
+        );
+      }
+    });
+    const unlistenChats = appWindow.listen<ChatReport>('chat-recovery', (e) => {
+      setChatReport(e.payload);
+      setChatMessage(null);
+    });
+    const unlistenOperation = appWindow.listen<CurrentOperation | null>('operation-changed', (e) => {
+      setCurrentOperation(e.payload);
+    });
+    void invoke<CurrentOperation | null>('get_current_operation')
+      .then(setCurrentOperation)
+      .catch(() => undefined);
+    const unlistenDryRun = appWindow.listen<DryRun>('dry-run', (e) => {
+      setLastDryRun(e.payload);
+    });
+    void invoke<{ dryRun: boolean }>('launch_options')
+      .then((options) => setDryRun(options.dryRun))
+      .catch(() => undefined);
 
     const handler = (e: KeyboardEvent) => {
       if (e.ctrlKey && e.key.toLowerCase() === 'r') {
@@ -106,10 +566,52 @@ function App() {
     return () => {
       unlistenReady.then((f) => f());
       unlistenLog.then((f) => f());
+      unlistenStyled.then((f) => f());
+      unlistenChats.then((f) => f());
+      unlistenTunnel.then((f) => f());
+      unlistenPrompt.then((f) => f());
+      unlistenDrift.then((f) => f());
+      unlistenUpdates.then((f) => f());
+      unlistenSettings.then((f) => f());
+      unlistenOperation.then((f) => f());
+      unlistenDryRun.then((f) => f());
       window.removeEventListener('keydown', handler);
     };
   }, []);
 
+  useEffect(() => {
+    invoke<UiState>('load_ui_state')
+      .then((ui) => setShowLogs(ui.showLogs))
+      .catch(() => undefined)
+      .finally(() => {
+        uiStateLoaded.current = true;
+      });
+  }, []);
+
+  useEffect(() => {
+    if (uiStateLoaded.current) {
+      void invoke('save_ui_state', { showLogs }).catch(() => undefined);
+    }
+  }, [showLogs]);
+
+  useEffect(() => {
+    const update = () => {
+      const visible = document.visibilityState === 'visible';
+      const filters: EventFilters = visible
+        ? {
+            logs: showLogs ? 'full' : 'basic',
+            status: 'full',
+            notices: 'full',
+            settings: 'full',
+          }
+        : { logs: 'basic', status: 'basic', notices: 'basic', settings: 'basic' };
+      void invoke('subscribe', { filters }).catch(() => undefined);
+    };
+    update();
+    document.addEventListener('visibilitychange', update);
+    return () => document.removeEventListener('visibilitychange', update);
+  }, [showLogs]);
+
   const handleOpenInBrowser = useCallback(() => {
     if (!url) {
       return;
@@ -134,7 +636,7 @@ function App() {
       setServerError(null);
       void invoke('start_server', { force: serverForce })
         .catch((err) => {
-          setServerError(err instanceof Error ? err.message : String(err));
+          setServerError(toLauncherError(err));
         })
         .finally(() => {
           setServerForce(false);
@@ -162,7 +664,7 @@ function App() {
         setStep('characterPrompt');
       }
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       setUpdateErrorMessage(message);
       setStep('characterPrompt');
     } finally {
@@ -170,6 +672,47 @@ function App() {
     }
   }, []);
 
+  const runOnboarding = useCallback(async () => {
+    setError(null);
+    setIsProcessing(true);
+    try {
+      const onboarding = await invoke<OnboardingState>('get_onboarding_state');
+      if (onboarding.complete) {
+        const options = await invoke<{ startMinimized: boolean }>('launch_options');
+        if (options.startMinimized) {
+          goToLaunching(false);
+          return;
+        }
+        const policy = await invoke<UpdatePolicy>('get_update_policy').catch(() => null);
+        setUpdatePolicy(policy);
+        if (policy?.mode === 'auto') {
+          void runVendorUpdate(false);
+        } else if (policy?.mode === 'manual' || policy?.mode === 'frozen') {
+          setUpdateSkipped(true);
+          setStep('characterPrompt');
+        } else {
+          setStep('updatePrompt');
+        }
+        return;
+      }
+      if (!onboarding.depsVerified) {
+        await invoke('check_dependencies');
+      }
+      if (!onboarding.vendorBootstrapped) {
+        await invoke('bootstrap_vendor');
+      }
+      goToLaunching(false);
+    } catch (err) {
+      setError(errorMessage(err));
+    } finally {
+      setIsProcessing(false);
+    }
+  }, [goToLaunching, runVendorUpdate]);
+
+  useEffect(() => {
+    void runOnboarding();
+  }, [runOnboarding]);
+
   const handleRunVendorUpdate = () => {
     void runVendorUpdate(false);
   };
@@ -195,7 +738,7 @@ function App() {
       await invoke('finalize_stash', { revert });
       setStep('characterPrompt');
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
     } finally {
       setIsProcessing(false);
     }
@@ -207,13 +750,16 @@ function App() {
     try {
       const result = await invoke<CharacterResponse>('run_character_sync');
       setCharacterResult(result);
-      if (result.success) {
+      if (result.offline) {
+        setCharacterSkipped(true);
+        goToLaunching(false);
+      } else if (result.success) {
         goToLaunching(false);
       } else {
         setStep('characterFailurePrompt');
       }
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       setCharacterResult({
         success: false,
         message,
@@ -247,10 +793,278 @@ function App() {
 
   const retryServer = (force = false) => {
     setServerError(null);
+    setServerPrompt(null);
     setServerForce(force);
     setServerRequested(false);
   };
 
+  const repairNodeModules = async () => {
+    setError(null);
+    setServerError(null);
+    setIsProcessing(true);
+    try {
+      await invoke('repair_node_modules', { cache: 'verify' });
+      if (!dryRun) {
+        retryServer(false);
+      }
+    } catch (err) {
+      setServerError(toLauncherError(err));
+    } finally {
+      setIsProcessing(false);
+    }
+  };
+
+  const resolveReadOnlyPath = async (action: 'fixPermissions' | 'relocateVendor') => {
+    setAccessMessage(null);
+    setIsProcessing(true);
+    try {
+      setAccessMessage(
+        action === 'fixPermissions'
+          ? await invoke<string>('fix_path_permissions', { path: activePrompt?.params.path ?? '' })
+          : await invoke<string>('relocate_vendor')
+      );
+      if (step === 'launching') {
+        retryServer(false);
+      } else {
+        void runVendorUpdate(false);
+      }
+    } catch (err) {
+      setAccessMessage(errorMessage(err));
+    } finally {
+      setIsProcessing(false);
+    }
+  };
+
+  const repairRepo = async () => {
+    setRepoMessage(null);
+    setIsProcessing(true);
+    try {
+      const report = await invoke<{ message: string }>('repair_repo');
+      setRepoMessage(report.message);
+      void runVendorUpdate(false);
+    } catch (err) {
+      setRepoMessage(errorMessage(err));
+    } finally {
+      setIsProcessing(false);
+    }
+  };
+
+  const restoreChat = async (issue: ChatIssue) => {
+    if (!issue.backup) {
+      return;
+    }
+    setIsProcessing(true);
+    try {
+      const message = await invoke<string>('restore_chat_backup', {
+        chat: issue.path,
+        backup: issue.backup,
+      });
+      setChatMessage(message);
+      setChatReport((report) =>
+        report
+          ? { ...report, issues: report.issues.filter((entry) => entry.path !== issue.path) }
+          : report
+      );
+    } catch (err) {
+      setChatMessage(errorMessage(err));
+    } finally {
+      setIsProcessing(false);
+    }
+  };
+
+  const captureReproState = async () => {
+    setReproMessage(null);
+    try {
+      const capture = await invoke<{ path: string }>('capture_repro_state', {
+        uiState: {
+          step,
+          ready,
+          updateStatus: updateResult?.status ?? null,
+          serverError,
+          prompt: serverPrompt,
+        },
+      });
+      setReproMessage(`Saved a debug snapshot to ${capture.path}. Attach it to your bug report.`);
+    } catch (err) {
+      setReproMessage(errorMessage(err));
+    }
+  };
+
+  useEffect(() => {
+    invoke<AutostartStatus>('autostart_status')
+      .then(setAutostart)
+      .catch(() => setAutostart(null));
+  }, []);
+
+  useEffect(() => {
+    invoke<DisplayCapabilities>('display_capabilities')
+      .then(setDisplay)
+      .catch(() => setDisplay(null));
+  }, []);
+
+  const installDesktopEntry = async () => {
+    setAutostartMessage(null);
+    try {
+      const path = await invoke<string>('install_desktop_entry');
+      setDisplay((current) => (current ? { ...current, desktopEntry: path } : current));
+      setAutostartMessage(`Added to the application menu (${path}).`);
+    } catch (err) {
+      setAutostartMessage(errorMessage(err));
+    }
+  };
+
+  const toggleAutostart = async () => {
+    setAutostartMessage(null);
+    try {
+      setAutostart(
+        await invoke<AutostartStatus>(autostart?.enabled ? 'disable_autostart' : 'enable_autostart')
+      );
+    } catch (err) {
+      setAutostartMessage(errorMessage(err));
+    }
+  };
+
+  useEffect(() => {
+    invoke<InstallRegistry>('list_installs')
+      .then(setInstalls)
+      .catch(() => setInstalls(null));
+  }, []);
+
+  const selectInstall = async (id: string | null) => {
+    setInstallMessage(null);
+    try {
+      setInstalls(await invoke<InstallRegistry>('set_active_install', { id }));
+    } catch (err) {
+      setInstallMessage(errorMessage(err));
+    }
+  };
+
+  const changeUpdatePolicy = async (mode: UpdatePolicyMode) => {
+    setUpdatePolicyMessage(null);
+    try {
+      setUpdatePolicy(await invoke<UpdatePolicy>('set_update_policy', { mode }));
+    } catch (err) {
+      setUpdatePolicyMessage(errorMessage(err));
+    }
+  };
+
+  const toggleNotification = (event: NotifyEvent, enabled: boolean) => {
+    invoke<NotificationToggles>('set_notification_enabled', { event, enabled })
+      .then(setNotifications)
+      .catch(() => undefined);
+  };
+
+  const openInstanceWindow = async (profile: string) => {
+    setInstanceMessage(null);
+    try {
+      await invoke('open_instance_window', { profile });
+    } catch (err) {
+      setInstanceMessage(errorMessage(err));
+    }
+  };
+
+  const loadLanInfo = async () => {
+    setLanMessage(null);
+    try {
+      setLanInfo(await invoke<LanAccessInfo>('lan_access_info'));
+    } catch (err) {
+      setLanMessage(errorMessage(err));
+    }
+  };
+
+  const allowLanFirewall = async () => {
+    if (!lanInfo) {
+      return;
+    }
+    setIsProcessing(true);
+    try {
+      setLanMessage(await invoke<string>('allow_lan_firewall', { port: lanInfo.port }));
+      setLanInfo({ ...lanInfo, firewallRule: true });
+    } catch (err) {
+      setLanMessage(errorMessage(err));
+    } finally {
+      setIsProcessing(false);
+    }
+  };
+
+  const handlePromptAction = (action: PromptAction) => {
+    switch (action) {
+      case 'retryWithStash':
+        handleRetryVendorUpdate();
+        break;
+      case 'manageStash':
+        setStep('stashPrompt');
+        break;
+      case 'skipUpdate':
+        handleSkipUpdate();
+        break;
+      case 'restoreStash':
+      case 'discardStash':
+        void handleFinalizeStash(action === 'restoreStash');
+        break;
+      case 'retryInstall':
+      case 'retryStart':
+        retryServer(false);
+        break;
+      case 'repairNodeModules':
+        void repairNodeModules();
+        break;
+      case 'forceStart':
+        retryServer(true);
+        break;
+      case 'fixPermissions':
+      case 'relocateVendor':
+        void resolveReadOnlyPath(action);
+        break;
+      case 'repairRepo':
+        void repairRepo();
+        break;
+      case 'exit':
+        handleExit();
+        break;
+    }
+  };
+
+  const activePrompt =
+    step === 'updateRetryPrompt' || step === 'stashPrompt'
+      ? updateResult?.prompt ?? null
+      : step === 'launching'
+        ? serverPrompt
+        : null;
+
+  useEffect(() => {
+    if (!activePrompt?.answer || answeredPrompts.current.has(activePrompt.code)) {
+      return;
+    }
+    answeredPrompts.current.add(activePrompt.code);
+    handlePromptAction(activePrompt.answer);
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [activePrompt]);
+
+  const promptText = (prompt: Prompt) => PROMPT_TEXT[prompt.code](prompt.params);
+
+  const renderPromptActions = (prompt: Prompt) => (
+    <>
+      <div style={buttonRowStyle}>
+        {prompt.actions.map((action) => (
+          <button
+            key={action}
+            onClick={() => handlePromptAction(action)}
+            disabled={isProcessing && action !== 'exit'}
+          >
+            {ACTION_LABELS[action]}
+          </button>
+        ))}
+      </div>
+      {prompt.code === 'readOnlyPath' && accessMessage && (
+        <p style={{ fontSize: '0.85rem' }}>{accessMessage}</p>
+      )}
+      {prompt.code === 'repoBroken' && repoMessage && (
+        <p style={{ fontSize: '0.85rem' }}>{repoMessage}</p>
+      )}
+    </>
+  );
+
   const buttonRowStyle = useMemo(
     () => ({ display: 'flex', gap: '0.75rem', marginTop: '1rem', flexWrap: 'wrap' as const }),
     []
@@ -294,10 +1108,16 @@ function App() {
       updateProgress = 100;
       updateMessage = UPDATE_SKIP_MESSAGE;
     } else if (updateResult) {
-      updateMessage = updateResult.message;
+      updateMessage =
+        updateResult.prompt && updateResult.prompt.code !== 'restoreStash'
+          ? promptText(updateResult.prompt)
+          : updateResult.message;
       if (updateResult.status === 'success' || updateResult.status === 'upToDate') {
         updateStatus = 'success';
         updateProgress = 100;
+      } else if (updateResult.status === 'offline' || updateResult.status === 'dryRun') {
+        updateStatus = 'skipped';
+        updateProgress = 100;
       } else if (updateResult.status === 'needRetry' || updateResult.status === 'failed') {
         updateStatus = 'error';
         updateProgress = 100;
@@ -306,7 +1126,7 @@ function App() {
       updateStatus = 'error';
       updateProgress = 100;
       updateMessage = updateErrorMessage;
-    } else if (step !== 'updatePrompt' && step !== 'updateRunning') {
+    } else if (step !== 'onboarding' && step !== 'updatePrompt' && step !== 'updateRunning') {
       updateStatus = 'success';
       updateProgress = 100;
     }
@@ -346,7 +1166,7 @@ function App() {
       if (serverError) {
         serverStatus = 'error';
         serverProgress = 100;
-        serverMessage = serverErrorInfo?.message ?? serverError;
+        serverMessage = serverErrorInfo?.message ?? errorMessage(serverError);
       } else if (serverRequested) {
         serverStatus = 'running';
         serverProgress = 60;
@@ -447,7 +1267,7 @@ function App() {
         )}
         {logContents && (
           <div>
-            <p>WTUpdate.log contents:</p>
+            <p>Update log contents:</p>
             <pre
               style={{
                 backgroundColor: '#111',
@@ -482,9 +1302,41 @@ function App() {
 
   const renderStepContent = () => {
     switch (step) {
+      case 'onboarding':
+        if (isProcessing || !error) {
+          return <p>Preparing WeylandTavern...</p>;
+        }
+        return (
+          <>
+            <p>First-time setup did not finish.</p>
+            <div style={buttonRowStyle}>
+              <button onClick={() => void runOnboarding()}>Retry setup</button>
+              <button onClick={handleExit}>Exit</button>
+            </div>
+          </>
+        );
       case 'updatePrompt':
         return (
           <>
+            {installs && installs.installs.length > 0 && (
+              <label style={{ fontSize: '0.85rem' }}>
+                Install{' '}
+                <select
+                  value={installs.active ?? ''}
+                  disabled={isProcessing}
+                  onChange={(e) => void selectInstall(e.target.value || null)}
+                >
+                  <option value="">SILLYTAVERN_DIR (.env)</option>
+                  {installs.installs.map((install) => (
+                    <option key={install.id} value={install.id}>
+                      {install.name}
+                    </option>
+                  ))}
+                </select>
+                {installMessage && <span> {installMessage}</span>}
+              </label>
+            )}
+            {renderUpdatePolicy()}
             <p>Run Vendor Update?</p>
             <div style={buttonRowStyle}>
               <button onClick={handleRunVendorUpdate} disabled={isProcessing}>
@@ -502,13 +1354,22 @@ function App() {
       case 'updateRunning':
         return <p>Updating WeylandTavern...</p>;
       case 'updateRetryPrompt': {
+        if (updateResult?.prompt) {
+          return (
+            <>
+              <p style={{ color: '#ff8a80' }}>{promptText(updateResult.prompt)}</p>
+              <p>Review the update log below and choose how to proceed.</p>
+              {renderPromptActions(updateResult.prompt)}
+            </>
+          );
+        }
         const retryMessage =
           updateResult?.message ?? 'There was an error updating WeylandTavern.';
         const canRetry = updateResult?.status === 'needRetry';
         return (
           <>
             <p style={{ color: '#ff8a80' }}>{retryMessage}</p>
-            <p>Review WTUpdate.log below and choose how to proceed.</p>
+            <p>Review the update log below and choose how to proceed.</p>
             <div style={buttonRowStyle}>
               {canRetry && (
                 <button onClick={handleRetryVendorUpdate} disabled={isProcessing}>
@@ -531,6 +1392,14 @@ function App() {
         );
       }
       case 'stashPrompt':
+        if (updateResult?.prompt?.code === 'restoreStash') {
+          return (
+            <>
+              <p>{promptText(updateResult.prompt)}</p>
+              {renderPromptActions(updateResult.prompt)}
+            </>
+          );
+        }
         return (
           <>
             <p>Restore stashed changes?</p>
@@ -547,6 +1416,17 @@ function App() {
       case 'characterPrompt':
         return (
           <>
+            {updatePolicy?.mode === 'manual' && updateSkipped && (
+              <button onClick={handleRunVendorUpdate} disabled={isProcessing}>
+                Update WeylandTavern first
+              </button>
+            )}
+            {updatePolicy?.mode === 'frozen' && updatePolicy.pinnedCommit && (
+              <p style={{ fontSize: '0.85rem' }}>
+                Updates are frozen at {updatePolicy.pinnedCommit.slice(0, 10)}.
+              </p>
+            )}
+            {renderUpdatePolicy()}
             <p>Run Character Updater?</p>
             <div style={buttonRowStyle}>
               <button onClick={handleRunCharacter} disabled={isProcessing}>
@@ -604,14 +1484,24 @@ function App() {
             )}
             {serverErrorInfo && (
               <div style={{ marginTop: '1rem' }}>
-                {serverErrorInfo.type === 'npm' ? (
+                {serverPrompt ? (
+                  <>
+                    <p style={{ color: '#ff8a80' }}>{promptText(serverPrompt)}</p>
+                    {renderPromptActions(serverPrompt)}
+                  </>
+                ) : serverErrorInfo.type === 'npm' ? (
                   <>
                     <p style={{ color: '#ff8a80' }}>
                       npm install failed. Continue launching anyway?
                     </p>
                     <div style={buttonRowStyle}>
-                      <button onClick={() => retryServer(false)}>Retry npm install</button>
-                      <button onClick={() => retryServer(true)}>
+                      <button onClick={() => retryServer(false)} disabled={isProcessing}>
+                        Retry npm install
+                      </button>
+                      <button onClick={() => void repairNodeModules()} disabled={isProcessing}>
+                        Repair node_modules
+                      </button>
+                      <button onClick={() => retryServer(true)} disabled={isProcessing}>
                         Continue without reinstalling
                       </button>
                       <button onClick={handleExit}>Exit</button>
@@ -622,12 +1512,17 @@ function App() {
                     <p style={{ color: '#ff8a80' }}>
                       Failed to start the server: {serverErrorInfo.message}
                     </p>
+                    {serverErrorInfo.remediation && <p>{serverErrorInfo.remediation}</p>}
                     <div style={buttonRowStyle}>
                       <button onClick={() => retryServer(false)}>Retry start</button>
                       <button onClick={handleExit}>Exit</button>
                     </div>
                   </>
                 )}
+                <div style={buttonRowStyle}>
+                  <button onClick={() => void captureReproState()}>Save debug snapshot</button>
+                </div>
+                {reproMessage && <p style={{ fontSize: '0.85rem' }}>{reproMessage}</p>}
               </div>
             )}
           </>
@@ -637,6 +1532,76 @@ function App() {
     }
   };
 
+  const renderUpdatePolicy = () => (
+    <label style={{ fontSize: '0.85rem' }}>
+      Updates{' '}
+      <select
+        value={updatePolicy?.mode ?? 'prompt'}
+        disabled={isProcessing}
+        onChange={(e) => void changeUpdatePolicy(e.target.value as UpdatePolicyMode)}
+      >
+        {(Object.keys(UPDATE_POLICY_LABELS) as UpdatePolicyMode[]).map((mode) => (
+          <option key={mode} value={mode}>
+            {UPDATE_POLICY_LABELS[mode]}
+          </option>
+        ))}
+      </select>
+      {updatePolicyMessage && <span> {updatePolicyMessage}</span>}
+    </label>
+  );
+
+  const renderChatRecovery = () => {
+    if (!chatReport || (chatReport.issues.length === 0 && !chatMessage)) {
+      return null;
+    }
+    return (
+      <div
+        style={{
+          position: 'fixed',
+          bottom: '1rem',
+          right: '1rem',
+          maxWidth: '28rem',
+          maxHeight: '60vh',
+          overflow: 'auto',
+          backgroundColor: 'rgba(0,0,0,0.85)',
+          border: '1px solid rgba(255,255,255,0.15)',
+          borderRadius: '0.75rem',
+          padding: '1rem',
+          textAlign: 'left',
+          zIndex: 5,
+        }}
+      >
+        <strong>Chat check after an unexpected shutdown</strong>
+        {chatReport.issues.map((issue) => (
+          <div key={issue.path} style={{ marginTop: '0.75rem' }}>
+            <code>{issue.path}</code>
+            <p
+              style={{
+                margin: '0.25rem 0',
+                color: issue.kind === 'repaired' ? 'inherit' : '#ff8a80',
+              }}
+            >
+              {issue.detail}
+            </p>
+            {issue.backup ? (
+              <button onClick={() => void restoreChat(issue)} disabled={isProcessing}>
+                Restore latest backup
+              </button>
+            ) : (
+              issue.kind === 'corrupted' && (
+                <span style={{ fontSize: '0.85rem', opacity: 0.75 }}>No backup found.</span>
+              )
+            )}
+          </div>
+        ))}
+        {chatMessage && <p style={{ fontSize: '0.85rem' }}>{chatMessage}</p>}
+        <div style={buttonRowStyle}>
+          <button onClick={() => setChatReport(null)}>Dismiss</button>
+        </div>
+      </div>
+    );
+  };
+
   if (ready) {
     return (
       <div
@@ -702,13 +1667,157 @@ function App() {
             <span style={{ fontSize: '0.85rem', opacity: 0.85 }}>
               Server ready at <code>{url}</code>
             </span>
+            {tunnel && (
+              <details style={{ fontSize: '0.85rem' }}>
+                <summary>
+                  Remote access: <code>{tunnel.url}</code>
+                </summary>
+                {tunnel.qrSvg && (
+                  <img
+                    src={`data:image/svg+xml;utf8,${encodeURIComponent(tunnel.qrSvg)}`}
+                    alt={`QR code for ${tunnel.url}`}
+                    style={{ marginTop: '0.5rem', width: '10rem', height: '10rem' }}
+                  />
+                )}
+              </details>
+            )}
+            {lanInfo && (
+              <div style={{ fontSize: '0.85rem', display: 'flex', flexDirection: 'column', gap: '0.35rem', alignItems: 'center' }}>
+                {lanInfo.hint && <span>{lanInfo.hint}</span>}
+                {lanInfo.listeningOnLan &&
+                  lanInfo.addresses.map((entry) => (
+                    <details key={`${entry.interface}-${entry.address}`}>
+                      <summary>
+                        {entry.interface}: <code>{entry.url}</code>
+                      </summary>
+                      <img
+                        src={`data:image/svg+xml;utf8,${encodeURIComponent(entry.qrSvg)}`}
+                        alt={`QR code for ${entry.url}`}
+                        style={{ marginTop: '0.5rem', width: '10rem', height: '10rem' }}
+                      />
+                    </details>
+                  ))}
+                {lanInfo.listeningOnLan && lanInfo.firewallRule === false && (
+                  <button onClick={() => void allowLanFirewall()} disabled={isProcessing}>
+                    Allow port {lanInfo.port} through Windows Firewall
+                  </button>
+                )}
+              </div>
+            )}
+            {lanMessage && <span style={{ fontSize: '0.85rem' }}>{lanMessage}</span>}
+            {currentOperation && (
+              <span style={{ fontSize: '0.85rem' }}>Busy {currentOperation.label}…</span>
+            )}
+            {dryRun && (
+              <span style={{ fontSize: '0.85rem' }}>
+                Dry run mode: updates, stash handling, restores, and node_modules repairs only
+                report what they would do.
+              </span>
+            )}
+            {lastDryRun && (
+              <span style={{ fontSize: '0.85rem' }}>
+                {lastDryRun.summary} ({lastDryRun.commands.length} command(s),{' '}
+                {lastDryRun.files.length} file(s); details in the log)
+              </span>
+            )}
+            {updateAvailable && (
+              <span style={{ fontSize: '0.85rem' }}>
+                {updateAvailable.pending} WeylandTavern update(s) available
+                {updateAvailable.latestSubject ? ` (latest: ${updateAvailable.latestSubject})` : ''}. They
+                {updatePolicy?.mode === 'manual'
+                  ? "can be applied with Update WeylandTavern at the next launch."
+                  : "are applied on the next launch's update step."}{' '}
+                <button onClick={() => setUpdateAvailable(null)}>Dismiss</button>
+              </span>
+            )}
+            {configDrift && (
+              <span style={{ fontSize: '0.85rem', color: configDrift.restartRequired ? '#ffcc80' : 'inherit' }}>
+                {configDrift.message}{' '}
+                <button onClick={() => setConfigDrift(null)}>Dismiss</button>
+              </span>
+            )}
+            {autostartMessage && <span style={{ fontSize: '0.85rem' }}>{autostartMessage}</span>}
             <div style={{ display: 'flex', gap: '0.5rem', flexWrap: 'wrap', justifyContent: 'center' }}>
               <button onClick={handleOpenInBrowser}>Open in browser</button>
+              <button onClick={() => (lanInfo ? setLanInfo(null) : void loadLanInfo())}>
+                {lanInfo ? 'Hide LAN access' : 'LAN access'}
+              </button>
+              {autostart && (
+                <button onClick={() => void toggleAutostart()}>
+                  {autostart.enabled ? 'Disable start on login' : 'Start on login'}
+                </button>
+              )}
+              {display && display.displayServer !== 'native' && !display.desktopEntry && (
+                <button onClick={() => void installDesktopEntry()}>Add to app menu</button>
+              )}
+              <button
+                onClick={() =>
+                  playtime
+                    ? setPlaytime(null)
+                    : void invoke<PlaytimeStats>('playtime_stats').then(setPlaytime)
+                }
+              >
+                {playtime ? 'Hide playtime' : 'Playtime'}
+              </button>
+              <button
+                onClick={() =>
+                  notifications
+                    ? setNotifications(null)
+                    : void invoke<NotificationToggles>('notification_settings').then(setNotifications)
+                }
+              >
+                {notifications ? 'Hide notifications' : 'Notifications'}
+              </button>
+              <button
+                onClick={() =>
+                  instances
+                    ? setInstances(null)
+                    : void invoke<RunningInstance[]>('list_instances').then(setInstances)
+                }
+              >
+                {instances ? 'Hide profiles' : 'Other profiles'}
+              </button>
               <button onClick={() => setShowLogs((value) => !value)}>
                 {showLogs ? 'Hide logs' : 'Show logs'}
               </button>
               <button onClick={() => void appWindow.close()}>Exit</button>
             </div>
+            {playtime && (
+              <span style={{ fontSize: '0.85rem' }}>
+                Today {formatDuration(playtime.today.activeSeconds)} active (
+                {formatDuration(playtime.today.uptimeSeconds)} running), this week{' '}
+                {formatDuration(playtime.thisWeek.activeSeconds)} active (
+                {formatDuration(playtime.thisWeek.uptimeSeconds)} running).
+              </span>
+            )}
+            {notifications && (
+              <div style={{ display: 'flex', gap: '0.75rem', flexWrap: 'wrap', justifyContent: 'center', fontSize: '0.85rem' }}>
+                {(Object.keys(NOTIFY_LABELS) as NotifyEvent[]).map((event) => (
+                  <label key={event}>
+                    <input
+                      type="checkbox"
+                      checked={notifications[event]}
+                      onChange={(e) => toggleNotification(event, e.target.checked)}
+                    />{' '}
+                    {NOTIFY_LABELS[event]}
+                  </label>
+                ))}
+              </div>
+            )}
+            {instances && (
+              <div style={{ display: 'flex', flexDirection: 'column', gap: '0.25rem', fontSize: '0.85rem' }}>
+                {instances.map((instance) => (
+                  <span key={instance.id}>
+                    {instance.name}
+                    {instance.current ? ' (this launcher)' : ''} – {instance.url ?? 'starting…'}{' '}
+                    <button disabled={!instance.url} onClick={() => void openInstanceWindow(instance.id)}>
+                      Open window
+                    </button>
+                  </span>
+                ))}
+                {instanceMessage && <span>{instanceMessage}</span>}
+              </div>
+            )}
             <span style={{ fontSize: '0.75rem', opacity: 0.75 }}>
               Use <kbd>Ctrl</kbd>+<kbd>L</kbd> to toggle logs, <kbd>Ctrl</kbd>+<kbd>R</kbd> to reload, and <kbd>Ctrl</kbd>+<kbd>Q</kbd> to quit.
             </span>
@@ -739,6 +1848,7 @@ function App() {
             </div>
           )}
         </div>
+        {renderChatRecovery()}
         {showLogs && (
           <div
             style={{
@@ -754,7 +1864,8 @@ function App() {
               zIndex: 10,
             }}
           >
-            <pre>{logs.join('\n')}</pre>
+            <LogLevelSelect />
+            <pre>{renderLogs(logs)}</pre>
           </div>
         )}
       </div>
@@ -783,6 +1894,7 @@ function App() {
         Use <kbd>Ctrl</kbd>+<kbd>L</kbd> to toggle logs, <kbd>Ctrl</kbd>+<kbd>R</kbd> to reload, and <kbd>Ctrl</kbd>+<kbd>Q</kbd>{' '}
         to quit.
       </p>
+      {renderChatRecovery()}
       {showLogs && (
         <div
           style={{
@@ -797,7 +1909,8 @@ function App() {
             padding: '1rem',
           }}
         >
-          <pre>{logs.join('\n')}</pre>
+          <LogLevelSelect />
+          <pre>{renderLogs(logs)}</pre>
         </div>
       )}
     </div>
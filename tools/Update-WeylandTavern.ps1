@@ -2,7 +2,9 @@ param(
   # Accepts: origin/<branch>, <branch>, tags/<tag>, or a full/short <SHA>
   [string]$Ref = "origin/nightly",
   # If set, check out the exact remote ref in detached HEAD (no local branch)
-  [switch]$PinExact
+  [switch]$PinExact,
+  # History depth for submodule init and fetches; 0 (default) fetches the full history
+  [int]$Depth = 0
 )
 
 # Fail fast on errors inside PowerShell
@@ -31,6 +33,10 @@ function Invoke-Git {
   return $out
 }
 
+# Depth arguments shared by submodule init and fetches (empty for full history)
+$depthArgs = @()
+if ($Depth -gt 0) { $depthArgs = @("--depth","$Depth") }
+
 # 1) Move to the repository root (script is assumed to live within the repo)
 $scriptRoot = Split-Path -Parent $MyInvocation.MyCommand.Path
 $repoRoot   = Resolve-Path (Join-Path $scriptRoot "..")
@@ -41,7 +47,7 @@ Write-Host "Working directory set to $repoRoot"
 #    Note: forward slashes are used to keep paths Git-friendly
 $sub = "vendor/WeylandTavern"
 Write-Host "Ensuring submodule initialized..."
-Invoke-Git @("submodule","update","--init","--",$sub)
+Invoke-Git (@("submodule","update","--init") + $depthArgs + @("--",$sub))
 
 # 3) Determine type of ref and fetch minimal data required
 Push-Location $sub
@@ -53,7 +59,7 @@ $refIsOrig = $Ref -like 'origin/*'
 $branchName = $null
 
 if ($refIsSHA) {
-  Invoke-Git -Quiet @("fetch","origin",$Ref,"--depth","1")
+  Invoke-Git -Quiet (@("fetch","origin",$Ref) + $depthArgs)
   Write-Host "Fetched commit $Ref ..."
 } elseif ($refIsTag) {
   $tagName = $Ref -replace '^tags/',''
@@ -66,7 +72,7 @@ if ($refIsSHA) {
 } else {
     $branchName = $Ref
 }
-  Invoke-Git -Quiet @("fetch","origin",$branchName,"--depth","1")
+  Invoke-Git -Quiet (@("fetch","origin",$branchName) + $depthArgs)
   Write-Host "Fetched remote branch 'origin/$branchName' ..."
 }
 